@@ -74,3 +74,23 @@ main-window--version-outdated-tooltip = Die Version ist zu veraltet und kann nic
 preferences = Einstellungen
 general = Allgemein
 enhancements = Verbesserungen
+
+
+duration-days = {$value} T
+duration-hours = {$value} Std.
+duration-minutes = {$value} Min.
+duration-seconds = {$value} Sek.
+
+date-just-now = Gerade eben
+date-minutes-ago = { $value ->
+    [one] vor {$value} Minute
+    *[other] vor {$value} Minuten
+}
+date-hours-ago = { $value ->
+    [one] vor {$value} Stunde
+    *[other] vor {$value} Stunden
+}
+date-days-ago = { $value ->
+    [one] vor {$value} Tag
+    *[other] vor {$value} Tagen
+}
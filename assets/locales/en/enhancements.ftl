@@ -47,6 +47,7 @@ fps-unlocker = FPS Unlocker
 fps-unlocker-description = Remove frames rendering limitation by modifying the game's memory. Can be detected by the anti-cheat
 
 enabled = Enabled
+disabled = Disabled
 
 fps-unlocker-interval = Overwrite interval
 fps-unlocker-interval-description = Delay in milliseconds between overwriting the FPS limit value. Periodic overwrites are necessary to prevent it from resetting
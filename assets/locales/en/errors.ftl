@@ -11,20 +11,44 @@ wine-run-error = Failed to run {$executable} executable using wine
 game-launching-failed = Failed to launch game
 failed-get-selected-wine = Failed to get selected wine version
 downloading-failed = Downloading failed
+not-enough-free-space = Not enough free space in {$path}: {$required} required, {$available} available
 unpacking-failed = Unpacking failed
 
 kill-game-process-failed = Failed to kill the game's process
 
 game-file-repairing-error = Failed to repair game file
 integrity-files-getting-error = Failed to get integrity files
-
-background-downloading-failed = Failed to download background picture
+folder-migration-failed = Failed to migrate folder
+folder-migration-rollback-failed = Failed to roll back folder migration
+launcher-restart-failed = Failed to restart the launcher
+installation-detection-failed = Failed to detect game installation
+installation-import-failed = Failed to import game installation
+packages-install-failed = Failed to install missing packages
+cleanup-failed = Failed to remove launcher files
+cleanup-search-failed = Failed to find launcher files
+cleanup-settings-save-failed = Failed to save cleanup settings
+files-not-moved = {$count ->
+    [one] {$count} file wasn't moved and was kept in its original location
+    *[other] {$count} files weren't moved and were kept in their original location
+}
+
+background-downloading-failed = Failed to update background picture
+background-settings-save-failed = Failed to save background settings
 components-index-sync-failed = Failed to sync components index
 components-index-verify-failed = Failed to verify components index
 config-update-error = Failed to save config
+config-backup-failed = Failed to backup config
+config-restore-failed = Failed to restore config
+config-check-failed = Found problems in the launcher config
+config-fix-failed = Failed to fix config problem
+installation-profile-save-failed = Failed to save installation profile
+installation-profile-remove-failed = Failed to remove installation profile
+installation-profile-switch-failed = Failed to switch installation profile
 wine-prefix-update-failed = Failed to update wine prefix
 dxvk-install-failed = Failed to install DXVK
 voice-package-deletion-error = Failed to delete voice package
+voice-operation-planning-failed = Failed to add voiceover to the queue
+voice-operation-failed = Failed to apply {$voice} voiceover changes
 
 game-diff-finding-error = Failed to find game diff
 patch-info-fetching-error = Failed to fetch patch info
@@ -59,6 +83,11 @@ game-session-update-failed = Failed to update game session
 game-session-remove-failed = Failed to remove game session
 game-session-set-current-failed = Failed to set current game session
 game-session-apply-failed = Failed to apply game session
+session-profile-save-failed = Failed to save session launch profile
+session-wine-not-downloaded = Wine {$wine} from the session launch profile is not downloaded
+session-dxvk-not-downloaded = DXVK {$dxvk} from the session launch profile is not downloaded
+desktop-shortcuts-update-failed = Failed to update desktop shortcuts
+steam-shortcut-add-failed = Failed to add Steam shortcut
 
 # Enhancements
 
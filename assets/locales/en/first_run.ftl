@@ -33,6 +33,10 @@ tos-dialog-message =
 dependencies = Dependencies
 missing-dependencies-title = You're missing some dependencies!
 missing-dependencies-message = You must install some packages to your system before continue installation process
+install-missing-packages = Install missing packages
+copy-command = Copy command
+command-copied = Command copied to clipboard
+command-copied-description = Run it in the terminal and press "Check" when packages are installed
 
 
 default-paths = Default paths
@@ -50,6 +54,18 @@ patch-folder = Patch folder
 temp-folder = Temp folder
 
 migrate = Migrate
+back = Back
+migration-plan = Migration plan
+migration-plan-description = Check where every folder will be moved. Migration can't be started while some folders have conflicts
+migration-rename = Renamed on the same disk
+migration-copy = Copied to another disk
+migration-path-only = Files are kept in place, only the path is changed
+migration-skip = Not moved
+migration-required-space = requires {$size} of free space
+migration-conflict-target-not-empty = Target folder is not empty
+migration-conflict-target-inside-source = Target folder is inside the source one
+migration-conflict-same-target = Another folder is moved to the same target
+migration-conflict-not-enough-space = Not enough free space on the target disk: {$required} required, {$available} available
 
 
 select-voice-packages = Select voice packages
@@ -5,3 +5,26 @@ active-session-description = Currently selected game session. Updates after each
 
 update-session = Update session using current wine prefix registry values
 delete-session = Delete session
+
+session-profile = Session launch profile
+session-profile-description = Settings which override the global ones when the game is launched with the current session. Leave fields empty to use global values
+session-profile-environment = Environment variables
+session-profile-environment-description = One KEY=value variable per line
+session-profile-gamescope = Own gamescope settings
+session-profile-gamescope-description = Save current gamescope settings into the profile and use them for this session
+session-profile-background = Background picture
+
+pre-launch-hook = Command before launch
+post-launch-hook = Command after exit
+
+session-shortcuts = Shortcuts
+desktop-shortcuts = Application menu shortcuts
+desktop-shortcuts-description = Create desktop entries which run the game with every game session
+steam-shortcut = Steam shortcut
+steam-shortcut-description = Add current game session as a non-Steam game. Restart Steam to see it
+steam-shortcut-added = Steam shortcut added
+steam-shortcut-steam-running = Steam is running and would overwrite the shortcut on exit. Close Steam and try again
+
+shortcut-play-session = Play ({$session})
+shortcut-play-session-description = Run the game with {$session} session
+shortcut-open-launcher = Open launcher
@@ -2,10 +2,31 @@ appearance = Appearance
 modern = Modern
 classic = Classic
 update-background = Update background picture
-update-background-description = Download official background picture for the launcher. You can disable it to use your custom image instead
+update-background-description = Download new official background picture when it's changed
+
+background = Background
+background-description = Background picture of the classic launcher style
+background-source = Source
+background-source-description = Where the background picture is taken from
+background-official = Official
+background-file = Picture
+background-folder = Folder
+background-slideshow = Slideshow
+background-rotation = Change picture
+background-rotation-launch = On every launch
+background-rotation-interval = By interval
+background-interval = Interval
+background-interval-description = Minutes between the slideshow pictures
+background-style = Style
+background-fit = Picture fitting
+background-fit-cover = Crop to fill the window
+background-fit-contain = Show the whole picture
+background-blur = Blur picture
+not-selected = Not selected
+pictures = Pictures
 
 launcher-language = Launcher language
-launcher-language-description = Applies after restart
+launcher-language-description = Language of the launcher interface
 
 game-edition = Game edition
 global = Global
@@ -23,6 +44,21 @@ chinese = Chinese
 
 migrate-installation = Migrate installation
 migrate-installation-description = Open special window where you can change your game installation folder
+import-installation = Import installation
+import-installation-description = Use game files you already have, for example from a Windows partition or another launcher, instead of downloading them
+import = Import
+detected-installation = Detected installation
+import-integrity-check = Integrity check
+import-integrity-checking = Checking game files
+import-integrity-passed = All the game files are in place
+import-integrity-broken = {$count ->
+    [one] {$count} file is broken or missing. You can repair it after importing
+    *[other] {$count} files are broken or missing. You can repair them after importing
+}
+import-integrity-unavailable = Game files weren't checked
+import-move-files = Move game files
+import-move-files-description = Move game files to another folder instead of using them in place
+import-target-folder = Target folder
 repair-game = Repair game
 
 status = Status
@@ -30,8 +66,8 @@ status = Status
 game-version = Game version
 game-not-installed = not installed
 
-game-predownload-available = Game update pre-downloading available: {$old} -> {$new}
-game-update-available = Game update available: {$old} -> {$new}
+game-predownload-available = Game update pre-downloading available: {$old} -> {$new} ({$size})
+game-update-available = Game update available: {$old} -> {$new} ({$size})
 game-outdated = Game is too outdated and can't be updated. Latest version: {$latest}
 
 player-patch-version = Player patch version
@@ -71,3 +107,97 @@ explorer = Explorer
 task-manager = Task manager
 configuration = Configuration
 debugger = Debugger
+
+config-history = Config history
+config-history-description = Look through previous launcher settings and restore them
+config-backups = Config backups
+config-backups-description = Launcher keeps a copy of the config every time it was changed. Expand a backup to see changes it made to the previous one
+config-backup-current = Current config
+config-backup-changes = Changes: {$changes}
+create-backup = Backup
+restore = Restore
+config-restored = Config restored
+config-restored-description = Restart the launcher to apply restored settings
+
+config-broken = Failed to load config
+config-broken-description = Launcher's config file is corrupted and can't be loaded: {$error}. You can restore one of its backups or reset it to default values
+reset-config = Reset config
+
+check-config = Check configuration
+check-config-description = Find launcher settings which may break the game's installation or launch
+config-check-passed = No problems found
+config-issues = Found problems
+
+config-issue-folder-missing = Folder {$path} doesn't exist
+config-issue-not-folder = {$path} is not a folder
+config-issue-repairer-threads = Game repairer needs at least one thread
+config-issue-temp-full = Filesystem of the temp folder {$path} is almost full
+config-issue-language = Unsupported launcher language: {$language}
+config-issue-component-missing = Selected component {$name} is not downloaded
+config-issue-resolution = Resolution {$width}×{$height} is not valid
+
+config-fix-create-folder = Create
+config-fix-repairer-threads = Set to 4
+config-fix-reset-temp = Use system temp
+config-fix-language = Use system language
+config-fix-reset-resolution = Reset
+config-fix-disable-virtual-desktop = Disable
+
+check-system = Check system
+check-system-description = Find missing packages and system settings which may prevent the game from running
+system-checks = System checks
+copy-report = Copy report
+report-copied = Report copied to clipboard
+remedy-copied = Solution copied to clipboard
+
+system-check-binary-available = Installed
+system-check-binary-missing = Not installed
+system-check-vulkan-available = Vulkan loader is available
+system-check-vulkan-missing = Vulkan loader is not installed. Wine and DXVK can't work without it
+system-check-glibc = glibc version {$version}
+system-check-kernel = Kernel version {$version}
+system-check-update-system = Update your system
+system-check-max-map-count = Current value is {$count}, {$recommended} is recommended
+system-check-esync = Opened files limit is {$limit}, esync needs at least {$required}
+system-check-esync-remedy = Set DefaultLimitNOFILE={$limit} in /etc/systemd/system.conf and /etc/systemd/user.conf
+system-check-fsync = Kernel {$version} or newer is needed for fsync
+system-check-free-space = {$size} available in {$path}
+system-check-free-space-remedy = Free at least {$size} of space
+
+storage = Storage
+storage-description = Remove downloaded archives, cache and unused wine and DXVK builds
+auto-cleanup = Automatic cleanup
+auto-cleanup-description = Remove archives of the installed game version after successful update
+launcher-files = Launcher files
+launcher-files-description = Total size: {$size}. Files which are being downloaded can't be removed
+remove-selected = Remove ({$size})
+cleanup-finished = Freed {$size}
+cleanup-old-game-archive = Old game archive
+cleanup-game-archive = Game archive or pre-downloaded update
+cleanup-component-archive = Wine or DXVK archive
+cleanup-cache = Cache
+cleanup-discord-rpc-icons = Discord RPC icons
+cleanup-wine-build = Unused wine build
+cleanup-dxvk-build = Unused DXVK build
+
+voice-queue = Voiceovers queue
+voice-queue-description = {$count ->
+    [0] No pending voiceovers changes
+    [one] {$count} pending voiceover change
+    *[other] {$count} pending voiceovers changes
+}
+voice-queue-download-size = Total download size: {$size}. Changes are applied one by one in the shown order
+voice-queue-progress = {$voice} ({$current} of {$total})
+voice-queue-blocked = Queue can be started when the current download is finished
+queue-updates = Queue updates
+start-queue = Start
+voice-operation-install = Installation, {$size}
+voice-operation-update = Update, {$size}
+voice-operation-remove = Removal, {$size}
+voice-queued-install = Queued for installation
+voice-queued-update = Queued for update
+voice-queued-remove = Queued for removal
+
+installation-profiles = Installation profiles
+installation-profiles-description = Named game installations with their own game folder, edition, wine prefix, voiceovers and components. Enter a name to save the current installation as a profile
+delete-installation-profile = Delete installation profile
@@ -24,6 +24,7 @@ save = Save
 continue = Continue
 resume = Resume
 exit = Exit
+cancel = Cancel
 check = Check
 restart = Restart
 agree = Agree
@@ -47,6 +48,10 @@ unpacking = Unpacking
 verifying-files = Verifying files
 repairing-files = Repairing files
 migrating-folders = Migrating folders
+rolling-back-migration = Moving files back
+folder-migration-interrupted = Folder migration was interrupted
+folder-migration-interrupted-description = Moving files from {$from} to {$to} wasn't finished. You can resume it or move already moved files back
+roll-back = Roll back
 applying-hdiff = Applying hdiff patches
 removing-outdated = Removing outdated files
 
@@ -65,6 +70,20 @@ update = Update
 download = Download
 predownload-update = Pre-download {$version} update ({$size})
 
+storage-plan = Storage plan
+storage-plan-download-size = Download size: {$size}
+storage-plan-unpacked-size = Unpacked size: {$size}
+storage-plan-space = {$required} required, {$available} available
+storage-plan-not-enough = Not enough free space. Free some space or choose another folder in the launcher settings
+storage-plan-low = Filesystem will be almost full after downloading
+storage-plan-unknown = Download size or free space can't be checked
+storage-plan-calculating = Calculating required space...
+storage-temp-folder = Temp folder
+storage-game-folder = Game folder
+storage-wine-builds = Wine builds
+storage-dxvk-builds = DXVK builds
+unknown-size = unknown
+
 kill-game-process = Kill game process
 
 main-window--patch-unavailable-tooltip = Patch servers are unavailable and launcher can't verify the game's patching status. You're allowed to run the game on your own risk
@@ -74,3 +93,27 @@ main-window--version-outdated-tooltip = Version is too outdated and can't be upd
 preferences = Preferences
 general = General
 enhancements = Enhancements
+
+
+duration-days = {$value} d
+duration-hours = {$value} h
+duration-minutes = {$value} min
+duration-seconds = {$value} s
+
+date-just-now = Just now
+date-minutes-ago = { $value ->
+    [one] {$value} minute ago
+    *[other] {$value} minutes ago
+}
+date-hours-ago = { $value ->
+    [one] {$value} hour ago
+    *[other] {$value} hours ago
+}
+date-days-ago = { $value ->
+    [one] {$value} day ago
+    *[other] {$value} days ago
+}
+
+news-events = Events
+news-announcements = Announcements
+news-info = Info
@@ -89,3 +89,26 @@ main-window--version-outdated-tooltip = Версия слишком стара 
 preferences = Настройки
 general = Основное
 enhancements = Улучшения
+
+
+duration-days = {$value} д
+duration-hours = {$value} ч
+duration-minutes = {$value} мин
+duration-seconds = {$value} с
+
+date-just-now = Только что
+date-minutes-ago = { $value ->
+    [one] {$value} минуту назад
+    [few] {$value} минуты назад
+    *[many] {$value} минут назад
+}
+date-hours-ago = { $value ->
+    [one] {$value} час назад
+    [few] {$value} часа назад
+    *[many] {$value} часов назад
+}
+date-days-ago = { $value ->
+    [one] {$value} день назад
+    [few] {$value} дня назад
+    *[many] {$value} дней назад
+}
@@ -0,0 +1,389 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Serialize, Deserialize};
+
+use anime_launcher_sdk::genshin::config::Schema;
+
+use anime_launcher_sdk::anime_game_core::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
+
+use anime_launcher_sdk::components::*;
+
+use crate::move_files;
+
+lazy_static::lazy_static! {
+    /// Files and folders which are being downloaded or unpacked right now
+    static ref ACTIVE_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+}
+
+/// Prefixes of the game and voiceover archives names
+const GAME_ARCHIVE_PREFIXES: &[&str] = &[
+    concat!("Ge", "nshi", "nImp", "act_"),
+    concat!("Yu", "anS", "hen_"),
+    "game_",
+    "Audio_",
+    "en-us_",
+    "ja-jp_",
+    "ko-kr_",
+    "zh-cn_"
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupKind {
+    /// Archive of the already installed game version
+    OldGameArchive,
+
+    /// Archive of the newer game version, e.g. pre-downloaded update
+    GameArchive,
+
+    /// Wine or DXVK archive left after failed or interrupted installation
+    ComponentArchive,
+
+    Cache,
+    DiscordRpcIcons,
+
+    /// Wine build which is not used by the config or profiles
+    WineBuild,
+
+    /// DXVK build which is not used by the config or profiles
+    DxvkBuild
+}
+
+/// Launcher-owned file or folder which can be removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupItem {
+    pub kind: CleanupKind,
+    pub path: PathBuf,
+    pub size: u64,
+
+    /// File or folder is being downloaded or unpacked
+    pub in_progress: bool
+}
+
+/// Settings of the storage cleanup, stored in the `cleanup.json` file
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanupSettings {
+    /// Remove old archives after successful game or voiceovers update
+    pub auto_cleanup: bool
+}
+
+impl CleanupSettings {
+    /// Get stored cleanup settings, or default ones if they weren't set
+    pub fn get() -> anyhow::Result<Self> {
+        if !crate::CLEANUP_SETTINGS_FILE.exists() {
+            return Ok(Self::default());
+        }
+
+        let settings = std::fs::read(crate::CLEANUP_SETTINGS_FILE.as_path())?;
+
+        Ok(serde_json::from_slice(&settings)?)
+    }
+
+    pub fn set(&self) -> anyhow::Result<()> {
+        std::fs::write(crate::CLEANUP_SETTINGS_FILE.as_path(), serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+}
+
+/// Marks file or folder as being downloaded or unpacked until it's dropped,
+/// so the cleanup never lists or removes it
+#[derive(Debug)]
+#[must_use]
+pub struct ActivePath(PathBuf);
+
+impl ActivePath {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        if let Ok(mut paths) = ACTIVE_PATHS.lock() {
+            paths.push(path.clone());
+        }
+
+        Self(path)
+    }
+
+    /// Mark component's archive in the temp folder and its build folder
+    pub fn component(uri: &str, temp: &Path, build: PathBuf) -> [Self; 2] {
+        let archive = temp.join(uri.rsplit('/').next().unwrap_or(uri));
+
+        [Self::new(archive), Self::new(build)]
+    }
+}
+
+impl Drop for ActivePath {
+    fn drop(&mut self) {
+        if let Ok(mut paths) = ACTIVE_PATHS.lock() {
+            if let Some(i) = paths.iter().position(|path| path == &self.0) {
+                paths.remove(i);
+            }
+        }
+    }
+}
+
+/// Check if the path, something inside of it, or its part
+/// (e.g. archive segment `name.zip.001` of `name.zip`) is being downloaded or unpacked
+pub fn is_active(path: &Path) -> bool {
+    let Ok(paths) = ACTIVE_PATHS.lock() else {
+        return false;
+    };
+
+    paths.iter().any(|active| {
+        if active.starts_with(path) {
+            return true;
+        }
+
+        match (path.parent(), path.file_name(), active.file_name()) {
+            (Some(parent), Some(name), Some(active_name)) => {
+                active.parent() == Some(parent) && name.to_string_lossy().starts_with(active_name.to_string_lossy().as_ref())
+            }
+
+            _ => false
+        }
+    })
+}
+
+/// Get the last `x.y.z` version from the archive name, e.g. the target version of a diff archive
+fn get_archive_version(name: &str) -> Option<(u64, u64, u64)> {
+    name.split(|c: char| !c.is_ascii_digit() && c != '.')
+        .filter_map(|part| {
+            let mut numbers = part.split('.')
+                .filter_map(|number| number.parse::<u64>().ok());
+
+            Some((numbers.next()?, numbers.next()?, numbers.next()?))
+        })
+        .last()
+}
+
+fn is_game_archive(name: &str) -> bool {
+    let is_archive = name.ends_with(".zip") || name.ends_with(".7z") || name.rsplit_once('.')
+        .map(|(name, part)| part.chars().all(|c| c.is_ascii_digit()) && (name.ends_with(".zip") || name.ends_with(".7z")))
+        .unwrap_or(false);
+
+    is_archive && GAME_ARCHIVE_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// Get names of the wine and DXVK builds used by the config, session and installation profiles
+///
+/// Fails if profiles can't be read, so builds used by them are never considered unused
+fn get_used_components(config: &Schema) -> anyhow::Result<Vec<String>> {
+    let mut used = Vec::new();
+
+    used.extend(config.game.wine.selected.clone());
+    used.extend(config.game.dxvk.selected.clone());
+
+    for profile in crate::session_profiles::list()?.into_values() {
+        used.extend(profile.wine);
+        used.extend(profile.dxvk);
+    }
+
+    for profile in crate::installation_profiles::list()?.into_values() {
+        used.extend(profile.wine);
+        used.extend(profile.dxvk);
+    }
+
+    Ok(used)
+}
+
+fn push_item(items: &mut Vec<CleanupItem>, kind: CleanupKind, path: PathBuf) {
+    items.push(CleanupItem {
+        kind,
+        size: move_files::get_size(&path).unwrap_or(0),
+        in_progress: is_active(&path),
+        path
+    });
+}
+
+/// Find launcher-owned files in the temp, cache and components builds folders
+///
+/// Fails if used components builds can't be found
+pub fn find_items(config: &Schema) -> anyhow::Result<Vec<CleanupItem>> {
+    let mut items = Vec::new();
+
+    // Temp folder
+    let temp = config.launcher.temp.clone().unwrap_or_else(std::env::temp_dir);
+
+    let game = Game::new(config.game.path.for_edition(config.launcher.edition), config.launcher.edition);
+
+    let installed = game.get_version().ok()
+        .and_then(|version| get_archive_version(&version.to_string()));
+
+    let component_archives = wine::get_groups(&config.components.path).unwrap_or_default().into_iter()
+        .flat_map(|group| group.versions)
+        .map(|version| version.uri)
+        .chain(dxvk::get_groups(&config.components.path).unwrap_or_default().into_iter()
+            .flat_map(|group| group.versions)
+            .map(|version| version.uri))
+        .filter_map(|uri| uri.rsplit('/').next().map(String::from))
+        .collect::<Vec<_>>();
+
+    if let Ok(entries) = temp.read_dir() {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if !entry.path().is_file() {
+                continue;
+            }
+
+            if is_game_archive(&name) {
+                let kind = match (get_archive_version(&name), installed) {
+                    (Some(version), Some(installed)) if version <= installed => CleanupKind::OldGameArchive,
+                    _ => CleanupKind::GameArchive
+                };
+
+                push_item(&mut items, kind, entry.path());
+            }
+
+            else if component_archives.contains(&name) {
+                push_item(&mut items, CleanupKind::ComponentArchive, entry.path());
+            }
+        }
+    }
+
+    // Cache folder
+    if let Ok(entries) = crate::CACHE_FOLDER.read_dir() {
+        for entry in entries.flatten() {
+            let kind = if entry.file_name() == "discord-rpc" {
+                CleanupKind::DiscordRpcIcons
+            } else {
+                CleanupKind::Cache
+            };
+
+            push_item(&mut items, kind, entry.path());
+        }
+    }
+
+    // Unused components builds
+    let used = get_used_components(config)?;
+
+    let builds = [
+        (CleanupKind::WineBuild, &config.game.wine.builds),
+        (CleanupKind::DxvkBuild, &config.game.dxvk.builds)
+    ];
+
+    for (kind, folder) in builds {
+        if let Ok(entries) = folder.read_dir() {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+
+                if entry.path().is_dir() && !used.contains(&name) {
+                    push_item(&mut items, kind, entry.path());
+                }
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+/// Remove given items, skipping the ones which are being downloaded
+///
+/// Returns amount of freed bytes
+pub fn remove(items: &[CleanupItem]) -> anyhow::Result<u64> {
+    let mut freed = 0;
+
+    for item in items {
+        // File could start downloading after it was found
+        if item.in_progress || is_active(&item.path) {
+            tracing::warn!("Skipping path which is being downloaded or unpacked: {:?}", item.path);
+
+            continue;
+        }
+
+        tracing::info!("Removing {:?} ({:?})", item.path, item.kind);
+
+        if item.path.is_dir() {
+            std::fs::remove_dir_all(&item.path)?;
+        } else if item.path.exists() {
+            std::fs::remove_file(&item.path)?;
+        }
+
+        freed += item.size;
+    }
+
+    Ok(freed)
+}
+
+/// Remove archives of the installed game version and left components archives
+///
+/// Supposed to be called after successful game or voiceovers update
+pub fn auto_cleanup(config: &Schema) -> anyhow::Result<u64> {
+    let items = find_items(config)?.into_iter()
+        .filter(|item| matches!(item.kind, CleanupKind::OldGameArchive | CleanupKind::ComponentArchive))
+        .collect::<Vec<_>>();
+
+    remove(&items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create empty temp folder for the test
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aagl-cleanup-test-{name}-{}", std::process::id()));
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn unused_builds() {
+        let path = temp_folder("builds");
+
+        let mut config = Schema::default();
+
+        config.launcher.temp = Some(path.join("temp"));
+
+        config.game.path.global = path.join("game");
+        config.game.path.china = path.join("game-china");
+
+        config.game.wine.builds = path.join("runners");
+        config.game.wine.selected = Some(String::from("used"));
+        config.game.dxvk.builds = path.join("dxvks");
+
+        config.components.path = path.join("components");
+
+        let used = config.game.wine.builds.join("used");
+        let unused = config.game.wine.builds.join("unused");
+
+        std::fs::create_dir_all(&used).unwrap();
+        std::fs::create_dir_all(&unused).unwrap();
+        std::fs::write(unused.join("file"), "build").unwrap();
+
+        let get_builds = || find_items(&config).unwrap().into_iter()
+            .filter(|item| item.kind == CleanupKind::WineBuild)
+            .collect::<Vec<_>>();
+
+        let builds = get_builds();
+
+        assert_eq!(builds.len(), 1);
+        assert_eq!(builds[0].path, unused);
+        assert_eq!(builds[0].size, 5);
+        assert!(!builds[0].in_progress);
+
+        // Build which is being unpacked is never removed
+        let active = ActivePath::new(&unused);
+
+        let builds = get_builds();
+
+        assert!(builds[0].in_progress);
+        assert_eq!(remove(&builds).unwrap(), 0);
+        assert!(unused.exists());
+
+        drop(active);
+
+        assert_eq!(remove(&get_builds()).unwrap(), 5);
+        assert!(!unused.exists());
+        assert!(used.exists());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
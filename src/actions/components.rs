@@ -0,0 +1,125 @@
+use std::path::{Path, PathBuf};
+
+use anime_launcher_sdk::anime_game_core::prelude::*;
+use anime_launcher_sdk::wincompatlib::prelude::*;
+
+use anime_launcher_sdk::components::*;
+use anime_launcher_sdk::components::wine::UnifiedWine;
+
+use anime_launcher_sdk::genshin::config::Schema;
+
+use super::cleanup::ActivePath;
+
+/// Component installation failure, reported by the stage it happened at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallError {
+    Init(String),
+    Download(String),
+    Unpack(String),
+    Panicked
+}
+
+impl std::fmt::Display for InstallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Init(err)     => write!(f, "Failed to initialize installer: {err}"),
+            Self::Download(err) => write!(f, "Failed to download component: {err}"),
+            Self::Unpack(err)   => write!(f, "Failed to unpack component: {err}"),
+            Self::Panicked      => write!(f, "Installer panicked")
+        }
+    }
+}
+
+impl std::error::Error for InstallError {}
+
+fn get_installer(uri: &str, temp: Option<PathBuf>) -> anyhow::Result<Installer> {
+    Ok(Installer::new(uri)?.with_temp_folder(temp.unwrap_or_else(std::env::temp_dir)))
+}
+
+/// Download and unpack component named `name` into the `folder`, blocking until it's finished
+///
+/// Installer updates are passed to the `updater` to report the progress
+pub fn install(uri: &str, name: &str, folder: &Path, temp: Option<PathBuf>, mut updater: impl FnMut(InstallerUpdate)) -> Result<(), InstallError> {
+    let mut installer = get_installer(uri, temp)
+        .map_err(|err| InstallError::Init(err.to_string()))?;
+
+    std::fs::create_dir_all(folder)
+        .map_err(|err| InstallError::Init(err.to_string()))?;
+
+    let _active = ActivePath::component(uri, &installer.temp_folder, folder.join(name));
+
+    let folder = folder.to_path_buf();
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    let handle = std::thread::spawn(move || {
+        installer.install(folder, move |update| {
+            let _ = sender.send(update);
+        });
+    });
+
+    let mut error = None;
+
+    // Channel is closed when the installer drops its updater
+    while let Ok(update) = receiver.recv() {
+        match &update {
+            InstallerUpdate::DownloadingError(err) => error = Some(InstallError::Download(err.to_string())),
+            InstallerUpdate::UnpackingError(err) => error = Some(InstallError::Unpack(err.clone())),
+
+            _ => ()
+        }
+
+        updater(update);
+    }
+
+    if handle.join().is_err() {
+        return Err(InstallError::Panicked);
+    }
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(())
+    }
+}
+
+/// Get wine from the config's builds folder set to use the config's prefix
+fn get_wine(config: &Schema, wine: &wine::Version) -> UnifiedWine {
+    wine.to_wine(config.components.path.clone(), Some(config.game.wine.builds.join(&wine.name)))
+        .with_prefix(&config.game.wine.prefix)
+        .with_loader(WineLoader::Current)
+        .with_arch(WineArch::Win64)
+}
+
+/// Create wine prefix from the config using the given wine version
+pub fn create_prefix(config: &Schema, wine: &wine::Version) -> anyhow::Result<()> {
+    get_wine(config, wine).init_prefix(None::<&str>)?;
+
+    Ok(())
+}
+
+/// Check if DXVK should be applied to the prefix made by the given wine version
+pub fn need_dxvk(config: &Schema, wine: &wine::Version) -> anyhow::Result<bool> {
+    let need_dxvk = match wine.find_group(&config.components.path)? {
+        Some(group) => wine.features_in(&group).unwrap_or_default().need_dxvk,
+        None => true
+    };
+
+    Ok(need_dxvk && matches!(get_wine(config, wine), UnifiedWine::Default(_)))
+}
+
+/// Apply downloaded DXVK version to the wine prefix from the config
+pub fn apply_dxvk(config: &Schema, wine: &wine::Version, dxvk: &dxvk::Version) -> anyhow::Result<()> {
+    let UnifiedWine::Default(wine) = get_wine(config, wine) else {
+        anyhow::bail!("DXVK can't be applied to {} wine", wine.name);
+    };
+
+    let params = InstallParams {
+        // We just created prefix so don't need to repair it
+        repair_dlls: false,
+
+        ..InstallParams::default()
+    };
+
+    wine.install_dxvk(config.game.dxvk.builds.join(&dxvk.name), params)?;
+
+    Ok(())
+}
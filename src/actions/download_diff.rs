@@ -0,0 +1,24 @@
+use anime_launcher_sdk::genshin::config::Schema;
+
+use anime_launcher_sdk::anime_game_core::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
+
+use super::cleanup::ActivePath;
+
+/// Download and install game or voiceover update into the game folder from the given config
+pub fn download_diff(config: &Schema, mut diff: VersionDiff, updater: impl Fn(DiffUpdate) + Clone + Send + 'static) -> anyhow::Result<()> {
+    let game_path = config.game.path.for_edition(config.launcher.edition).to_path_buf();
+
+    if let Some(temp) = &config.launcher.temp {
+        diff = diff.with_temp_folder(temp.clone());
+    }
+
+    let temp = config.launcher.temp.clone().unwrap_or_else(std::env::temp_dir);
+
+    // Keep the archive from being removed by the cleanup
+    let _archive = diff.file_name().map(|name| ActivePath::new(temp.join(name)));
+
+    diff.install_to(game_path, updater)?;
+
+    Ok(())
+}
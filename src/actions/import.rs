@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+
+use anime_launcher_sdk::genshin::config::Schema;
+
+use anime_launcher_sdk::anime_game_core::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
+
+use crate::move_files::{self, MoveUpdate, TransferMode};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportUpdate {
+    /// Game files are being checked
+    Verifying {
+        processed: u64,
+        total: u64
+    },
+
+    /// Game files are being moved to the new location
+    Moving {
+        moved: u64,
+        total: u64
+    },
+
+    /// File wasn't moved and was kept in its original location
+    FileFailed {
+        path: PathBuf,
+        error: String
+    }
+}
+
+/// Game installation found in the existing folder
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedInstallation {
+    pub path: PathBuf,
+    pub edition: GameEdition,
+    pub version: Version,
+    pub voices: Vec<VoiceLocale>
+}
+
+/// Detect game edition, version and installed voice packages from the files in the folder
+pub fn detect_installation(path: impl AsRef<Path>) -> anyhow::Result<DetectedInstallation> {
+    let path = path.as_ref();
+
+    let Some(edition) = GameEdition::list().iter().copied().find(|edition| path.join(edition.data_folder()).is_dir()) else {
+        anyhow::bail!("Game files were not found in {:?}", path);
+    };
+
+    let game = Game::new(path, edition);
+
+    let voices = game.get_voice_packages()
+        .map(|packages| packages.into_iter().map(|package| package.locale()).collect())
+        .unwrap_or_default();
+
+    Ok(DetectedInstallation {
+        path: path.to_path_buf(),
+        edition,
+        version: game.get_version()?,
+        voices
+    })
+}
+
+/// Quickly check sizes of the detected installation's files using the integrity files of its edition
+///
+/// Returns amount of broken or missing files. Game files are not repaired,
+/// which can be done later from the launcher
+pub fn verify_installation(installation: &DetectedInstallation, mut updater: impl FnMut(ImportUpdate)) -> anyhow::Result<usize> {
+    let mut files = repairer::try_get_integrity_files(installation.edition, None)?;
+
+    for locale in &installation.voices {
+        if let Ok(mut voice_files) = repairer::try_get_voice_integrity_files(installation.edition, *locale, None) {
+            files.append(&mut voice_files);
+        }
+    }
+
+    let total = files.len() as u64;
+    let mut broken = 0;
+
+    for (i, file) in files.iter().enumerate() {
+        if !file.fast_verify(&installation.path) {
+            tracing::warn!("Imported game file is broken: {:?}", file.path);
+
+            broken += 1;
+        }
+
+        updater(ImportUpdate::Verifying {
+            processed: i as u64 + 1,
+            total
+        });
+    }
+
+    Ok(broken)
+}
+
+/// Adopt the detected installation, moving it to `target` if specified
+///
+/// Files are moved in the verified mode and recorded in the migration journal,
+/// so an interrupted moving can be resumed or rolled back from the main window.
+/// Returns final path of the installation
+pub fn import_installation(installation: &DetectedInstallation, target: Option<impl AsRef<Path>>, mut updater: impl FnMut(ImportUpdate)) -> anyhow::Result<PathBuf> {
+    let Some(target) = target else {
+        return Ok(installation.path.clone());
+    };
+
+    let target = target.as_ref();
+
+    if target != installation.path {
+        move_files::move_files_with_progress(&installation.path, target, Some(crate::MIGRATION_JOURNAL_FILE.as_path()), TransferMode::Verified, |update| {
+            updater(match update {
+                MoveUpdate::Progress { moved, total } => ImportUpdate::Moving { moved, total },
+                MoveUpdate::Failed { path, error } => ImportUpdate::FileFailed { path, error }
+            });
+        })?;
+    }
+
+    Ok(target.to_path_buf())
+}
+
+/// Make the config use the imported installation
+pub fn apply_installation(config: &mut Schema, installation: &DetectedInstallation, path: impl Into<PathBuf>) {
+    config.launcher.edition = installation.edition;
+
+    match installation.edition {
+        GameEdition::Global => config.game.path.global = path.into(),
+        GameEdition::China  => config.game.path.china  = path.into()
+    }
+
+    config.game.voices = installation.voices.iter()
+        .map(|locale| locale.to_code().to_string())
+        .collect();
+}
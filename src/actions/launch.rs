@@ -0,0 +1,61 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde_json::Value as JsonValue;
+
+use anime_launcher_sdk::config::ConfigExt;
+use anime_launcher_sdk::genshin::config::{Config, Schema};
+
+/// Launcher argument which makes it run the game with the config read from stdin
+pub const LAUNCH_WITH_CONFIG_ARG: &str = "--launch-with-config";
+
+/// Run the game with the current game session's launch profile applied
+///
+/// Without an active launch profile the game is run by this process
+/// using the launcher config. Blocks until the game is closed
+pub fn launch(config: &Schema) -> anyhow::Result<()> {
+    match crate::session_profiles::active() {
+        Some((name, profile)) => {
+            tracing::info!("Using launch profile of the session: {name}");
+
+            crate::session_profiles::run_with(config, &profile, run)
+        }
+
+        None => anime_launcher_sdk::genshin::game::run()
+    }
+}
+
+/// Run the game with exactly the given config
+///
+/// SDK reads launch settings from the process' in-memory config, so the game is run
+/// by a child launcher process which gets the config through stdin.
+/// Config of this process and the config file are never changed
+pub fn run(config: &Schema) -> anyhow::Result<()> {
+    let mut child = Command::new(std::env::current_exe()?)
+        .arg(LAUNCH_WITH_CONFIG_ARG)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&serde_json::to_vec(config)?)?;
+    }
+
+    let status = child.wait()?;
+
+    if !status.success() {
+        anyhow::bail!("Game process exited with status {status}");
+    }
+
+    Ok(())
+}
+
+/// Run the game with the config passed by the parent launcher process through stdin
+///
+/// Config is only set in memory of this process and is never flushed to the config file
+pub fn run_from_stdin() -> anyhow::Result<()> {
+    let config: JsonValue = serde_json::from_reader(std::io::stdin())?;
+
+    Config::update(Schema::from(&config));
+
+    anime_launcher_sdk::genshin::game::run()
+}
@@ -0,0 +1,512 @@
+use std::path::{Path, PathBuf};
+
+use crate::move_files::{self, Filesystem, SystemFilesystem, Journal, MoveUpdate, TransferMode};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrateUpdate {
+    /// Files are being moved to the new location
+    Moving {
+        moved: u64,
+        total: u64
+    },
+
+    /// Files of the interrupted migration are being moved back
+    RollingBack {
+        moved: u64,
+        total: u64
+    },
+
+    /// File wasn't moved and was kept in its original location
+    FileFailed {
+        path: PathBuf,
+        error: String
+    }
+}
+
+/// Move folder's content to the new location and remove the cleanup folder if specified
+///
+/// Free space of the new location is checked before moving.
+/// Files are moved in the verified mode, and the ones which failed to be moved are reported
+/// with `MigrateUpdate::FileFailed` and kept in the original location.
+/// Moved files are recorded in the migration journal, so an interrupted migration
+/// is resumed by calling this function again, or rolled back with `rollback_migration`
+#[inline]
+pub fn migrate_folder(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    cleanup_folder: Option<impl AsRef<Path>>,
+    updater: impl FnMut(MigrateUpdate)
+) -> anyhow::Result<()> {
+    migrate_folder_with_journal(crate::MIGRATION_JOURNAL_FILE.as_path(), from, to, cleanup_folder, updater)
+}
+
+fn migrate_folder_with_journal(
+    journal: &Path,
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    cleanup_folder: Option<impl AsRef<Path>>,
+    mut updater: impl FnMut(MigrateUpdate)
+) -> anyhow::Result<()> {
+    move_files::move_files_with_progress(from.as_ref(), to.as_ref(), Some(journal), TransferMode::Verified, |update| {
+        updater(match update {
+            MoveUpdate::Progress { moved, total } => MigrateUpdate::Moving { moved, total },
+            MoveUpdate::Failed { path, error } => MigrateUpdate::FileFailed { path, error }
+        });
+    })?;
+
+    if let Some(cleanup_folder) = cleanup_folder {
+        std::fs::remove_dir_all(cleanup_folder)?;
+    }
+
+    Ok(())
+}
+
+/// Get journal of the migration which was interrupted
+#[inline]
+pub fn get_interrupted_migration() -> anyhow::Result<Option<Journal>> {
+    Journal::read(crate::MIGRATION_JOURNAL_FILE.as_path())
+}
+
+/// Move files of the interrupted migration back to their original location
+///
+/// Partially copied files are removed from the new location
+#[inline]
+pub fn rollback_migration(updater: impl FnMut(MigrateUpdate)) -> anyhow::Result<()> {
+    rollback_migration_with_journal(crate::MIGRATION_JOURNAL_FILE.as_path(), updater)
+}
+
+fn rollback_migration_with_journal(journal: &Path, mut updater: impl FnMut(MigrateUpdate)) -> anyhow::Result<()> {
+    if let Some(journal) = Journal::read(journal)? {
+        journal.rollback(|update| {
+            updater(match update {
+                MoveUpdate::Progress { moved, total } => MigrateUpdate::RollingBack { moved, total },
+                MoveUpdate::Failed { path, error } => MigrateUpdate::FileFailed { path, error }
+            });
+        })?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FolderTransfer {
+    /// Folder's content is renamed within the same disk
+    Rename,
+
+    /// Folder's content is copied to another disk
+    Copy,
+
+    /// Folder's content is kept in place and only its path is changed
+    PathOnly,
+
+    /// Nothing to move: the path wasn't changed or the folder doesn't exist
+    Skip
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationConflict {
+    /// Target folder already contains some files
+    TargetNotEmpty,
+
+    /// Target folder is inside the source one
+    TargetInsideSource,
+
+    /// Another folder is moved to the same target
+    SameTarget,
+
+    /// Target disk doesn't have enough free space for all the folders copied there
+    NotEnoughSpace {
+        required: u64,
+        available: u64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFolder {
+    pub from: PathBuf,
+    pub to: PathBuf,
+
+    /// Total size of the folder's files
+    pub size: u64,
+
+    pub transfer: FolderTransfer,
+
+    /// Free space needed on the target disk
+    pub required_space: u64,
+
+    pub conflicts: Vec<MigrationConflict>
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationPlan {
+    /// Planned folders in the same order they were given
+    pub folders: Vec<PlannedFolder>
+}
+
+impl MigrationPlan {
+    /// Plan folders migration without moving anything
+    ///
+    /// `folders` contains source and target paths, and whether the folder's content should be moved.
+    /// Files of the not movable folders are kept in place, like the temp folder
+    #[inline]
+    pub fn new(folders: impl IntoIterator<Item = (PathBuf, PathBuf, bool)>) -> Self {
+        Self::with_filesystem(folders, &SystemFilesystem)
+    }
+
+    pub(crate) fn with_filesystem(folders: impl IntoIterator<Item = (PathBuf, PathBuf, bool)>, filesystem: &dyn Filesystem) -> Self {
+        let mut planned = folders.into_iter()
+            .map(|(from, to, movable)| {
+                let to_parent = move_files::get_existing_parent(&to).unwrap_or(&to);
+
+                let transfer = if from == to || !from.exists() {
+                    FolderTransfer::Skip
+                } else if !movable {
+                    FolderTransfer::PathOnly
+                } else if filesystem.is_same_disk(&from, to_parent) {
+                    FolderTransfer::Rename
+                } else {
+                    FolderTransfer::Copy
+                };
+
+                let size = match transfer {
+                    FolderTransfer::Rename | FolderTransfer::Copy => move_files::get_size(&from).unwrap_or(0),
+                    FolderTransfer::PathOnly | FolderTransfer::Skip => 0
+                };
+
+                let mut conflicts = Vec::new();
+
+                if matches!(transfer, FolderTransfer::Rename | FolderTransfer::Copy) {
+                    if to.starts_with(&from) {
+                        conflicts.push(MigrationConflict::TargetInsideSource);
+                    }
+
+                    else if to.read_dir().map(|mut entries| entries.next().is_some()).unwrap_or(false) {
+                        conflicts.push(MigrationConflict::TargetNotEmpty);
+                    }
+                }
+
+                PlannedFolder {
+                    required_space: if transfer == FolderTransfer::Copy { size } else { 0 },
+
+                    from,
+                    to,
+                    size,
+                    transfer,
+                    conflicts
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let moved = planned.iter()
+            .filter(|folder| matches!(folder.transfer, FolderTransfer::Rename | FolderTransfer::Copy))
+            .map(|folder| folder.to.clone())
+            .collect::<Vec<_>>();
+
+        let copied = planned.iter()
+            .filter(|folder| folder.transfer == FolderTransfer::Copy)
+            .map(|folder| (folder.to.clone(), folder.required_space))
+            .collect::<Vec<_>>();
+
+        for folder in &mut planned {
+            if !matches!(folder.transfer, FolderTransfer::Rename | FolderTransfer::Copy) {
+                continue;
+            }
+
+            if moved.iter().filter(|to| **to == folder.to).count() > 1 {
+                folder.conflicts.push(MigrationConflict::SameTarget);
+            }
+
+            if folder.transfer == FolderTransfer::Copy {
+                let to_parent = move_files::get_existing_parent(&folder.to).unwrap_or(&folder.to);
+
+                // All the folders copied to the same disk share its free space
+                let required = copied.iter()
+                    .filter(|(to, _)| filesystem.is_same_disk(to_parent, move_files::get_existing_parent(to).unwrap_or(to.as_path())))
+                    .map(|(_, required)| required)
+                    .sum::<u64>();
+
+                if let Some(available) = filesystem.available(to_parent) {
+                    if available < required {
+                        folder.conflicts.push(MigrationConflict::NotEnoughSpace {
+                            required,
+                            available
+                        });
+                    }
+                }
+            }
+        }
+
+        Self {
+            folders: planned
+        }
+    }
+
+    #[inline]
+    pub fn has_conflicts(&self) -> bool {
+        self.folders.iter().any(|folder| !folder.conflicts.is_empty())
+    }
+
+    /// Get folders which content should be moved
+    pub fn moved_folders(&self) -> impl Iterator<Item = &PlannedFolder> {
+        self.folders.iter().filter(|folder| matches!(folder.transfer, FolderTransfer::Rename | FolderTransfer::Copy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create empty temp folder for the test
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aagl-migrate-test-{name}-{}", std::process::id()));
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    /// Create `a.txt`, `b/c.txt` and `b/d/e.txt` files in the folder
+    fn create_files(path: &Path) {
+        std::fs::create_dir_all(path.join("b/d")).unwrap();
+
+        std::fs::write(path.join("a.txt"), "a").unwrap();
+        std::fs::write(path.join("b/c.txt"), "c").unwrap();
+        std::fs::write(path.join("b/d/e.txt"), "e").unwrap();
+    }
+
+    fn assert_files(path: &Path) {
+        assert_eq!(std::fs::read_to_string(path.join("a.txt")).unwrap(), "a");
+        assert_eq!(std::fs::read_to_string(path.join("b/c.txt")).unwrap(), "c");
+        assert_eq!(std::fs::read_to_string(path.join("b/d/e.txt")).unwrap(), "e");
+    }
+
+    /// Move `a.txt` and write the journal as if the migration was interrupted after it
+    fn interrupt(journal: &Path, from: &Path, to: &Path) {
+        std::fs::create_dir_all(to).unwrap();
+        std::fs::rename(from.join("a.txt"), to.join("a.txt")).unwrap();
+
+        // File which was being copied when the migration was interrupted
+        std::fs::create_dir_all(to.join("b")).unwrap();
+        std::fs::write(to.join("b/c.txt.migrating"), "partial").unwrap();
+
+        let header = serde_json::json!({
+            "from": from,
+            "to": to
+        });
+
+        std::fs::write(journal, format!("{header}\na.txt\n")).unwrap();
+    }
+
+    #[test]
+    fn migrate() {
+        let temp = temp_folder("migrate");
+
+        let journal = temp.join("journal");
+        let from = temp.join("from");
+        let to = temp.join("to");
+
+        create_files(&from);
+
+        let mut progress = Vec::new();
+
+        migrate_folder_with_journal(&journal, &from, &to, None::<&Path>, |update| progress.push(update)).unwrap();
+
+        assert_files(&to);
+        assert!(!journal.exists());
+
+        assert_eq!(progress.last(), Some(&MigrateUpdate::Moving { moved: 3, total: 3 }));
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn resume_interrupted() {
+        let temp = temp_folder("resume");
+
+        let journal = temp.join("journal");
+        let from = temp.join("from");
+        let to = temp.join("to");
+
+        create_files(&from);
+        interrupt(&journal, &from, &to);
+
+        let interrupted = Journal::read(&journal).unwrap().unwrap();
+
+        assert_eq!(interrupted.from, from);
+        assert_eq!(interrupted.to, to);
+        assert_eq!(interrupted.moved, vec![PathBuf::from("a.txt")]);
+
+        migrate_folder_with_journal(&journal, &from, &to, None::<&Path>, |_| ()).unwrap();
+
+        assert_files(&to);
+        assert!(!journal.exists());
+        assert!(!to.join("b/c.txt.migrating").exists());
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn another_migration_interrupted() {
+        let temp = temp_folder("another");
+
+        let journal = temp.join("journal");
+        let from = temp.join("from");
+        let to = temp.join("to");
+
+        create_files(&from);
+        interrupt(&journal, &from, &to);
+
+        let other = temp.join("other");
+
+        create_files(&other);
+
+        assert!(migrate_folder_with_journal(&journal, &other, temp.join("other-to"), None::<&Path>, |_| ()).is_err());
+
+        // Interrupted migration is kept to be resumed or rolled back
+        assert!(journal.exists());
+        assert_files(&other);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn rollback_interrupted() {
+        let temp = temp_folder("rollback");
+
+        let journal = temp.join("journal");
+        let from = temp.join("from");
+        let to = temp.join("to");
+
+        create_files(&from);
+        interrupt(&journal, &from, &to);
+
+        rollback_migration_with_journal(&journal, |_| ()).unwrap();
+
+        assert_files(&from);
+
+        assert!(!journal.exists());
+        assert!(!to.join("b/c.txt.migrating").exists());
+        assert!(!to.exists());
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn rollback_without_journal() {
+        let temp = temp_folder("rollback-empty");
+
+        rollback_migration_with_journal(&temp.join("journal"), |_| ()).unwrap();
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    /// Filesystem with `disk` subfolder of the temp folder mounted as another disk
+    fn other_disk(temp: &Path, available: u64) -> move_files::OtherDisk {
+        std::fs::create_dir_all(temp.join("disk")).unwrap();
+
+        move_files::OtherDisk {
+            root: temp.join("disk"),
+            available: Some(available),
+            corrupt: false
+        }
+    }
+
+    #[test]
+    fn plan_transfers() {
+        let temp = temp_folder("plan");
+
+        create_files(&temp.join("game"));
+        create_files(&temp.join("prefix"));
+        create_files(&temp.join("temp"));
+        create_files(&temp.join("same"));
+
+        let plan = MigrationPlan::with_filesystem([
+            (temp.join("game"), temp.join("new/game"), true),
+            (temp.join("prefix"), temp.join("disk/prefix"), true),
+            (temp.join("temp"), temp.join("disk/temp"), false),
+            (temp.join("same"), temp.join("same"), true),
+            (temp.join("missing"), temp.join("disk/missing"), true)
+        ], &other_disk(&temp, 1024));
+
+        let transfers = plan.folders.iter()
+            .map(|folder| (folder.transfer, folder.size, folder.required_space))
+            .collect::<Vec<_>>();
+
+        assert_eq!(transfers, [
+            (FolderTransfer::Rename, 3, 0),
+            (FolderTransfer::Copy, 3, 3),
+            (FolderTransfer::PathOnly, 0, 0),
+            (FolderTransfer::Skip, 0, 0),
+            (FolderTransfer::Skip, 0, 0)
+        ]);
+
+        assert!(!plan.has_conflicts());
+        assert_eq!(plan.moved_folders().count(), 2);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn plan_conflicts() {
+        let temp = temp_folder("plan-conflicts");
+
+        create_files(&temp.join("game"));
+        create_files(&temp.join("prefix"));
+        create_files(&temp.join("dxvk"));
+        create_files(&temp.join("wine"));
+        create_files(&temp.join("used"));
+
+        let plan = MigrationPlan::with_filesystem([
+            (temp.join("game"), temp.join("game/inner"), true),
+            (temp.join("prefix"), temp.join("used"), true),
+            (temp.join("dxvk"), temp.join("components"), true),
+            (temp.join("wine"), temp.join("components"), true)
+        ], &other_disk(&temp, 1024));
+
+        let conflicts = plan.folders.iter()
+            .map(|folder| folder.conflicts.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(conflicts, [
+            vec![MigrationConflict::TargetInsideSource],
+            vec![MigrationConflict::TargetNotEmpty],
+            vec![MigrationConflict::SameTarget],
+            vec![MigrationConflict::SameTarget]
+        ]);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn plan_not_enough_space() {
+        let temp = temp_folder("plan-space");
+
+        create_files(&temp.join("game"));
+        create_files(&temp.join("prefix"));
+
+        let folders = [
+            (temp.join("game"), temp.join("disk/game"), true),
+            (temp.join("prefix"), temp.join("disk/prefix"), true)
+        ];
+
+        // Both folders share free space of the disk they're copied to
+        let plan = MigrationPlan::with_filesystem(folders.clone(), &other_disk(&temp, 5));
+
+        for folder in &plan.folders {
+            assert_eq!(folder.conflicts, [MigrationConflict::NotEnoughSpace {
+                required: 6,
+                available: 5
+            }]);
+        }
+
+        let plan = MigrationPlan::with_filesystem(folders, &other_disk(&temp, 6));
+
+        assert!(!plan.has_conflicts());
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+}
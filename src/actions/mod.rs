@@ -0,0 +1,15 @@
+//! Launcher actions which don't depend on the UI
+//!
+//! Every action reports its progress through a callback,
+//! so it can be driven by the main window as well as from the command line
+
+pub mod launch;
+pub mod download_diff;
+pub mod repair;
+pub mod migrate;
+pub mod import;
+pub mod components;
+pub mod provision;
+pub mod storage_plan;
+pub mod cleanup;
+pub mod voice_queue;
@@ -0,0 +1,283 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use anime_launcher_sdk::anime_game_core::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::consts::GameEdition;
+
+use anime_launcher_sdk::components::*;
+use anime_launcher_sdk::components::loader::ComponentsLoader;
+
+use anime_launcher_sdk::config::ConfigExt;
+use anime_launcher_sdk::genshin::config::{Config, Schema};
+
+use super::components;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ProvisionPaths {
+    /// Base folder of all the other ones, like the launcher folder in the first run wizard
+    pub launcher: Option<PathBuf>,
+
+    pub runners: Option<PathBuf>,
+    pub dxvks: Option<PathBuf>,
+    pub prefix: Option<PathBuf>,
+    pub game_global: Option<PathBuf>,
+    pub game_china: Option<PathBuf>,
+    pub fps_unlocker: Option<PathBuf>,
+    pub components: Option<PathBuf>,
+    pub temp: Option<PathBuf>
+}
+
+/// Settings of the unattended first run setup
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Provision {
+    /// Launcher language, like `en-us`
+    pub language: Option<String>,
+
+    pub edition: Option<GameEdition>,
+    pub paths: ProvisionPaths,
+
+    /// Voice packages codes, like `en-us` or `ja-jp`
+    pub voices: Option<Vec<String>>,
+
+    /// Name of the wine build. Latest one is used if not specified
+    pub wine: Option<String>,
+
+    /// Name of the DXVK build. Latest one is used if not specified
+    pub dxvk: Option<String>,
+
+    /// Values merged into the `game.enhancements` config section
+    pub enhancements: Option<JsonValue>
+}
+
+impl Provision {
+    /// Read provisioning file. Its format is chosen by the extension, json is used by default
+    pub fn read(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(toml::from_str(&content)?),
+            _ => Ok(serde_json::from_str(&content)?)
+        }
+    }
+
+    /// Apply provisioned settings to the config
+    pub fn apply(&self, config: &mut Schema) -> anyhow::Result<()> {
+        if let Some(language) = &self.language {
+            config.launcher.language = crate::i18n::format_lang(&language.parse()?);
+        }
+
+        if let Some(edition) = self.edition {
+            config.launcher.edition = edition;
+        }
+
+        let paths = &self.paths;
+
+        if let Some(folder) = &paths.launcher {
+            config.game.wine.builds = folder.join("runners");
+            config.game.dxvk.builds = folder.join("dxvks");
+            config.game.wine.prefix = folder.join("prefix");
+            config.game.path.global = folder.join(concat!("Ge", "nshi", "n Imp", "act"));
+            config.game.path.china  = folder.join(concat!("Yu", "anS", "hen"));
+            config.components.path  = folder.join("components");
+            config.launcher.temp    = Some(folder.clone());
+
+            config.game.enhancements.fps_unlocker.path = folder.join("fps-unlocker");
+        }
+
+        let fields = [
+            (&paths.runners,     &mut config.game.wine.builds),
+            (&paths.dxvks,       &mut config.game.dxvk.builds),
+            (&paths.prefix,      &mut config.game.wine.prefix),
+            (&paths.game_global, &mut config.game.path.global),
+            (&paths.game_china,  &mut config.game.path.china),
+            (&paths.components,  &mut config.components.path),
+
+            (&paths.fps_unlocker, &mut config.game.enhancements.fps_unlocker.path)
+        ];
+
+        for (path, field) in fields {
+            if let Some(path) = path {
+                *field = path.clone();
+            }
+        }
+
+        if let Some(temp) = &paths.temp {
+            config.launcher.temp = Some(temp.clone());
+        }
+
+        if let Some(voices) = &self.voices {
+            for voice in voices {
+                if VoiceLocale::from_str(voice).is_none() {
+                    anyhow::bail!("Unknown voice package: {voice}");
+                }
+            }
+
+            config.game.voices = voices.clone();
+        }
+
+        if let Some(enhancements) = &self.enhancements {
+            let mut value = serde_json::to_value(&*config)?;
+
+            merge(&mut value["game"]["enhancements"], enhancements);
+
+            *config = Schema::from(&value);
+        }
+
+        Ok(())
+    }
+}
+
+/// Recursively merge `from` object's values into the `to` one
+fn merge(to: &mut JsonValue, from: &JsonValue) {
+    match (to, from) {
+        (JsonValue::Object(to), JsonValue::Object(from)) => {
+            for (key, value) in from {
+                merge(to.entry(key.clone()).or_insert(JsonValue::Null), value);
+            }
+        }
+
+        (to, from) => *to = from.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProvisionUpdate {
+    SyncingComponents,
+    DownloadingWine(String),
+    CreatingPrefix(PathBuf),
+    DownloadingDxvk(String),
+    ApplyingDxvk(String),
+
+    /// Progress of the current downloading or unpacking
+    Progress {
+        current: u64,
+        total: u64
+    }
+}
+
+/// Pass downloading and unpacking progress of the installer to the provision updater
+fn report_progress(update: InstallerUpdate, updater: &mut impl FnMut(ProvisionUpdate)) {
+    match update {
+        InstallerUpdate::DownloadingProgress(current, total) |
+        InstallerUpdate::UnpackingProgress(current, total) => updater(ProvisionUpdate::Progress {
+            current,
+            total
+        }),
+
+        _ => ()
+    }
+}
+
+/// Apply provisioned settings and run the first run wizard's steps without UI:
+/// sync components index, download wine, create prefix, download and apply DXVK
+///
+/// `.first-run` file is removed when everything is done
+pub fn provision(settings: &Provision, mut updater: impl FnMut(ProvisionUpdate)) -> anyhow::Result<()> {
+    let mut config = Config::get()?;
+
+    settings.apply(&mut config)?;
+
+    crate::config_history::update_raw(config.clone())?;
+
+    if settings.language.is_some() {
+        crate::i18n::set_lang(config.launcher.language.parse()?)?;
+    }
+
+    // Sync components index
+    updater(ProvisionUpdate::SyncingComponents);
+
+    let loader = ComponentsLoader::new(config.components.path.clone());
+
+    if loader.is_sync(config.components.servers.clone())?.is_none() {
+        let synced = config.components.servers.iter().any(|host| {
+            match loader.sync(host) {
+                Ok(_) => true,
+
+                Err(err) => {
+                    tracing::error!("Failed to sync components index from {host}: {err}");
+
+                    false
+                }
+            }
+        });
+
+        if !synced {
+            anyhow::bail!("Failed to sync components index");
+        }
+    }
+
+    // Download wine
+    let wine_versions = wine::get_groups(&config.components.path)?
+        .into_iter()
+        .flat_map(|group| group.versions);
+
+    let wine = match &settings.wine {
+        Some(name) => wine_versions.into_iter().find(|version| &version.name == name),
+        None => wine_versions.into_iter().next()
+    };
+
+    let Some(wine) = wine else {
+        anyhow::bail!("Wine build not found: {}", settings.wine.as_deref().unwrap_or("latest"));
+    };
+
+    if !wine.is_downloaded_in(&config.game.wine.builds) {
+        updater(ProvisionUpdate::DownloadingWine(wine.title.clone()));
+
+        components::install(&wine.uri, &wine.name, &config.game.wine.builds, config.launcher.temp.clone(), |update| report_progress(update, &mut updater))?;
+    }
+
+    config.game.wine.selected = Some(wine.name.clone());
+
+    crate::config_history::update_raw(config.clone())?;
+
+    // Create prefix
+    updater(ProvisionUpdate::CreatingPrefix(config.game.wine.prefix.clone()));
+
+    components::create_prefix(&config, &wine)?;
+
+    // Download and apply DXVK
+    if !components::need_dxvk(&config, &wine)? {
+        tracing::info!("Selected wine version has `need_dxvk = false` feature. Skipping DXVK applying...");
+    }
+
+    else {
+        let dxvk_versions = dxvk::get_groups(&config.components.path)?
+            .into_iter()
+            .flat_map(|group| group.versions);
+
+        let dxvk = match &settings.dxvk {
+            Some(name) => dxvk_versions.into_iter().find(|version| &version.name == name),
+            None => dxvk_versions.into_iter().next()
+        };
+
+        let Some(dxvk) = dxvk else {
+            anyhow::bail!("DXVK build not found: {}", settings.dxvk.as_deref().unwrap_or("latest"));
+        };
+
+        if !dxvk.is_downloaded_in(&config.game.dxvk.builds) {
+            updater(ProvisionUpdate::DownloadingDxvk(dxvk.name.clone()));
+
+            components::install(&dxvk.uri, &dxvk.name, &config.game.dxvk.builds, config.launcher.temp.clone(), |update| report_progress(update, &mut updater))?;
+        }
+
+        updater(ProvisionUpdate::ApplyingDxvk(dxvk.name.clone()));
+
+        components::apply_dxvk(&config, &wine, &dxvk)?;
+
+        config.game.dxvk.selected = Some(dxvk.name);
+
+        crate::config_history::update_raw(config)?;
+    }
+
+    if crate::FIRST_RUN_FILE.exists() {
+        std::fs::remove_file(crate::FIRST_RUN_FILE.as_path())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use anime_launcher_sdk::genshin::config::Schema;
+
+use anime_launcher_sdk::anime_game_core::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
+use anime_launcher_sdk::anime_game_core::repairer::IntegrityFile;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepairUpdate {
+    /// Integrity files were fetched and are being verified
+    Verifying {
+        processed: u64,
+        total: u64
+    },
+
+    /// Broken files are being repaired
+    Repairing {
+        repaired: u64,
+        total: u64
+    },
+
+    /// Failed to repair a single file. Other files are still repaired
+    RepairingError(String)
+}
+
+/// Verify game and installed voiceovers files and repair broken ones
+///
+/// Returns amount of broken files
+pub fn repair_game(config: &Schema, updater: impl FnMut(RepairUpdate)) -> anyhow::Result<usize> {
+    let mut files = repairer::try_get_integrity_files(config.launcher.edition, None)?;
+
+    // Add voiceovers files
+    let game_path = config.game.path.for_edition(config.launcher.edition).to_path_buf();
+    let game = Game::new(&game_path, config.launcher.edition);
+
+    if let Ok(voiceovers) = game.get_voice_packages() {
+        for package in voiceovers {
+            if let Ok(mut voiceover_files) = repairer::try_get_voice_integrity_files(config.launcher.edition, package.locale(), None) {
+                files.append(&mut voiceover_files);
+            }
+        }
+    }
+
+    repair_files(config, files, updater)
+}
+
+/// Verify given game files and repair broken ones
+///
+/// Returns amount of broken files
+pub fn repair_files(config: &Schema, files: Vec<IntegrityFile>, mut updater: impl FnMut(RepairUpdate)) -> anyhow::Result<usize> {
+    let game_path = config.game.path.for_edition(config.launcher.edition).to_path_buf();
+
+    let total = files.iter().map(|file| file.size).sum::<u64>();
+
+    updater(RepairUpdate::Verifying {
+        processed: 0,
+        total
+    });
+
+    // Zero threads would leave files unverified
+    let threads = config.launcher.repairer.threads.max(1);
+    let fast = config.launcher.repairer.fast;
+
+    let median_size = total / threads;
+    let mut i = 0;
+
+    let (verify_sender, verify_receiver) = std::sync::mpsc::channel();
+
+    for _ in 0..threads {
+        let mut thread_files = Vec::new();
+        let mut thread_files_size = 0;
+
+        while i < files.len() {
+            thread_files.push(files[i].clone());
+
+            thread_files_size += files[i].size;
+            i += 1;
+
+            if thread_files_size >= median_size {
+                break;
+            }
+        }
+
+        let thread_sender = verify_sender.clone();
+        let game_path = game_path.clone();
+
+        std::thread::spawn(move || {
+            for file in thread_files {
+                let status = if fast {
+                    file.fast_verify(&game_path)
+                } else {
+                    file.verify(&game_path)
+                };
+
+                thread_sender.send((file, status)).unwrap();
+            }
+        });
+    }
+
+    // We have [threads] copies of this sender + the original one
+    // receiver will return Err when all the senders will be dropped.
+    // [threads] senders will be dropped when threads will finish verifying files
+    // but this one will live as long as current thread exists so we should drop it manually
+    drop(verify_sender);
+
+    let mut broken = Vec::new();
+    let mut processed = 0;
+
+    while let Ok((file, status)) = verify_receiver.recv() {
+        processed += file.size;
+
+        if !status {
+            broken.push(file);
+        }
+
+        updater(RepairUpdate::Verifying {
+            processed,
+            total
+        });
+    }
+
+    let broken_count = broken.len();
+
+    if !broken.is_empty() {
+        let total = broken.len() as u64;
+
+        updater(RepairUpdate::Repairing {
+            repaired: 0,
+            total
+        });
+
+        tracing::warn!("Found broken files:\n{}", broken.iter().fold(String::new(), |acc, file| acc + &format!("- {}\n", file.path.to_string_lossy())));
+
+        for (i, file) in broken.into_iter().enumerate() {
+            tracing::debug!("Repairing file: {}", file.path.to_string_lossy());
+
+            if let Err(err) = file.repair(&game_path) {
+                tracing::error!("Failed to repair game file: {err}");
+
+                updater(RepairUpdate::RepairingError(err.to_string()));
+            }
+
+            updater(RepairUpdate::Repairing {
+                repaired: i as u64 + 1,
+                total
+            });
+        }
+    }
+
+    Ok(broken_count)
+}
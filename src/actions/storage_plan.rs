@@ -0,0 +1,345 @@
+use std::path::{Path, PathBuf};
+
+use anime_launcher_sdk::genshin::config::Schema;
+
+use anime_launcher_sdk::anime_game_core::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
+use anime_launcher_sdk::anime_game_core::installer::downloader::Downloader;
+
+use crate::move_files::{self, Filesystem, SystemFilesystem};
+
+/// Free space which should be left on the filesystem after downloading
+pub const LOW_SPACE_MARGIN: u64 = 1024 * 1024 * 1024;
+
+/// Folder files will be written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageUsage {
+    /// Archives are downloaded there before unpacking
+    Temp,
+
+    Game,
+    WineBuilds,
+    DxvkBuilds
+}
+
+/// Filesystem used by one or more planned folders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFilesystem {
+    pub folders: Vec<(StorageUsage, PathBuf)>,
+
+    /// Amount of bytes written to the filesystem
+    pub required: u64,
+
+    /// Current free space, or `None` if it can't be checked
+    pub available: Option<u64>
+}
+
+impl PlannedFilesystem {
+    pub fn status(&self) -> StorageStatus {
+        match self.available {
+            Some(available) if available < self.required => StorageStatus::NotEnough,
+            Some(available) if available < self.required + LOW_SPACE_MARGIN => StorageStatus::Low,
+
+            Some(_) => StorageStatus::Enough,
+
+            None => StorageStatus::Unknown
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StorageStatus {
+    Enough,
+
+    /// Free space or download size can't be checked
+    Unknown,
+
+    /// Less than [`LOW_SPACE_MARGIN`] will be left after downloading
+    Low,
+
+    NotEnough
+}
+
+/// Free space needed to download and unpack something, split by filesystems
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StoragePlan {
+    /// Size of the downloaded archives, `None` if some of them are unknown
+    pub download_size: Option<u64>,
+
+    /// Size of the unpacked files, `None` if some of them are unknown
+    pub unpacked_size: Option<u64>,
+
+    pub filesystems: Vec<PlannedFilesystem>
+}
+
+impl StoragePlan {
+    /// Plan downloading of the archives to the temp folder and unpacking them to the target folders
+    ///
+    /// Archives are unpacked while they're still in the temp folder,
+    /// so if both folders are on the same filesystem it needs space for both of them.
+    /// Unknown unpacked sizes are considered equal to the downloaded ones
+    #[inline]
+    pub fn new(temp: &Path, items: impl IntoIterator<Item = (StorageUsage, PathBuf, Option<u64>, Option<u64>)>) -> Self {
+        Self::with_filesystem(temp, items, &SystemFilesystem)
+    }
+
+    pub(crate) fn with_filesystem(temp: &Path, items: impl IntoIterator<Item = (StorageUsage, PathBuf, Option<u64>, Option<u64>)>, filesystem: &dyn Filesystem) -> Self {
+        let mut plan = Self {
+            download_size: Some(0),
+            unpacked_size: Some(0),
+            filesystems: Vec::new()
+        };
+
+        for (usage, path, downloaded, unpacked) in items {
+            plan.download_size = plan.download_size.zip(downloaded).map(|(a, b)| a + b);
+            plan.unpacked_size = plan.unpacked_size.zip(unpacked).map(|(a, b)| a + b);
+
+            plan.add(StorageUsage::Temp, temp, downloaded.unwrap_or(0), filesystem);
+
+            if usage != StorageUsage::Temp {
+                plan.add(usage, &path, unpacked.or(downloaded).unwrap_or(0), filesystem);
+            }
+        }
+
+        plan
+    }
+
+    fn add(&mut self, usage: StorageUsage, path: &Path, size: u64, filesystem: &dyn Filesystem) {
+        let parent = move_files::get_existing_parent(path).unwrap_or(path);
+
+        let planned = self.filesystems.iter_mut().find(|planned| {
+            planned.folders.iter().any(|(_, folder)| {
+                filesystem.is_same_disk(parent, move_files::get_existing_parent(folder).unwrap_or(folder))
+            })
+        });
+
+        match planned {
+            Some(planned) => {
+                if !planned.folders.iter().any(|(folder_usage, folder)| *folder_usage == usage && folder == path) {
+                    planned.folders.push((usage, path.to_path_buf()));
+                }
+
+                planned.required += size;
+            }
+
+            None => self.filesystems.push(PlannedFilesystem {
+                folders: vec![(usage, path.to_path_buf())],
+                required: size,
+                available: filesystem.available(parent)
+            })
+        }
+    }
+
+    /// Plan installation of the game or voiceover diffs to the game folder
+    pub fn for_diffs<'a>(config: &Schema, diffs: impl IntoIterator<Item = &'a VersionDiff>) -> Self {
+        let game_path = config.game.path.for_edition(config.launcher.edition).to_path_buf();
+
+        Self::new(&get_temp_folder(config), diffs.into_iter().map(|diff| {
+            (StorageUsage::Game, game_path.clone(), diff.downloaded_size(), diff.unpacked_size())
+        }))
+    }
+
+    /// Plan pre-downloading of the diffs. They're only downloaded to the temp folder
+    pub fn for_predownload<'a>(config: &Schema, diffs: impl IntoIterator<Item = &'a VersionDiff>) -> Self {
+        let temp = get_temp_folder(config);
+
+        Self::new(&temp, diffs.into_iter().map(|diff| {
+            (StorageUsage::Temp, temp.clone(), diff.downloaded_size(), Some(0))
+        }))
+    }
+
+    /// Plan installation of wine or DXVK builds from their archives' URIs
+    ///
+    /// Sends a request to get every archive's size
+    pub fn for_components<'a>(config: &Schema, components: impl IntoIterator<Item = (StorageUsage, &'a str)>) -> Self {
+        Self::new(&get_temp_folder(config), components.into_iter().map(|(usage, uri)| {
+            let folder = match usage {
+                StorageUsage::DxvkBuilds => config.game.dxvk.builds.clone(),
+                _ => config.game.wine.builds.clone()
+            };
+
+            let size = Downloader::new(uri).ok()
+                .and_then(|downloader| downloader.length());
+
+            (usage, folder, size, None)
+        }))
+    }
+
+    /// Get the worst status of the planned filesystems
+    ///
+    /// Status is unknown if some of the downloaded sizes are unknown
+    pub fn status(&self) -> StorageStatus {
+        let status = self.filesystems.iter()
+            .map(PlannedFilesystem::status)
+            .max()
+            .unwrap_or(StorageStatus::Enough);
+
+        if self.download_size.is_none() {
+            status.max(StorageStatus::Unknown)
+        } else {
+            status
+        }
+    }
+}
+
+/// Get folder archives are downloaded to
+pub fn get_temp_folder(config: &Schema) -> PathBuf {
+    config.launcher.temp.clone().unwrap_or_else(std::env::temp_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    /// Create empty temp folder for the test
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aagl-storage-plan-test-{name}-{}", std::process::id()));
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    /// Filesystem with `disk` subfolder of the temp folder mounted as another disk
+    fn other_disk(temp: &Path, available: Option<u64>) -> move_files::OtherDisk {
+        std::fs::create_dir_all(temp.join("disk")).unwrap();
+
+        move_files::OtherDisk {
+            root: temp.join("disk"),
+            available,
+            corrupt: false
+        }
+    }
+
+    fn filesystem(required: u64, available: Option<u64>) -> PlannedFilesystem {
+        PlannedFilesystem {
+            folders: Vec::new(),
+            required,
+            available
+        }
+    }
+
+    #[test]
+    fn filesystem_status() {
+        assert_eq!(filesystem(GIB, Some(3 * GIB)).status(), StorageStatus::Enough);
+        assert_eq!(filesystem(GIB, Some(GIB + LOW_SPACE_MARGIN)).status(), StorageStatus::Enough);
+        assert_eq!(filesystem(GIB, Some(GIB + LOW_SPACE_MARGIN - 1)).status(), StorageStatus::Low);
+        assert_eq!(filesystem(GIB, Some(GIB)).status(), StorageStatus::Low);
+        assert_eq!(filesystem(GIB, Some(GIB - 1)).status(), StorageStatus::NotEnough);
+        assert_eq!(filesystem(GIB, None).status(), StorageStatus::Unknown);
+    }
+
+    #[test]
+    fn plan_status() {
+        let mut plan = StoragePlan {
+            download_size: Some(GIB),
+            unpacked_size: Some(GIB),
+            filesystems: vec![filesystem(GIB, Some(10 * GIB))]
+        };
+
+        assert_eq!(plan.status(), StorageStatus::Enough);
+
+        // Worst filesystem is reported
+        plan.filesystems.push(filesystem(GIB, None));
+
+        assert_eq!(plan.status(), StorageStatus::Unknown);
+
+        plan.filesystems.push(filesystem(GIB, Some(GIB / 2)));
+
+        assert_eq!(plan.status(), StorageStatus::NotEnough);
+
+        // Unknown download size makes enough space unknown
+        let plan = StoragePlan {
+            download_size: None,
+            unpacked_size: None,
+            filesystems: vec![filesystem(GIB, Some(10 * GIB))]
+        };
+
+        assert_eq!(plan.status(), StorageStatus::Unknown);
+    }
+
+    #[test]
+    fn plan_same_disk() {
+        let temp = temp_folder("same-disk");
+        let filesystem = other_disk(&temp, Some(10 * GIB));
+
+        // Both temp and game folders are on the other disk, and the game folder doesn't exist yet
+        let plan = StoragePlan::with_filesystem(&temp.join("disk/temp"), [
+            (StorageUsage::Game, temp.join("disk/game"), Some(GIB), Some(2 * GIB)),
+            (StorageUsage::Game, temp.join("disk/game"), Some(GIB), None)
+        ], &filesystem);
+
+        assert_eq!(plan.download_size, Some(2 * GIB));
+        assert_eq!(plan.unpacked_size, None);
+
+        assert_eq!(plan.filesystems, [PlannedFilesystem {
+            folders: vec![
+                (StorageUsage::Temp, temp.join("disk/temp")),
+                (StorageUsage::Game, temp.join("disk/game"))
+            ],
+
+            // Unknown unpacked size is considered equal to the downloaded one
+            required: 2 * GIB + 3 * GIB,
+            available: Some(10 * GIB)
+        }]);
+
+        assert_eq!(plan.status(), StorageStatus::Enough);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn plan_other_disk() {
+        let temp = temp_folder("other-disk");
+        let filesystem = other_disk(&temp, Some(GIB));
+
+        let plan = StoragePlan::with_filesystem(&temp.join("temp"), [
+            (StorageUsage::WineBuilds, temp.join("disk/runners"), Some(GIB / 2), None),
+            (StorageUsage::DxvkBuilds, temp.join("disk/dxvks"), None, None)
+        ], &filesystem);
+
+        assert_eq!(plan.download_size, None);
+        assert_eq!(plan.filesystems.len(), 2);
+
+        assert_eq!(plan.filesystems[0].folders, [(StorageUsage::Temp, temp.join("temp"))]);
+        assert_eq!(plan.filesystems[0].required, GIB / 2);
+
+        assert_eq!(plan.filesystems[1].folders, [
+            (StorageUsage::WineBuilds, temp.join("disk/runners")),
+            (StorageUsage::DxvkBuilds, temp.join("disk/dxvks"))
+        ]);
+
+        assert_eq!(plan.filesystems[1].required, GIB / 2);
+        assert_eq!(plan.filesystems[1].available, Some(GIB));
+        assert_eq!(plan.filesystems[1].status(), StorageStatus::Low);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn plan_predownload() {
+        let temp = temp_folder("predownload");
+        let filesystem = other_disk(&temp, Some(GIB));
+
+        // Pre-downloaded archives are only stored in the temp folder
+        let plan = StoragePlan::with_filesystem(&temp.join("disk/temp"), [
+            (StorageUsage::Temp, temp.join("disk/temp"), Some(2 * GIB), Some(0))
+        ], &filesystem);
+
+        assert_eq!(plan.filesystems, [PlannedFilesystem {
+            folders: vec![(StorageUsage::Temp, temp.join("disk/temp"))],
+            required: 2 * GIB,
+            available: Some(GIB)
+        }]);
+
+        assert_eq!(plan.status(), StorageStatus::NotEnough);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+}
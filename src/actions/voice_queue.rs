@@ -0,0 +1,362 @@
+use std::time::Duration;
+use std::sync::mpsc::RecvTimeoutError;
+
+use anime_launcher_sdk::config::ConfigExt;
+use anime_launcher_sdk::genshin::config::{Config, Schema};
+
+use anime_launcher_sdk::anime_game_core::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::voice_data::package::get_voice_package_path;
+
+use crate::move_files;
+
+use super::download_diff::download_diff;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceOperationKind {
+    Install,
+    Update,
+    Remove
+}
+
+/// Progress of the running voiceover operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceProgress {
+    Downloading {
+        current: u64,
+        total: u64
+    },
+
+    Unpacking {
+        current: u64,
+        total: u64
+    },
+
+    Removing {
+        current: u64,
+        total: u64
+    }
+}
+
+/// Pending voiceover installation, update or removal
+#[derive(Debug, Clone)]
+pub struct VoiceOperation {
+    pub kind: VoiceOperationKind,
+    pub locale: VoiceLocale,
+
+    /// Downloaded archive size for installation and update,
+    /// or size of the installed files for removal. `None` if unknown
+    pub size: Option<u64>,
+
+    /// Diff to download. `None` if the voiceover's files are already in the game folder
+    pub diff: Option<VersionDiff>
+}
+
+impl VoiceOperation {
+    /// Plan installation or update of the voiceover. Requests its diff from the API
+    ///
+    /// Returns `None` if the voiceover is already installed, selected in the config and up to date
+    pub fn install(config: &Schema, locale: VoiceLocale) -> anyhow::Result<Option<Self>> {
+        let edition = config.launcher.edition;
+        let game_path = config.game.path.for_edition(edition).to_path_buf();
+
+        let mut package = VoicePackage::with_locale(locale, edition)?;
+
+        if package.is_installed_in(&game_path) {
+            if let Some(installed) = VoicePackage::new(get_voice_package_path(&game_path, edition, locale), edition) {
+                package = installed;
+            }
+        }
+
+        let selected = config.game.voices.iter()
+            .any(|voice| VoiceLocale::from_str(voice) == Some(locale));
+
+        let kind = if selected {
+            VoiceOperationKind::Update
+        } else {
+            VoiceOperationKind::Install
+        };
+
+        let diff = match package.try_get_diff()? {
+            diff @ (VersionDiff::NotInstalled { .. } |
+                    VersionDiff::Diff { .. } |
+                    VersionDiff::Outdated { .. }) => Some(diff),
+
+            // Files are already in the game folder so it only should be selected in the config
+            _ if !selected => None,
+
+            _ => return Ok(None)
+        };
+
+        Ok(Some(Self {
+            kind,
+            locale,
+            size: diff.as_ref().map(|diff| diff.downloaded_size()).unwrap_or(Some(0)),
+            diff
+        }))
+    }
+
+    /// Plan removal of the voiceover
+    pub fn remove(config: &Schema, locale: VoiceLocale) -> Self {
+        let edition = config.launcher.edition;
+        let path = get_voice_package_path(config.game.path.for_edition(edition), edition, locale);
+
+        Self {
+            kind: VoiceOperationKind::Remove,
+            locale,
+            size: move_files::get_size(&path).ok(),
+            diff: None
+        }
+    }
+
+    /// Amount of bytes which will be downloaded by this operation
+    pub fn download_size(&self) -> u64 {
+        match self.kind {
+            VoiceOperationKind::Remove => 0,
+            _ => self.size.unwrap_or(0)
+        }
+    }
+
+    /// Amount of work this operation does, used to calculate combined progress of the queue
+    ///
+    /// Installation downloads and then unpacks the archive, so its size is counted twice
+    pub fn work_size(&self) -> u64 {
+        match self.kind {
+            VoiceOperationKind::Remove => self.size.unwrap_or(0),
+            _ => self.size.unwrap_or(0) * 2
+        }
+    }
+
+    /// Amount of work done by this operation with the given progress. Never exceeds `work_size`
+    pub fn work_done(&self, progress: VoiceProgress) -> u64 {
+        let size = self.size.unwrap_or(0);
+
+        let scale = |current: u64, total: u64| {
+            (current.min(total) as f64 / total.max(1) as f64 * size as f64) as u64
+        };
+
+        match progress {
+            VoiceProgress::Downloading { current, total } => scale(current, total),
+            VoiceProgress::Unpacking { current, total } => size + scale(current, total),
+            VoiceProgress::Removing { current, total } => scale(current, total)
+        }
+    }
+}
+
+/// Ordered list of the pending voiceovers operations. Every voiceover can have only one operation
+#[derive(Debug, Clone, Default)]
+pub struct VoiceQueue {
+    operations: Vec<VoiceOperation>
+}
+
+impl VoiceQueue {
+    #[inline]
+    pub fn operations(&self) -> &[VoiceOperation] {
+        &self.operations
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn get(&self, locale: VoiceLocale) -> Option<&VoiceOperation> {
+        self.operations.iter().find(|operation| operation.locale == locale)
+    }
+
+    /// Add operation to the end of the queue, or replace the pending operation of the same voiceover
+    ///
+    /// Installation and removal of the same voiceover cancel each other
+    pub fn push(&mut self, operation: VoiceOperation) {
+        let Some(i) = self.operations.iter().position(|pending| pending.locale == operation.locale) else {
+            self.operations.push(operation);
+
+            return;
+        };
+
+        let cancelled = match (self.operations[i].kind, operation.kind) {
+            (VoiceOperationKind::Install, VoiceOperationKind::Remove) => true,
+            (VoiceOperationKind::Remove, VoiceOperationKind::Install | VoiceOperationKind::Update) => true,
+
+            _ => false
+        };
+
+        if cancelled {
+            self.operations.remove(i);
+        } else {
+            self.operations[i] = operation;
+        }
+    }
+
+    /// Remove operation from the queue
+    pub fn cancel(&mut self, index: usize) -> Option<VoiceOperation> {
+        if index < self.operations.len() {
+            Some(self.operations.remove(index))
+        } else {
+            None
+        }
+    }
+
+    pub fn move_up(&mut self, index: usize) {
+        if index > 0 && index < self.operations.len() {
+            self.operations.swap(index - 1, index);
+        }
+    }
+
+    pub fn move_down(&mut self, index: usize) {
+        if index + 1 < self.operations.len() {
+            self.operations.swap(index, index + 1);
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.operations.clear();
+    }
+
+    /// Take the first operation to process it
+    pub fn pop_front(&mut self) -> Option<VoiceOperation> {
+        self.cancel(0)
+    }
+
+    /// Total amount of bytes which will be downloaded by the queue
+    pub fn download_size(&self) -> u64 {
+        self.operations.iter().map(VoiceOperation::download_size).sum()
+    }
+
+    /// Total amount of work the queue does
+    pub fn work_size(&self) -> u64 {
+        self.operations.iter().map(VoiceOperation::work_size).sum()
+    }
+}
+
+/// Interval of the removed voiceover's size checks
+const REMOVING_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Perform voiceover operation and update voiceovers list in the config
+pub fn process(operation: &VoiceOperation, updater: impl Fn(VoiceProgress) + Clone + Send + 'static) -> anyhow::Result<()> {
+    match operation.kind {
+        VoiceOperationKind::Install |
+        VoiceOperationKind::Update => {
+            if let Some(diff) = operation.diff.clone() {
+                download_diff(&Config::get()?, diff, move |update| {
+                    match update {
+                        DiffUpdate::InstallerUpdate(InstallerUpdate::DownloadingProgress(current, total)) => {
+                            updater(VoiceProgress::Downloading { current, total });
+                        }
+
+                        DiffUpdate::InstallerUpdate(InstallerUpdate::UnpackingProgress(current, total)) => {
+                            updater(VoiceProgress::Unpacking { current, total });
+                        }
+
+                        _ => ()
+                    }
+                })?;
+            }
+
+            // Config could be changed while the voiceover was downloading
+            let mut config = Config::get()?;
+
+            if !config.game.voices.iter().any(|voice| VoiceLocale::from_str(voice) == Some(operation.locale)) {
+                config.game.voices.push(operation.locale.to_code().to_string());
+            }
+
+            crate::config_history::update_raw(config)?;
+        }
+
+        VoiceOperationKind::Remove => {
+            let mut config = Config::get()?;
+
+            config.game.voices.retain(|voice| VoiceLocale::from_str(voice) != Some(operation.locale));
+
+            crate::config_history::update_raw(config.clone())?;
+
+            let package = VoicePackage::with_locale(operation.locale, config.launcher.edition)?;
+            let game_path = config.game.path.for_edition(config.launcher.edition).to_path_buf();
+
+            if package.is_installed_in(&game_path) {
+                let path = get_voice_package_path(&game_path, config.launcher.edition, operation.locale);
+                let total = operation.size.unwrap_or(0);
+
+                // Package is removed at once so its remaining size is checked meanwhile
+                let (sender, receiver) = std::sync::mpsc::channel::<()>();
+
+                let watcher = std::thread::spawn(move || {
+                    while let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(REMOVING_PROGRESS_INTERVAL) {
+                        let remaining = move_files::get_size(&path).unwrap_or(0);
+
+                        updater(VoiceProgress::Removing {
+                            current: total.saturating_sub(remaining),
+                            total
+                        });
+                    }
+                });
+
+                let result = package.delete_in(game_path);
+
+                drop(sender);
+
+                let _ = watcher.join();
+
+                result?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Create empty temp folder for the test
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aagl-voice-queue-test-{name}-{}", std::process::id()));
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn removal_plan() {
+        let path = temp_folder("remove");
+
+        let mut config = Schema::default();
+
+        config.game.path.global = path.join("game");
+        config.game.path.china = path.join("game-china");
+
+        let voice = get_voice_package_path(config.game.path.for_edition(config.launcher.edition), config.launcher.edition, VoiceLocale::English);
+
+        std::fs::create_dir_all(&voice).unwrap();
+        std::fs::write(voice.join("file"), "voice").unwrap();
+
+        let operation = VoiceOperation::remove(&config, VoiceLocale::English);
+
+        assert_eq!(operation.kind, VoiceOperationKind::Remove);
+        assert_eq!(operation.size, Some(5));
+        assert_eq!(operation.download_size(), 0);
+        assert_eq!(operation.work_size(), 5);
+        assert_eq!(operation.work_done(VoiceProgress::Removing { current: 2, total: 5 }), 2);
+
+        // Installation is counted twice as its archive is downloaded and then unpacked
+        let operation = VoiceOperation {
+            kind: VoiceOperationKind::Install,
+            ..operation
+        };
+
+        assert_eq!(operation.work_size(), 10);
+        assert_eq!(operation.work_done(VoiceProgress::Downloading { current: 50, total: 100 }), 2);
+        assert_eq!(operation.work_done(VoiceProgress::Unpacking { current: 100, total: 100 }), 10);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -1,23 +1,283 @@
+use std::path::{Path, PathBuf};
+use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Deserialize};
+
 use anime_launcher_sdk::anime_game_core::installer::downloader::Downloader;
 use anime_launcher_sdk::anime_game_core::minreq;
+use anime_launcher_sdk::anime_game_core::genshin::consts::GameEdition;
+
+use anime_launcher_sdk::config::ConfigExt;
+use anime_launcher_sdk::genshin::config::Config;
+
+use gtk::gdk_pixbuf::{Pixbuf, InterpType};
 
 use md5::{Md5, Digest};
 
-#[derive(Debug, Clone)]
+/// Extensions of the pictures used by the `Folder` background source
+pub const PICTURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "bmp"];
+
+/// The picture is scaled down this many times and back to blur it
+const BLUR_FACTOR: i32 = 24;
+
+/// Seconds the background info fetched from the API is used before requesting it again
+const INFO_CACHE_EXPIRY: u64 = 6 * 60 * 60;
+
+/// Seconds before the next API request after a failed one. Doubled with every next failure
+const FAILURE_BACKOFF: u64 = 5 * 60;
+
+/// Max seconds between the API requests after failures
+const MAX_FAILURE_BACKOFF: u64 = 24 * 60 * 60;
+
+thread_local! {
+    /// Provider of the window background style, created on the first `apply_css` call
+    static CSS_PROVIDER: RefCell<Option<gtk::CssProvider>> = RefCell::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BackgroundSource {
+    /// Picture from the official launcher API
+    #[default]
+    Official,
+
+    /// Local picture file
+    File,
+
+    /// Slideshow of the pictures from a local folder
+    Folder
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackgroundRotation {
+    /// Show next picture on every launcher start
+    Launch,
+
+    /// Show next picture every given amount of minutes
+    Interval(u64)
+}
+
+impl Default for BackgroundRotation {
+    #[inline]
+    fn default() -> Self {
+        Self::Launch
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BackgroundFit {
+    /// Crop the picture to fill the whole window
+    #[default]
+    Cover,
+
+    /// Show the whole picture
+    Contain
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackgroundSettings {
+    pub source: BackgroundSource,
+
+    /// Picture used by the `File` source
+    pub file: Option<PathBuf>,
+
+    /// Folder used by the `Folder` source
+    pub folder: Option<PathBuf>,
+
+    pub rotation: BackgroundRotation,
+    pub fit: BackgroundFit,
+    pub blur: bool
+}
+
+impl BackgroundSettings {
+    /// Get stored background settings, or default ones if they weren't set
+    pub fn get() -> anyhow::Result<Self> {
+        if !crate::BACKGROUND_SETTINGS_FILE.exists() {
+            return Ok(Self::default());
+        }
+
+        let settings = std::fs::read(crate::BACKGROUND_SETTINGS_FILE.as_path())?;
+
+        Ok(serde_json::from_slice(&settings)?)
+    }
+
+    pub fn set(&self) -> anyhow::Result<()> {
+        std::fs::write(crate::BACKGROUND_SETTINGS_FILE.as_path(), serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+}
+
+/// Background API and files used to cache its info and download the picture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackgroundEndpoint {
+    /// API uri the background info is requested from
+    pub uri: String,
+
+    /// File storing info of the latest API request
+    pub cache_file: PathBuf,
+
+    /// File the picture is downloaded to before its hash is verified
+    pub download_file: PathBuf,
+
+    /// Downloaded picture shown by the launcher
+    pub picture_file: PathBuf
+}
+
+impl BackgroundEndpoint {
+    /// Official API of the currently selected game edition
+    pub fn official() -> Self {
+        Self {
+            uri: get_uri(),
+            cache_file: crate::BACKGROUND_CACHE_FILE.clone(),
+            download_file: crate::BACKGROUND_DOWNLOAD_FILE.clone(),
+            picture_file: crate::BACKGROUND_FILE.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Background {
     pub uri: String,
     pub hash: String
 }
 
+/// Background info of the latest API request, stored on disk
+/// so the API isn't requested on every launch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct BackgroundCache {
+    /// API uri the info was requested from
+    uri: String,
+
+    /// Info of the last successful request
+    info: Option<Background>,
+
+    /// Timestamp of the last successful request
+    fetched_at: u64,
+
+    /// Amount of failed requests in a row
+    failures: u32,
+
+    /// Timestamp of the last failed request
+    failed_at: u64
+}
+
+impl BackgroundCache {
+    /// Read cache of the endpoint's API uri, or create an empty one
+    fn read(endpoint: &BackgroundEndpoint) -> Self {
+        std::fs::read(&endpoint.cache_file).ok()
+            .and_then(|cache| serde_json::from_slice::<Self>(&cache).ok())
+            .filter(|cache| cache.uri == endpoint.uri)
+            .unwrap_or_else(|| Self {
+                uri: endpoint.uri.clone(),
+                ..Self::default()
+            })
+    }
+
+    fn write(&self, endpoint: &BackgroundEndpoint) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(anyhow::Error::from)
+            .and_then(|cache| {
+                if let Some(folder) = endpoint.cache_file.parent() {
+                    std::fs::create_dir_all(folder)?;
+                }
+
+                std::fs::write(&endpoint.cache_file, cache)?;
+
+                Ok(())
+            });
+
+        if let Err(err) = result {
+            tracing::warn!("Failed to save background info cache: {err}");
+        }
+    }
+
+    /// Seconds to wait after the last failed request
+    fn backoff(&self) -> u64 {
+        FAILURE_BACKOFF.saturating_mul(1 << self.failures.saturating_sub(1).min(16))
+            .min(MAX_FAILURE_BACKOFF)
+    }
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default()
+}
+
 pub fn get_uri() -> String {
-    let uri = concat!("https://sdk-os-static.", "ho", "yo", "verse", ".com/hk4e_global/mdk/launcher/api/content?filter_adv=true&key=gcStgarh&launcher_id=10&language=");
+    let edition = Config::get()
+        .map(|config| config.launcher.edition)
+        .unwrap_or_else(|_| crate::CONFIG.launcher.edition);
+
+    let uri = match edition {
+        GameEdition::Global => concat!("https://sdk-os-static.", "ho", "yo", "verse", ".com/hk4e_global/mdk/launcher/api/content?filter_adv=true&key=gcStgarh&launcher_id=10&language="),
+        GameEdition::China  => concat!("https://sdk-static.", "mi", "ho", "yo", ".com/hk4e_cn/mdk/launcher/api/content?filter_adv=true&key=eYd89JmJ&launcher_id=18&language=")
+    };
 
     uri.to_owned() + &crate::i18n::format_lang(&crate::i18n::get_lang())
 }
 
-#[cached::proc_macro::cached(result)]
-pub fn get_background_info() -> anyhow::Result<Background> {
-    let json = serde_json::from_slice::<serde_json::Value>(minreq::get(get_uri()).send()?.as_bytes())?;
+/// Get background picture info from the endpoint's API
+///
+/// The API response is cached on disk for `INFO_CACHE_EXPIRY` seconds.
+/// Failed requests aren't repeated for an increasing time,
+/// and the last successfully fetched info is returned meanwhile
+pub fn get_background_info(endpoint: &BackgroundEndpoint) -> anyhow::Result<Background> {
+    let now = timestamp();
+
+    let mut cache = BackgroundCache::read(endpoint);
+
+    if let Some(info) = &cache.info {
+        if now.saturating_sub(cache.fetched_at) < INFO_CACHE_EXPIRY {
+            return Ok(info.clone());
+        }
+    }
+
+    if cache.failures > 0 && now < cache.failed_at + cache.backoff() {
+        tracing::debug!("Background API request is postponed after {} failures", cache.failures);
+
+        return match cache.info {
+            Some(info) => Ok(info),
+            None => anyhow::bail!("Background API is unavailable")
+        };
+    }
+
+    match fetch_background_info(&endpoint.uri) {
+        Ok(info) => {
+            cache.info = Some(info.clone());
+            cache.fetched_at = now;
+            cache.failures = 0;
+
+            cache.write(endpoint);
+
+            Ok(info)
+        }
+
+        Err(err) => {
+            cache.failures += 1;
+            cache.failed_at = now;
+
+            cache.write(endpoint);
+
+            match cache.info {
+                Some(info) => {
+                    tracing::warn!("Failed to fetch background info, using the cached one: {err}");
+
+                    Ok(info)
+                }
+
+                None => Err(err)
+            }
+        }
+    }
+}
+
+fn fetch_background_info(uri: &str) -> anyhow::Result<Background> {
+    let json = serde_json::from_slice::<serde_json::Value>(minreq::get(uri).with_timeout(10).send()?.as_bytes())?;
 
     let uri = match json["data"]["adv"]["background"].as_str() {
         Some(uri) => uri.to_owned(),
@@ -41,28 +301,470 @@ pub fn get_background_info() -> anyhow::Result<Background> {
     })
 }
 
-pub fn download_background() -> anyhow::Result<()> {
-    tracing::debug!("Downloading background picture");
+fn get_file_hash(path: &Path) -> anyhow::Result<String> {
+    let hash = Md5::digest(std::fs::read(path)?);
+
+    Ok(format!("{:x}", hash).to_lowercase())
+}
+
+/// Download the picture and replace the endpoint's picture file with it if its hash is correct
+fn update_background_file(endpoint: &BackgroundEndpoint, info: &Background) -> anyhow::Result<()> {
+    let mut downloader = Downloader::new(&info.uri)?;
 
-    let info = get_background_info()?;
+    downloader.continue_downloading = false;
+
+    let result = downloader.download(&endpoint.download_file, |_, _| {})
+        .map_err(|err| anyhow::anyhow!(err))
+        .and_then(|_| get_file_hash(&endpoint.download_file));
+
+    match result {
+        Ok(hash) if hash == info.hash => {
+            // Both files are in the same folder so the picture is replaced atomically
+            std::fs::rename(&endpoint.download_file, &endpoint.picture_file)?;
+
+            Ok(())
+        }
+
+        Ok(hash) => {
+            std::fs::remove_file(&endpoint.download_file)?;
+
+            anyhow::bail!("Downloaded background picture has wrong hash: {hash}, expected {}", info.hash);
+        }
 
-    if crate::BACKGROUND_FILE.exists() {
-        let hash = Md5::digest(std::fs::read(crate::BACKGROUND_FILE.as_path())?);
+        Err(err) => {
+            if endpoint.download_file.exists() {
+                std::fs::remove_file(&endpoint.download_file)?;
+            }
 
-        if format!("{:x}", hash).to_lowercase() == info.hash {
+            Err(err)
+        }
+    }
+}
+
+/// Download background picture from the endpoint if it was changed
+///
+/// Errors are only returned when there's no previously downloaded picture to show
+pub fn download_background(endpoint: &BackgroundEndpoint) -> anyhow::Result<()> {
+    tracing::debug!("Downloading background picture");
+
+    let result = get_background_info(endpoint).and_then(|info| {
+        if endpoint.picture_file.exists() && get_file_hash(&endpoint.picture_file)? == info.hash {
             tracing::debug!("Background picture is already downloaded. Skipping");
 
             return Ok(());
         }
+
+        update_background_file(endpoint, &info)
+    });
+
+    match result {
+        Err(err) if endpoint.picture_file.exists() => {
+            tracing::warn!("Failed to update background picture, using the last downloaded one: {err}");
+
+            Ok(())
+        }
+
+        result => result
     }
+}
 
-    let mut downloader = Downloader::new(info.uri)?;
+/// Get pictures of the given folder sorted by their names
+pub fn get_folder_pictures(folder: impl AsRef<Path>) -> anyhow::Result<Vec<PathBuf>> {
+    let mut pictures = std::fs::read_dir(folder)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .map(|ext| PICTURE_EXTENSIONS.contains(&ext.to_string_lossy().to_ascii_lowercase().as_str()))
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
 
-    downloader.continue_downloading = false;
+    pictures.sort();
+
+    Ok(pictures)
+}
+
+/// Get picture of the slideshow which is shown now
+fn get_current_slide(folder: &Path) -> Option<PathBuf> {
+    let current = std::fs::read_to_string(crate::BACKGROUND_SLIDE_FILE.as_path()).ok()?;
+    let current = folder.join(current.trim());
 
-    if let Err(err) = downloader.download(crate::BACKGROUND_FILE.as_path(), |_, _| {}) {
-        anyhow::bail!(err);
+    current.exists().then_some(current)
+}
+
+/// Switch the slideshow to the next picture
+fn next_slide(folder: &Path) -> anyhow::Result<PathBuf> {
+    let pictures = get_folder_pictures(folder)?;
+
+    if pictures.is_empty() {
+        anyhow::bail!("No pictures found in {:?}", folder);
+    }
+
+    let next = match get_current_slide(folder) {
+        Some(current) => pictures.iter()
+            .position(|picture| picture == &current)
+            .map(|i| (i + 1) % pictures.len())
+            .unwrap_or_default(),
+
+        None => 0
+    };
+
+    let next = pictures[next].clone();
+
+    if let Some(name) = next.file_name() {
+        std::fs::write(crate::BACKGROUND_SLIDE_FILE.as_path(), name.to_string_lossy().as_bytes())?;
+    }
+
+    Ok(next)
+}
+
+/// Get background picture of the current game session, if it has one
+fn get_session_picture() -> Option<PathBuf> {
+    match crate::session_profiles::current() {
+        Ok(Some((_, profile))) => profile.background.filter(|picture| picture.exists()),
+
+        Ok(None) => None,
+
+        Err(err) => {
+            tracing::warn!("Failed to get current session profile: {err}");
+
+            None
+        }
+    }
+}
+
+/// Get picture which should be shown with the given settings without downloading or generating anything
+fn get_source_picture(settings: &BackgroundSettings) -> Option<PathBuf> {
+    if let Some(picture) = get_session_picture() {
+        return Some(picture);
+    }
+
+    match settings.source {
+        BackgroundSource::Official => Some(crate::BACKGROUND_FILE.clone()),
+        BackgroundSource::File => settings.file.clone(),
+        BackgroundSource::Folder => settings.folder.as_deref().and_then(get_current_slide)
+    }
+}
+
+/// Save blurred copy of the picture
+fn blur(picture: &Path, output: &Path) -> anyhow::Result<()> {
+    let pixbuf = Pixbuf::from_file(picture)?;
+
+    let width = pixbuf.width();
+    let height = pixbuf.height();
+
+    // Bilinear upscaling of the small copy smoothes it out
+    let blurred = pixbuf.scale_simple((width / BLUR_FACTOR).max(1), (height / BLUR_FACTOR).max(1), InterpType::Bilinear)
+        .and_then(|pixbuf| pixbuf.scale_simple(width, height, InterpType::Bilinear));
+
+    let Some(blurred) = blurred else {
+        anyhow::bail!("Failed to blur background picture");
+    };
+
+    if let Some(folder) = output.parent() {
+        std::fs::create_dir_all(folder)?;
+    }
+
+    blurred.savev(output, "png", &[])?;
+
+    Ok(())
+}
+
+/// Prepare background picture according to the background settings
+///
+/// Downloads the official picture, switches the slideshow if `next` is set
+/// and blurs the picture if needed. Use `get_css` to show it then
+pub fn prepare(next: bool) -> anyhow::Result<()> {
+    let settings = BackgroundSettings::get()?;
+
+    let picture = match get_session_picture() {
+        Some(picture) => picture,
+
+        None => match settings.source {
+            BackgroundSource::Official => {
+                if !crate::KEEP_BACKGROUND_FILE.exists() {
+                    download_background(&BackgroundEndpoint::official())?;
+                }
+
+                crate::BACKGROUND_FILE.clone()
+            }
+
+            BackgroundSource::File => match &settings.file {
+                Some(file) => file.clone(),
+                None => anyhow::bail!("Background picture is not selected")
+            }
+
+            BackgroundSource::Folder => {
+                let Some(folder) = &settings.folder else {
+                    anyhow::bail!("Background pictures folder is not selected");
+                };
+
+                match get_current_slide(folder) {
+                    Some(current) if !next => current,
+
+                    _ => next_slide(folder)?
+                }
+            }
+        }
+    };
+
+    if settings.blur {
+        blur(&picture, &crate::BLURRED_BACKGROUND_FILE)?;
     }
 
     Ok(())
 }
+
+/// Get css of the classic launcher style's window background
+pub fn get_css() -> String {
+    let settings = BackgroundSettings::get().unwrap_or_else(|err| {
+        tracing::error!("Failed to read background settings: {err}");
+
+        BackgroundSettings::default()
+    });
+
+    let picture = if settings.blur {
+        Some(crate::BLURRED_BACKGROUND_FILE.clone())
+    } else {
+        get_source_picture(&settings)
+    };
+
+    let size = match settings.fit {
+        BackgroundFit::Cover => "cover",
+        BackgroundFit::Contain => "contain"
+    };
+
+    format!("
+        window.classic-style {{
+            background: url(\"file://{}\");
+            background-repeat: no-repeat;
+            background-position: center;
+            background-size: {size};
+        }}
+    ", picture.unwrap_or_else(|| crate::BACKGROUND_FILE.clone()).to_string_lossy())
+}
+
+/// Show background picture in the classic style windows, or reload it if it was changed
+///
+/// The same css provider is updated on every call. Must be called from the GTK thread
+pub fn apply_css() {
+    CSS_PROVIDER.with(|provider| {
+        let mut provider = provider.borrow_mut();
+
+        let provider = provider.get_or_insert_with(|| {
+            let provider = gtk::CssProvider::new();
+
+            if let Some(display) = gtk::gdk::Display::default() {
+                gtk::style_context_add_provider_for_display(&display, &provider, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION);
+            }
+
+            provider
+        });
+
+        provider.load_from_data(&get_css());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    const PICTURE: &[u8] = b"picture";
+
+    /// Create empty temp folder for the test
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aagl-background-test-{name}-{}", std::process::id()));
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    /// Run HTTP server answering requests with the `handler`'s status and body
+    ///
+    /// Returns server's address and amount of the API requests (`/api` path) it got
+    fn serve(handler: impl Fn(&str) -> (u16, Vec<u8>) + Send + 'static) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+
+                // Read request headers
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n])
+                    }
+                }
+
+                let request = String::from_utf8_lossy(&request);
+
+                let mut line = request.split_whitespace();
+
+                let method = line.next().unwrap_or_default();
+                let path = line.next().unwrap_or_default();
+
+                if path.starts_with("/api") {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+
+                let (status, body) = handler(path);
+
+                let _ = write!(stream, "HTTP/1.1 {status} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+
+                if method != "HEAD" {
+                    let _ = stream.write_all(&body);
+                }
+            }
+        });
+
+        (address, requests)
+    }
+
+    /// Run background API returning picture with the given hash in its name
+    fn serve_api(hash: &str) -> (String, Arc<AtomicUsize>) {
+        let (pictures, _) = serve(|_| (200, PICTURE.to_vec()));
+
+        let picture = format!("{pictures}/{hash}_background.png");
+
+        serve(move |_| {
+            (200, serde_json::json!({ "data": { "adv": { "background": picture } } }).to_string().into_bytes())
+        })
+    }
+
+    fn get_endpoint(path: &Path, address: &str) -> BackgroundEndpoint {
+        BackgroundEndpoint {
+            uri: format!("{address}/api"),
+            cache_file: path.join("cache.json"),
+            download_file: path.join(".background.download"),
+            picture_file: path.join("background")
+        }
+    }
+
+    fn picture_hash() -> String {
+        format!("{:x}", Md5::digest(PICTURE))
+    }
+
+    #[test]
+    fn cache_hit() {
+        let path = temp_folder("cache");
+
+        let (address, requests) = serve_api(&picture_hash());
+        let endpoint = get_endpoint(&path, &address);
+
+        let info = get_background_info(&endpoint).unwrap();
+
+        assert_eq!(info.hash, picture_hash());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Cached info is used until it's expired
+        let cached = get_background_info(&endpoint).unwrap();
+
+        assert_eq!(cached.uri, info.uri);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Cache of other uri is not used
+        let other = BackgroundEndpoint {
+            uri: format!("{address}/api?language=other"),
+            ..endpoint.clone()
+        };
+
+        get_background_info(&other).unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn failures_backoff() {
+        let path = temp_folder("backoff");
+
+        let (address, requests) = serve(|_| (500, b"error".to_vec()));
+        let endpoint = get_endpoint(&path, &address);
+
+        assert!(get_background_info(&endpoint).is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Request is postponed after the failure
+        assert!(get_background_info(&endpoint).is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        let mut cache = BackgroundCache::read(&endpoint);
+
+        assert_eq!(cache.failures, 1);
+        assert_eq!(cache.backoff(), FAILURE_BACKOFF);
+
+        // Request is repeated after the backoff time, and the next backoff is doubled
+        cache.failed_at -= cache.backoff() + 1;
+        cache.write(&endpoint);
+
+        assert!(get_background_info(&endpoint).is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let mut cache = BackgroundCache::read(&endpoint);
+
+        assert_eq!(cache.failures, 2);
+        assert_eq!(cache.backoff(), FAILURE_BACKOFF * 2);
+
+        // Expired info is still returned when the API is unavailable
+        cache.info = Some(Background {
+            uri: String::from("cached"),
+            hash: String::new()
+        });
+
+        cache.failures = 0;
+        cache.fetched_at = 0;
+
+        cache.write(&endpoint);
+
+        assert_eq!(get_background_info(&endpoint).unwrap().uri, "cached");
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        cache.failures = 64;
+
+        assert_eq!(cache.backoff(), MAX_FAILURE_BACKOFF);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn picture_hash_mismatch() {
+        let path = temp_folder("hash");
+
+        let (address, _) = serve_api("00000000000000000000000000000000");
+        let endpoint = get_endpoint(&path, &address);
+
+        assert!(download_background(&endpoint).is_err());
+
+        assert!(!endpoint.picture_file.exists());
+        assert!(!endpoint.download_file.exists());
+
+        // Correct picture replaces the background file
+        let (address, _) = serve_api(&picture_hash());
+        let endpoint = get_endpoint(&path, &address);
+
+        download_background(&endpoint).unwrap();
+
+        assert_eq!(std::fs::read(&endpoint.picture_file).unwrap(), PICTURE);
+        assert!(!endpoint.download_file.exists());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
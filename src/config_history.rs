@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::Value as JsonValue;
+
+use anime_launcher_sdk::config::ConfigExt;
+use anime_launcher_sdk::genshin::config::{Config, Schema};
+use anime_launcher_sdk::genshin::consts::config_file;
+
+/// Amount of config backups to keep. Older ones are removed
+pub const MAX_BACKUPS: usize = 30;
+
+/// Stored copy of the config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    pub path: PathBuf,
+
+    /// UTC timestamp of the backup creation
+    pub timestamp: u64
+}
+
+impl Backup {
+    /// Read backup's content as JSON
+    pub fn read(&self) -> anyhow::Result<JsonValue> {
+        Ok(serde_json::from_slice(&std::fs::read(&self.path)?)?)
+    }
+}
+
+/// Single difference between two config versions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added(String, String),
+    Removed(String, String),
+    Changed(String, String, String)
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added(key, value)      => write!(f, "+ {key}: {value}"),
+            Self::Removed(key, value)    => write!(f, "- {key}: {value}"),
+            Self::Changed(key, old, new) => write!(f, "{key}: {old} → {new}")
+        }
+    }
+}
+
+/// Get list of stored backups, newest first
+pub fn list() -> anyhow::Result<Vec<Backup>> {
+    if !crate::CONFIG_BACKUPS_FOLDER.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+
+    for entry in crate::CONFIG_BACKUPS_FOLDER.read_dir()?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        let timestamp = name.strip_prefix("config-")
+            .and_then(|name| name.strip_suffix(".json"))
+            .and_then(|timestamp| timestamp.parse::<u64>().ok());
+
+        if let Some(timestamp) = timestamp {
+            backups.push(Backup {
+                path: entry.path(),
+                timestamp
+            });
+        }
+    }
+
+    backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    Ok(backups)
+}
+
+/// Store copy of the current config file if it differs from the latest backup
+///
+/// Returns `None` if there was nothing to save
+pub fn backup() -> anyhow::Result<Option<Backup>> {
+    let config = config_file()?;
+
+    if !config.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read(&config)?;
+
+    // Don't store broken configs
+    serde_json::from_slice::<JsonValue>(&content)?;
+
+    let backups = list()?;
+
+    if let Some(latest) = backups.first() {
+        if std::fs::read(&latest.path).ok().as_ref() == Some(&content) {
+            return Ok(None);
+        }
+    }
+
+    if !crate::CONFIG_BACKUPS_FOLDER.exists() {
+        std::fs::create_dir_all(crate::CONFIG_BACKUPS_FOLDER.as_path())?;
+    }
+
+    let mut timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    // Two backups made within the same second
+    if let Some(latest) = backups.first() {
+        timestamp = timestamp.max(latest.timestamp + 1);
+    }
+
+    let backup = Backup {
+        path: crate::CONFIG_BACKUPS_FOLDER.join(format!("config-{timestamp}.json")),
+        timestamp
+    };
+
+    std::fs::write(&backup.path, content)?;
+
+    tracing::debug!("Created config backup: {:?}", backup.path);
+
+    for outdated in backups.iter().skip(MAX_BACKUPS - 1) {
+        std::fs::remove_file(&outdated.path)?;
+    }
+
+    Ok(Some(backup))
+}
+
+/// Back up the current config file and write the given config
+///
+/// Should be used instead of `Config::update_raw` so every config change could be rolled back
+pub fn update_raw(config: Schema) -> anyhow::Result<()> {
+    if let Err(err) = backup() {
+        tracing::warn!("Failed to backup config: {err}");
+    }
+
+    Config::update_raw(config)
+}
+
+/// Back up the current config file and write the in-memory config
+///
+/// Should be used instead of `Config::flush` so every config change could be rolled back
+pub fn flush() -> anyhow::Result<()> {
+    if let Err(err) = backup() {
+        tracing::warn!("Failed to backup config: {err}");
+    }
+
+    Config::flush()
+}
+
+/// Replace current config with the given backup
+///
+/// Current config is backed up before, if it's parsable
+pub fn restore(backup: &Backup) -> anyhow::Result<()> {
+    tracing::info!("Restoring config backup: {:?}", backup.path);
+
+    if let Err(err) = self::backup() {
+        tracing::warn!("Failed to backup current config before restoring: {err}");
+    }
+
+    let config = Schema::from(&backup.read()?);
+
+    Config::update_raw(config)?;
+
+    Ok(())
+}
+
+/// Replace current config with the default one
+pub fn reset() -> anyhow::Result<()> {
+    tracing::warn!("Resetting config to default values");
+
+    if let Err(err) = backup() {
+        tracing::warn!("Failed to backup current config before resetting: {err}");
+    }
+
+    Config::update_raw(Schema::default())?;
+
+    Ok(())
+}
+
+fn flatten(prefix: String, value: &JsonValue, output: &mut Vec<(String, String)>) {
+    match value {
+        JsonValue::Object(entries) if !entries.is_empty() => {
+            for (key, value) in entries {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+
+                flatten(key, value, output);
+            }
+        }
+
+        _ => output.push((prefix, value.to_string()))
+    }
+}
+
+/// Get list of changes made in the `new` config version comparing to the `old` one
+pub fn diff(old: &JsonValue, new: &JsonValue) -> Vec<Change> {
+    let mut old_values = Vec::new();
+    let mut new_values = Vec::new();
+
+    flatten(String::new(), old, &mut old_values);
+    flatten(String::new(), new, &mut new_values);
+
+    let mut changes = Vec::new();
+
+    for (key, old_value) in &old_values {
+        match new_values.iter().find(|(new_key, _)| new_key == key) {
+            Some((_, new_value)) if new_value != old_value => {
+                changes.push(Change::Changed(key.clone(), old_value.clone(), new_value.clone()));
+            }
+
+            Some(_) => (),
+
+            None => changes.push(Change::Removed(key.clone(), old_value.clone()))
+        }
+    }
+
+    for (key, new_value) in new_values {
+        if !old_values.iter().any(|(old_key, _)| old_key == &key) {
+            changes.push(Change::Added(key, new_value));
+        }
+    }
+
+    changes
+}
@@ -0,0 +1,380 @@
+use std::path::{Path, PathBuf};
+
+use anime_launcher_sdk::genshin::config::Schema;
+
+use anime_launcher_sdk::anime_game_core::installer::free_space;
+
+use crate::tr;
+
+/// Minimal amount of free space in the temp folder before warning the user
+pub const MIN_TEMP_FREE_SPACE: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error
+}
+
+/// Automatic solution of the config issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fix {
+    CreateFolder(PathBuf),
+    SetRepairerThreads(u64),
+
+    /// Use system temp folder
+    ResetTemp,
+
+    SetLanguage(String),
+
+    /// Let gamescope choose game and window resolutions itself
+    ResetGamescopeResolution,
+
+    DisableVirtualDesktop
+}
+
+impl Fix {
+    /// Apply fix to the given config. Folders are created right away
+    pub fn apply(&self, config: &mut Schema) -> anyhow::Result<()> {
+        tracing::info!("Applying config fix: {self:?}");
+
+        match self {
+            Self::CreateFolder(path) => std::fs::create_dir_all(path)?,
+
+            Self::SetRepairerThreads(threads) => config.launcher.repairer.threads = *threads,
+            Self::ResetTemp => config.launcher.temp = None,
+            Self::SetLanguage(lang) => config.launcher.language = lang.clone(),
+
+            Self::ResetGamescopeResolution => {
+                let gamescope = &mut config.game.enhancements.gamescope;
+
+                gamescope.game.width = 0;
+                gamescope.game.height = 0;
+
+                gamescope.gamescope.width = 0;
+                gamescope.gamescope.height = 0;
+            }
+
+            Self::DisableVirtualDesktop => config.game.wine.virtual_desktop.enabled = false
+        }
+
+        Ok(())
+    }
+
+    /// Human readable fix title
+    pub fn title(&self) -> String {
+        match self {
+            Self::CreateFolder(_)       => tr!("config-fix-create-folder"),
+            Self::SetRepairerThreads(_) => tr!("config-fix-repairer-threads"),
+            Self::ResetTemp             => tr!("config-fix-reset-temp"),
+            Self::SetLanguage(_)        => tr!("config-fix-language"),
+
+            Self::ResetGamescopeResolution => tr!("config-fix-reset-resolution"),
+            Self::DisableVirtualDesktop    => tr!("config-fix-disable-virtual-desktop")
+        }
+    }
+}
+
+/// Problem found in the config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+
+    /// Config field the issue is related to, e.g. `game.wine.prefix`
+    pub field: String,
+
+    pub description: String,
+    pub fix: Option<Fix>
+}
+
+impl Issue {
+    #[inline]
+    fn error(field: &str, description: String, fix: Option<Fix>) -> Self {
+        Self {
+            severity: Severity::Error,
+            field: field.to_string(),
+            description,
+            fix
+        }
+    }
+
+    #[inline]
+    fn warning(field: &str, description: String, fix: Option<Fix>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            field: field.to_string(),
+            description,
+            fix
+        }
+    }
+}
+
+fn check_folder(issues: &mut Vec<Issue>, field: &str, path: &Path, severity: Severity) {
+    if !path.exists() {
+        let description = tr!("config-issue-folder-missing", {
+            "path" = path.to_string_lossy().to_string()
+        });
+
+        let fix = Some(Fix::CreateFolder(path.to_path_buf()));
+
+        issues.push(match severity {
+            Severity::Error => Issue::error(field, description, fix),
+            Severity::Warning => Issue::warning(field, description, fix)
+        });
+    }
+
+    else if !path.is_dir() {
+        issues.push(Issue::error(field, tr!("config-issue-not-folder", {
+            "path" = path.to_string_lossy().to_string()
+        }), None));
+    }
+}
+
+/// Check config values for the problems which would break launcher's flows
+///
+/// Errors are sorted before warnings
+pub fn validate(config: &Schema) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    // Launcher language
+    let lang = config.launcher.language.parse::<unic_langid::LanguageIdentifier>().ok()
+        .and_then(|lang| crate::i18n::normalize_lang(&lang));
+
+    if lang.is_none() {
+        issues.push(Issue::error("launcher.language", tr!("config-issue-language", {
+            "language" = config.launcher.language.clone()
+        }), Some(Fix::SetLanguage(crate::i18n::format_lang(&crate::i18n::get_default_lang())))));
+    }
+
+    // Wine prefix should be created by the launcher, but its parent folder must exist
+    if let Some(parent) = config.game.wine.prefix.parent() {
+        check_folder(&mut issues, "game.wine.prefix", parent, Severity::Error);
+    }
+
+    check_folder(&mut issues, "components.path", &config.components.path, Severity::Error);
+    check_folder(&mut issues, "game.wine.builds", &config.game.wine.builds, Severity::Warning);
+    check_folder(&mut issues, "game.dxvk.builds", &config.game.dxvk.builds, Severity::Warning);
+
+    // Repairer splits files between threads
+    if config.launcher.repairer.threads == 0 {
+        issues.push(Issue::error("launcher.repairer.threads", tr!("config-issue-repairer-threads"), Some(Fix::SetRepairerThreads(4))));
+    }
+
+    // Temp folder
+    if let Some(temp) = &config.launcher.temp {
+        if !temp.exists() {
+            issues.push(Issue::warning("launcher.temp", tr!("config-issue-folder-missing", {
+                "path" = temp.to_string_lossy().to_string()
+            }), Some(Fix::ResetTemp)));
+        }
+
+        else if let Some(available) = free_space::available(temp) {
+            if available < MIN_TEMP_FREE_SPACE {
+                issues.push(Issue::warning("launcher.temp", tr!("config-issue-temp-full", {
+                    "path" = temp.to_string_lossy().to_string()
+                }), Some(Fix::ResetTemp)));
+            }
+        }
+    }
+
+    // Selected components
+    if let Some(wine) = &config.game.wine.selected {
+        if !config.game.wine.builds.join(wine).exists() {
+            issues.push(Issue::warning("game.wine.selected", tr!("config-issue-component-missing", {
+                "name" = wine.clone()
+            }), None));
+        }
+    }
+
+    // Resolutions should have both width and height set, or none of them
+    let gamescope = &config.game.enhancements.gamescope;
+
+    for (field, width, height) in [
+        ("game.enhancements.gamescope.game", gamescope.game.width, gamescope.game.height),
+        ("game.enhancements.gamescope.gamescope", gamescope.gamescope.width, gamescope.gamescope.height)
+    ] {
+        if (width == 0) != (height == 0) {
+            issues.push(Issue::warning(field, tr!("config-issue-resolution", {
+                "width" = width.to_string(),
+                "height" = height.to_string()
+            }), Some(Fix::ResetGamescopeResolution)));
+        }
+    }
+
+    let desktop = &config.game.wine.virtual_desktop;
+
+    if desktop.enabled && (desktop.width == 0 || desktop.height == 0) {
+        issues.push(Issue::error("game.wine.virtual_desktop", tr!("config-issue-resolution", {
+            "width" = desktop.width.to_string(),
+            "height" = desktop.height.to_string()
+        }), Some(Fix::DisableVirtualDesktop)));
+    }
+
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create empty temp folder for the test
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aagl-validator-test-{name}-{}", std::process::id()));
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    /// Get config with all the folders placed in the `path`
+    fn get_config(path: &Path) -> Schema {
+        let mut config = Schema::default();
+
+        config.launcher.language = String::from("en-us");
+        config.launcher.temp = None;
+        config.launcher.repairer.threads = 4;
+
+        config.game.wine.prefix = path.join("prefix");
+        config.game.wine.builds = path.join("runners");
+        config.game.wine.selected = Some(String::from("wine"));
+        config.game.wine.virtual_desktop.enabled = false;
+
+        config.game.dxvk.builds = path.join("dxvks");
+
+        config.game.enhancements.gamescope.game.width = 0;
+        config.game.enhancements.gamescope.game.height = 0;
+        config.game.enhancements.gamescope.gamescope.width = 0;
+        config.game.enhancements.gamescope.gamescope.height = 0;
+
+        config.components.path = path.join("components");
+
+        std::fs::create_dir_all(config.game.wine.builds.join("wine")).unwrap();
+        std::fs::create_dir_all(&config.game.dxvk.builds).unwrap();
+        std::fs::create_dir_all(&config.components.path).unwrap();
+
+        config
+    }
+
+    fn fields(issues: &[Issue]) -> Vec<(&str, Severity)> {
+        issues.iter()
+            .map(|issue| (issue.field.as_str(), issue.severity))
+            .collect()
+    }
+
+    /// Apply all the issues' fixes and validate config again
+    fn fix_all(config: &mut Schema, issues: Vec<Issue>) -> Vec<Issue> {
+        for issue in issues {
+            issue.fix.expect("issue has no fix").apply(config).unwrap();
+        }
+
+        validate(config)
+    }
+
+    #[test]
+    fn valid_config() {
+        let path = temp_folder("valid");
+
+        assert!(validate(&get_config(&path)).is_empty());
+    }
+
+    #[test]
+    fn missing_wine_build() {
+        let path = temp_folder("wine");
+
+        let mut config = get_config(&path);
+
+        config.game.wine.selected = Some(String::from("missing"));
+
+        let issues = validate(&config);
+
+        assert_eq!(fields(&issues), [("game.wine.selected", Severity::Warning)]);
+        assert!(issues[0].fix.is_none());
+    }
+
+    #[test]
+    fn bad_prefix_path() {
+        let path = temp_folder("prefix");
+
+        let mut config = get_config(&path);
+
+        config.game.wine.prefix = path.join("missing/prefix");
+
+        let issues = validate(&config);
+
+        assert_eq!(fields(&issues), [("game.wine.prefix", Severity::Error)]);
+        assert_eq!(issues[0].fix, Some(Fix::CreateFolder(path.join("missing"))));
+
+        assert!(fix_all(&mut config, issues).is_empty());
+        assert!(path.join("missing").is_dir());
+
+        // Files can't be fixed automatically
+        std::fs::write(path.join("file"), "").unwrap();
+
+        config.game.wine.prefix = path.join("file/prefix");
+
+        let issues = validate(&config);
+
+        assert_eq!(fields(&issues), [("game.wine.prefix", Severity::Error)]);
+        assert!(issues[0].fix.is_none());
+    }
+
+    #[test]
+    fn invalid_resolutions() {
+        let path = temp_folder("resolutions");
+
+        let mut config = get_config(&path);
+
+        config.game.enhancements.gamescope.game.width = 1920;
+        config.game.enhancements.gamescope.gamescope.height = 1080;
+
+        config.game.wine.virtual_desktop.enabled = true;
+        config.game.wine.virtual_desktop.width = 0;
+        config.game.wine.virtual_desktop.height = 720;
+
+        let issues = validate(&config);
+
+        assert_eq!(fields(&issues), [
+            ("game.wine.virtual_desktop", Severity::Error),
+            ("game.enhancements.gamescope.game", Severity::Warning),
+            ("game.enhancements.gamescope.gamescope", Severity::Warning)
+        ]);
+
+        assert!(fix_all(&mut config, issues).is_empty());
+        assert!(!config.game.wine.virtual_desktop.enabled);
+
+        // Both sizes set is fine
+        config.game.enhancements.gamescope.game.width = 1920;
+        config.game.enhancements.gamescope.game.height = 1080;
+
+        assert!(validate(&config).is_empty());
+    }
+
+    #[test]
+    fn launcher_settings() {
+        let path = temp_folder("launcher");
+
+        let mut config = get_config(&path);
+
+        config.launcher.language = String::from("not a language");
+        config.launcher.repairer.threads = 0;
+        config.launcher.temp = Some(path.join("missing"));
+
+        let issues = validate(&config);
+
+        assert_eq!(fields(&issues), [
+            ("launcher.language", Severity::Error),
+            ("launcher.repairer.threads", Severity::Error),
+            ("launcher.temp", Severity::Warning)
+        ]);
+
+        assert!(fix_all(&mut config, issues).is_empty());
+
+        assert_eq!(config.launcher.repairer.threads, 4);
+        assert_eq!(config.launcher.temp, None);
+    }
+}
@@ -0,0 +1,260 @@
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+
+use unic_langid::LanguageIdentifier;
+
+use anime_launcher_sdk::config::ConfigExt;
+use anime_launcher_sdk::genshin::config::{Config, Schema};
+
+use anime_launcher_sdk::anime_game_core::prelude::*;
+use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
+
+use crate::actions;
+use crate::actions::repair::RepairUpdate;
+
+/// Launcher state shared between the UI components
+///
+/// Created once in `main` and passed to the components through their `Init`.
+/// Lives in the main (GTK) thread only, so use component messages to change it from other threads.
+///
+/// App's context reads and writes the launcher config, so changes made by other threads
+/// are visible through it as well
+#[derive(Debug, Clone)]
+pub struct AppContext {
+    inner: Rc<AppContextInner>
+}
+
+#[derive(Debug)]
+struct AppContextInner {
+    ready: Cell<bool>,
+
+    /// Own config of the detached context
+    config: Option<RefCell<Schema>>,
+
+    language: RefCell<LanguageIdentifier>,
+
+    main_window: RefCell<Option<adw::ApplicationWindow>>,
+    preferences_window: RefCell<Option<adw::PreferencesWindow>>
+}
+
+impl AppContext {
+    fn with_config(config: Option<Schema>, language: LanguageIdentifier) -> Self {
+        Self {
+            inner: Rc::new(AppContextInner {
+                ready: Cell::new(false),
+
+                config: config.map(RefCell::new),
+                language: RefCell::new(language),
+
+                main_window: RefCell::new(None),
+                preferences_window: RefCell::new(None)
+            })
+        }
+    }
+
+    /// Create context of the app using the launcher config
+    #[inline]
+    pub fn new(language: LanguageIdentifier) -> Self {
+        Self::with_config(None, language)
+    }
+
+    /// Create context with its own config which is never saved
+    ///
+    /// Used to run launcher actions headlessly, e.g. in tests
+    #[inline]
+    pub fn detached(config: Schema, language: LanguageIdentifier) -> Self {
+        Self::with_config(Some(config), language)
+    }
+
+    /// Check if the app is fully initialized
+    ///
+    /// Components use it to ignore signals emitted while their widgets are being set up
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.inner.ready.get()
+    }
+
+    #[inline]
+    pub fn set_ready(&self, ready: bool) {
+        self.inner.ready.set(ready);
+    }
+
+    /// Get config used by the app
+    pub fn config(&self) -> Schema {
+        match &self.inner.config {
+            Some(config) => config.borrow().clone(),
+            None => Config::get().unwrap_or_else(|_| crate::CONFIG.clone())
+        }
+    }
+
+    /// Replace config used by the app. It's saved to the config file on the app's exit
+    pub fn update_config(&self, config: Schema) {
+        match &self.inner.config {
+            Some(current) => {
+                current.replace(config);
+            }
+
+            None => Config::update(config)
+        }
+    }
+
+    /// Get launcher language
+    pub fn language(&self) -> LanguageIdentifier {
+        self.inner.language.borrow().clone()
+    }
+
+    /// Set launcher language and re-render all the subscribed components
+    pub fn set_language(&self, language: LanguageIdentifier) -> anyhow::Result<()> {
+        crate::i18n::set_lang(language)?;
+
+        self.inner.language.replace(crate::i18n::get_lang());
+
+        Ok(())
+    }
+
+    /// Get handle of the game installation selected in the current config
+    pub fn game(&self) -> Game {
+        let config = self.config();
+
+        Game::new(config.game.path.for_edition(config.launcher.edition), config.launcher.edition)
+    }
+
+    pub fn main_window(&self) -> Option<adw::ApplicationWindow> {
+        self.inner.main_window.borrow().clone()
+    }
+
+    pub fn set_main_window(&self, window: adw::ApplicationWindow) {
+        self.inner.main_window.replace(Some(window));
+    }
+
+    pub fn preferences_window(&self) -> Option<adw::PreferencesWindow> {
+        self.inner.preferences_window.borrow().clone()
+    }
+
+    pub fn set_preferences_window(&self, window: adw::PreferencesWindow) {
+        self.inner.preferences_window.replace(Some(window));
+    }
+
+    /// Run the game with the current config, blocking until it's closed
+    pub fn launch(&self) -> anyhow::Result<()> {
+        match &self.inner.config {
+            // SDK only runs the game with the launcher config of its process
+            Some(config) => actions::launch::run(&config.borrow()),
+
+            None => actions::launch::launch(&self.config())
+        }
+    }
+
+    /// Download and install game or voiceover update, blocking until it's finished
+    #[inline]
+    pub fn download_diff(&self, diff: VersionDiff, updater: impl Fn(DiffUpdate) + Clone + Send + 'static) -> anyhow::Result<()> {
+        actions::download_diff::download_diff(&self.config(), diff, updater)
+    }
+
+    /// Verify and repair the game files, returns amount of broken files
+    #[inline]
+    pub fn repair_game(&self, updater: impl FnMut(RepairUpdate)) -> anyhow::Result<usize> {
+        actions::repair::repair_game(&self.config(), updater)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use unic_langid::langid;
+
+    use anime_launcher_sdk::anime_game_core::repairer::IntegrityFile;
+
+    use super::*;
+
+    /// Create empty temp folder for the test
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aagl-context-test-{name}-{}", std::process::id()));
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    /// Create detached context with all the launcher paths inside of the given folder
+    fn get_context(path: &Path) -> AppContext {
+        let mut config = Schema::default();
+
+        config.launcher.temp = Some(path.join("temp"));
+
+        config.game.path.global = path.join("game");
+        config.game.path.china = path.join("game-china");
+
+        config.game.wine.builds = path.join("runners");
+        config.game.wine.prefix = path.join("prefix");
+        config.game.dxvk.builds = path.join("dxvks");
+
+        config.components.path = path.join("components");
+
+        AppContext::detached(config, langid!("en-us"))
+    }
+
+    #[test]
+    fn config_and_language() {
+        let path = temp_folder("config");
+        let ctx = get_context(&path);
+
+        assert_eq!(ctx.config().game.wine.builds, path.join("runners"));
+        assert_eq!(ctx.language(), langid!("en-us"));
+        assert!(!ctx.game().is_installed());
+
+        // Clones share the same state
+        let mut config = ctx.config();
+
+        config.game.wine.selected = Some(String::from("wine"));
+
+        ctx.clone().update_config(config);
+
+        assert_eq!(ctx.config().game.wine.selected.as_deref(), Some("wine"));
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn repair_files() {
+        let path = temp_folder("repair");
+        let ctx = get_context(&path);
+
+        let mut config = ctx.config();
+
+        config.launcher.repairer.fast = true;
+        config.launcher.repairer.threads = 2;
+
+        ctx.update_config(config);
+
+        std::fs::create_dir_all(path.join("game/data")).unwrap();
+
+        std::fs::write(path.join("game/data/a"), "a").unwrap();
+        std::fs::write(path.join("game/data/b"), "bb").unwrap();
+
+        let files = ["data/a", "data/b"].into_iter()
+            .map(|file| IntegrityFile {
+                path: PathBuf::from(file),
+                md5: String::new(),
+                size: std::fs::metadata(path.join("game").join(file)).unwrap().len(),
+                base_url: String::new()
+            })
+            .collect::<Vec<_>>();
+
+        let mut updates = Vec::new();
+
+        let broken = actions::repair::repair_files(&ctx.config(), files, |update| updates.push(update)).unwrap();
+
+        assert_eq!(broken, 0);
+        assert_eq!(updates.first(), Some(&RepairUpdate::Verifying { processed: 0, total: 3 }));
+        assert_eq!(updates.last(), Some(&RepairUpdate::Verifying { processed: 3, total: 3 }));
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
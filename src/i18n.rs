@@ -1,5 +1,14 @@
+use std::collections::{HashMap, BTreeSet};
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+
 use unic_langid::{langid, LanguageIdentifier};
 
+use fluent_templates::{Loader, ArcLoader};
+use fluent_templates::fluent_bundle::FluentValue;
+
+use relm4::SharedState;
+
 fluent_templates::static_loader! {
     pub static LOCALES = {
         locales: "./assets/locales",
@@ -29,26 +38,48 @@ pub const SUPPORTED_LANGUAGES: &[LanguageIdentifier] = &[
     langid!("nl-nl")
 ];
 
-pub static mut LANG: LanguageIdentifier = langid!("en-us");
+/// Language used when a message is not translated
+pub const FALLBACK_LANG: LanguageIdentifier = langid!("en-us");
+
+/// Translations loaded at runtime from the `LAUNCHER_FOLDER/locales` folder
+///
+/// They're layered over the embedded ones so translators can test their changes without rebuilding the launcher
+static OVERRIDES: RwLock<Option<ArcLoader>> = RwLock::new(None);
+
+/// Current launcher language
+///
+/// Components can `subscribe` to it to re-render their translated labels when the language is changed
+pub static LANG: SharedState<LanguageIdentifier> = SharedState::new();
 
-/// Set launcher language
+/// Languages and ids of the not translated messages which were already reported
+static REPORTED_FALLBACKS: Mutex<BTreeSet<(String, String)>> = Mutex::new(BTreeSet::new());
+
+/// Get supported language closest to the given one
+///
+/// E.g. `en-gb` -> `en-us`, `zh-tw` -> `zh-cn`. Returns `None` if the language is not supported at all
+pub fn normalize_lang(lang: &LanguageIdentifier) -> Option<LanguageIdentifier> {
+    SUPPORTED_LANGUAGES.iter()
+        .find(|item| item.language == lang.language && item.region == lang.region)
+        .or_else(|| SUPPORTED_LANGUAGES.iter().find(|item| item.language == lang.language))
+        .cloned()
+}
+
+/// Set launcher language and notify subscribed components
+///
+/// Regional variants which are not supported are replaced by the closest supported ones
 pub fn set_lang(lang: LanguageIdentifier) -> anyhow::Result<()> {
-    if SUPPORTED_LANGUAGES.iter().any(|item| item.language == lang.language) {
-        unsafe {
-            LANG = lang
-        }
+    let Some(lang) = normalize_lang(&lang) else {
+        anyhow::bail!("Language '{lang}' is not supported");
+    };
 
-        Ok(())
-    }
+    *LANG.write() = lang;
 
-    else {
-        anyhow::bail!("Language '{lang}' is not supported")
-    }
+    Ok(())
 }
 
 /// Get launcher language
 pub fn get_lang() -> LanguageIdentifier {
-    unsafe { LANG.clone() }
+    LANG.read().clone()
 }
 
 /// Get system language or default language if system one is not supported
@@ -57,20 +88,255 @@ pub fn get_lang() -> LanguageIdentifier {
 /// - `LC_ALL`
 /// - `LC_MESSAGES`
 /// - `LANG`
+///
+/// Not supported regional variants are replaced by the closest supported ones (e.g. `en-gb` -> `en-us`)
 pub fn get_default_lang() -> LanguageIdentifier {
     let current = std::env::var("LC_ALL")
-        .unwrap_or_else(|_| std::env::var("LC_MESSAGES")
-        .unwrap_or_else(|_| std::env::var("LANG")
-        .unwrap_or_else(|_| String::from("en_us"))))
-        .to_ascii_lowercase();
-
-    for lang in SUPPORTED_LANGUAGES {
-        if current.starts_with(lang.language.as_str()) {
-            return lang.clone();
+        .or_else(|_| std::env::var("LC_MESSAGES"))
+        .or_else(|_| std::env::var("LANG"))
+        .unwrap_or_else(|_| String::from("en_US"));
+
+    // e.g. zh_TW.UTF-8 -> zh-TW
+    let current = current.split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-");
+
+    current.parse::<LanguageIdentifier>().ok()
+        .and_then(|current| normalize_lang(&current))
+        .unwrap_or(FALLBACK_LANG)
+}
+
+/// Get languages which should be used to look up messages of the given one
+///
+/// E.g. `zh-tw` -> `zh-tw`, `zh-cn`, `en-us`
+pub fn get_fallback_chain(lang: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    let mut chain = vec![lang.clone()];
+
+    // Other regional variant of the same language
+    if let Some(variant) = SUPPORTED_LANGUAGES.iter().find(|item| item.language == lang.language && *item != lang) {
+        chain.push(variant.clone());
+    }
+
+    if !chain.contains(&FALLBACK_LANG) {
+        chain.push(FALLBACK_LANG);
+    }
+
+    chain
+}
+
+/// Load translations overrides from the given folder
+///
+/// The folder should have the same structure as `assets/locales`: `<folder>/<language>/*.ftl`.
+/// Returns `false` if the folder doesn't exist
+pub fn load_overrides(folder: impl AsRef<Path>) -> anyhow::Result<bool> {
+    let folder = folder.as_ref();
+
+    if !folder.exists() {
+        return Ok(false);
+    }
+
+    // Make messages shared between the languages available to the overrides
+    let common = crate::CACHE_FOLDER.join("locales-common.ftl");
+
+    std::fs::create_dir_all(crate::CACHE_FOLDER.as_path())?;
+    std::fs::write(&common, include_str!("../assets/locales/common.ftl"))?;
+
+    let loader = ArcLoader::builder(folder, FALLBACK_LANG)
+        .shared_resources(Some(&[common]))
+        .build()
+        .map_err(|err| anyhow::anyhow!("Failed to load translations overrides: {err}"))?;
+
+    tracing::info!("Loaded translations overrides from {:?}", folder);
+
+    if let Ok(mut overrides) = OVERRIDES.write() {
+        *overrides = Some(loader);
+    }
+
+    Ok(true)
+}
+
+/// Look up message in the overrides first, and in the embedded translations then
+fn lookup(lang: &LanguageIdentifier, id: &str, args: Option<&HashMap<&str, FluentValue>>) -> Option<String> {
+    if let Ok(overrides) = OVERRIDES.read() {
+        if let Some(message) = overrides.as_ref().and_then(|overrides| overrides.lookup_single_language(lang, id, args)) {
+            return Some(message);
         }
     }
 
-    get_lang()
+    // Embedded translations are negotiated for the supported languages only,
+    // other ones would silently fall back to English skipping their regional variants
+    if !SUPPORTED_LANGUAGES.contains(lang) {
+        return None;
+    }
+
+    LOCALES.lookup_complete(lang, id, args)
+}
+
+/// Get translated message in the current launcher language
+///
+/// Falls back to other languages from `get_fallback_chain` when the message is not translated,
+/// and to the message id itself when it doesn't exist at all
+pub fn translate(id: &str, args: Option<&HashMap<&str, FluentValue>>) -> String {
+    let lang = get_lang();
+
+    for (i, fallback) in get_fallback_chain(&lang).iter().enumerate() {
+        if let Some(message) = lookup(fallback, id, args) {
+            if i > 0 {
+                let reported = REPORTED_FALLBACKS.lock()
+                    .map(|mut reported| !reported.insert((lang.to_string(), id.to_string())))
+                    .unwrap_or(true);
+
+                if !reported {
+                    tracing::warn!("Message {id} is not translated to {lang}, using {fallback}");
+                }
+            }
+
+            return message;
+        }
+    }
+
+    tracing::error!("Failed to find a message with given id: {id}");
+
+    id.to_string()
+}
+
+/// Units used to format byte sizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeUnits {
+    /// Powers of 1024: KiB, MiB, GiB
+    Binary,
+
+    /// Powers of 1000: KB, MB, GB
+    Decimal
+}
+
+/// Get decimal and thousands separators used by the language
+fn number_separators(lang: &LanguageIdentifier) -> (char, char) {
+    match lang.language.as_str() {
+        "en" | "zh" | "ja" | "ko" => ('.', ','),
+
+        "de" | "es" | "it" | "id" | "tr" | "pt" | "vi" | "nl" => (',', '.'),
+
+        // ru, fr, hu, sv, pl
+        _ => (',', '\u{a0}')
+    }
+}
+
+/// Format number with given amount of decimal digits using the current launcher language
+///
+/// E.g. `12345.678` with 2 digits is `12,345.68` in English and `12.345,68` in German
+pub fn format_number(value: f64, precision: usize) -> String {
+    let (decimal, thousands) = number_separators(&get_lang());
+
+    let formatted = format!("{:.*}", precision, value.abs());
+
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None)
+    };
+
+    let mut result = String::new();
+
+    if value < 0.0 {
+        result.push('-');
+    }
+
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            result.push(thousands);
+        }
+
+        result.push(digit);
+    }
+
+    if let Some(fraction) = fraction {
+        result.push(decimal);
+        result.push_str(fraction);
+    }
+
+    result
+}
+
+/// Format byte size using the current launcher language, e.g. `1.50 GiB`
+pub fn format_size(bytes: u64, units: SizeUnits) -> String {
+    let (base, names) = match units {
+        SizeUnits::Binary  => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB"]),
+        SizeUnits::Decimal => (1000.0, ["B", "KB", "MB", "GB", "TB"])
+    };
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= base && unit < names.len() - 1 {
+        size /= base;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", format_number(size, 0), names[0])
+    } else {
+        format!("{} {}", format_number(size, 2), names[unit])
+    }
+}
+
+/// Format duration using two largest non-zero units, e.g. `1 h 5 min`
+pub fn format_duration(duration: std::time::Duration) -> String {
+    let seconds = duration.as_secs();
+
+    let values = [
+        seconds / 86400,
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60
+    ];
+
+    let parts = values.into_iter()
+        .enumerate()
+        .skip_while(|(_, value)| *value == 0)
+        .take(2)
+        .filter(|(_, value)| *value > 0)
+        .map(|(unit, value)| match unit {
+            0 => crate::tr!("duration-days", { "value" = value }),
+            1 => crate::tr!("duration-hours", { "value" = value }),
+            2 => crate::tr!("duration-minutes", { "value" = value }),
+            _ => crate::tr!("duration-seconds", { "value" = value })
+        })
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        return crate::tr!("duration-seconds", { "value" = 0 });
+    }
+
+    parts.join(" ")
+}
+
+/// Format UTC timestamp as a local date and time
+pub fn format_datetime(timestamp: u64) -> String {
+    gtk::glib::DateTime::from_unix_local(timestamp as i64)
+        .and_then(|date| date.format("%x %X"))
+        .map(|date| date.to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// Format UTC timestamp relatively to the current time, e.g. `5 minutes ago`
+///
+/// Dates older than a week are formatted using `format_datetime`
+pub fn format_relative_date(timestamp: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default();
+
+    match now.saturating_sub(timestamp) {
+        0..=59 => crate::tr!("date-just-now"),
+
+        seconds @ 60..=3599 => crate::tr!("date-minutes-ago", { "value" = seconds / 60 }),
+        seconds @ 3600..=86399 => crate::tr!("date-hours-ago", { "value" = seconds / 3600 }),
+        seconds @ 86400..=604799 => crate::tr!("date-days-ago", { "value" = seconds / 86400 }),
+
+        _ => format_datetime(timestamp)
+    }
 }
 
 pub fn format_lang(lang: &LanguageIdentifier) -> String {
@@ -100,21 +366,13 @@ pub fn format_lang(lang: &LanguageIdentifier) -> String {
 /// ```
 macro_rules! tr {
     ($id:expr) => {
-        {
-            use fluent_templates::Loader;
-
-            #[allow(unused_unsafe)]
-            $crate::i18n::LOCALES
-                .lookup(unsafe { &$crate::i18n::LANG }, $id)
-                .expect(&format!("Failed to find a message with given id: {}", stringify!($id)))
-        }
+        $crate::i18n::translate($id, None)
     };
 
     ($id:expr, { $($key:literal = $value:expr),* }) => {
         {
-            use std::collections::HashMap;
+            use std::collections::{HashMap, BTreeSet};
 
-            use fluent_templates::Loader;
             use fluent_templates::fluent_bundle::FluentValue;
 
             let mut args = HashMap::new();
@@ -123,10 +381,133 @@ macro_rules! tr {
                 args.insert($key, FluentValue::from($value));
             )*
 
-            #[allow(unused_unsafe)]
-            $crate::i18n::LOCALES
-                .lookup_complete(unsafe { &$crate::i18n::LANG }, $id, Some(&args))
-                .expect(&format!("Failed to find a message with given id: {}", stringify!($id)))
+            $crate::i18n::translate($id, Some(&args))
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Tests changing the global launcher language shouldn't run at the same time
+    static LANG_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Call the function with `en-us`, `de-de` and `ru-ru` launcher languages
+    ///
+    /// Fluent isolation marks are removed from the results
+    fn formatted(function: impl Fn() -> String) -> [String; 3] {
+        let _lock = LANG_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+
+        [langid!("en-us"), langid!("de-de"), langid!("ru-ru")].map(|lang| {
+            set_lang(lang).unwrap();
+
+            function().replace(['\u{2068}', '\u{2069}'], "")
+        })
+    }
+
+    #[test]
+    fn concurrent_language_switch() {
+        const SWITCHES: usize = 100;
+
+        let _lock = LANG_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+
+        let languages = [langid!("en-us"), langid!("de-de"), langid!("ja-jp")];
+
+        let (sender, receiver) = relm4::channel::<LanguageIdentifier>();
+
+        LANG.subscribe(&sender, |lang| lang.clone());
+
+        let handles = languages.iter().cloned()
+            .map(|lang| std::thread::spawn(move || {
+                for _ in 0..SWITCHES {
+                    set_lang(lang.clone()).expect("Failed to set language");
+
+                    assert!(SUPPORTED_LANGUAGES.contains(&get_lang()));
+                    assert_ne!(translate("launch", None), "launch");
+                }
+            }))
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().expect("Language switching thread panicked");
+        }
+
+        // Every switch should be reported to the subscribers
+        for _ in 0..languages.len() * SWITCHES {
+            let lang = receiver.recv_sync().expect("Language change was not reported");
+
+            assert!(languages.contains(&lang));
+        }
+
+        assert!(languages.contains(&get_lang()));
+
+        assert!(set_lang(langid!("xx-xx")).is_err());
+    }
+
+    #[test]
+    fn normalize_languages() {
+        assert_eq!(normalize_lang(&langid!("de-de")), Some(langid!("de-de")));
+        assert_eq!(normalize_lang(&langid!("en-gb")), Some(langid!("en-us")));
+        assert_eq!(normalize_lang(&langid!("zh-tw")), Some(langid!("zh-cn")));
+        assert_eq!(normalize_lang(&langid!("pt-pt")), Some(langid!("pt-br")));
+        assert_eq!(normalize_lang(&langid!("ru")), Some(langid!("ru-ru")));
+        assert_eq!(normalize_lang(&langid!("xx-xx")), None);
+    }
+
+    #[test]
+    fn format_numbers() {
+        assert_eq!(formatted(|| format_number(0.0, 0)), ["0", "0", "0"]);
+        assert_eq!(formatted(|| format_number(999.0, 0)), ["999", "999", "999"]);
+        assert_eq!(formatted(|| format_number(1000.0, 0)), ["1,000", "1.000", "1\u{a0}000"]);
+        assert_eq!(formatted(|| format_number(12345.678, 2)), ["12,345.68", "12.345,68", "12\u{a0}345,68"]);
+        assert_eq!(formatted(|| format_number(-1234567.0, 1)), ["-1,234,567.0", "-1.234.567,0", "-1\u{a0}234\u{a0}567,0"]);
+    }
+
+    #[test]
+    fn format_sizes() {
+        assert_eq!(formatted(|| format_size(0, SizeUnits::Binary)), ["0 B", "0 B", "0 B"]);
+        assert_eq!(formatted(|| format_size(1023, SizeUnits::Binary)), ["1,023 B", "1.023 B", "1\u{a0}023 B"]);
+        assert_eq!(formatted(|| format_size(1024, SizeUnits::Binary)), ["1.00 KiB", "1,00 KiB", "1,00 KiB"]);
+        assert_eq!(formatted(|| format_size(1536 * 1024 * 1024, SizeUnits::Binary)), ["1.50 GiB", "1,50 GiB", "1,50 GiB"]);
+
+        assert_eq!(formatted(|| format_size(999, SizeUnits::Decimal)), ["999 B", "999 B", "999 B"]);
+        assert_eq!(formatted(|| format_size(1000, SizeUnits::Decimal)), ["1.00 KB", "1,00 KB", "1,00 KB"]);
+        assert_eq!(formatted(|| format_size(2_500_000_000_000_000, SizeUnits::Decimal)), ["2,500.00 TB", "2.500,00 TB", "2\u{a0}500,00 TB"]);
+    }
+
+    #[test]
+    fn format_durations() {
+        let duration = |seconds| move || format_duration(Duration::from_secs(seconds));
+
+        assert_eq!(formatted(duration(0)), ["0 s", "0 Sek.", "0 с"]);
+        assert_eq!(formatted(duration(59)), ["59 s", "59 Sek.", "59 с"]);
+        assert_eq!(formatted(duration(60)), ["1 min", "1 Min.", "1 мин"]);
+        assert_eq!(formatted(duration(3599)), ["59 min 59 s", "59 Min. 59 Sek.", "59 мин 59 с"]);
+        assert_eq!(formatted(duration(3600)), ["1 h", "1 Std.", "1 ч"]);
+        assert_eq!(formatted(duration(3661)), ["1 h 1 min", "1 Std. 1 Min.", "1 ч 1 мин"]);
+        assert_eq!(formatted(duration(93784)), ["1 d 2 h", "1 T 2 Std.", "1 д 2 ч"]);
+    }
+
+    #[test]
+    fn format_relative_dates() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        // Offsets are chosen so a second passed during the test doesn't change the result
+        let ago = |seconds| move || format_relative_date(now - seconds);
+
+        assert_eq!(formatted(ago(0)), ["Just now", "Gerade eben", "Только что"]);
+        assert_eq!(formatted(ago(60)), ["1 minute ago", "vor 1 Minute", "1 минуту назад"]);
+        assert_eq!(formatted(ago(150)), ["2 minutes ago", "vor 2 Minuten", "2 минуты назад"]);
+        assert_eq!(formatted(ago(310)), ["5 minutes ago", "vor 5 Minuten", "5 минут назад"]);
+        assert_eq!(formatted(ago(21 * 60 + 10)), ["21 minutes ago", "vor 21 Minuten", "21 минуту назад"]);
+        assert_eq!(formatted(ago(3600)), ["1 hour ago", "vor 1 Stunde", "1 час назад"]);
+        assert_eq!(formatted(ago(86400)), ["1 day ago", "vor 1 Tag", "1 день назад"]);
+        assert_eq!(formatted(ago(6 * 86400)), ["6 days ago", "vor 6 Tagen", "6 дней назад"]);
+    }
+}
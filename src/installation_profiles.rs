@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Serialize, Deserialize};
+
+use anime_launcher_sdk::config::ConfigExt;
+use anime_launcher_sdk::genshin::config::{Config, Schema};
+
+use anime_launcher_sdk::anime_game_core::genshin::consts::GameEdition;
+
+/// Settings of a single game installation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstallationProfile {
+    pub game_path: PathBuf,
+    pub edition: GameEdition,
+    pub prefix: PathBuf,
+    pub voices: Vec<String>,
+    pub components_path: PathBuf,
+
+    /// Name of the wine build from `game.wine.builds`
+    pub wine: Option<String>,
+
+    /// Name of the DXVK build from `game.dxvk.builds`
+    pub dxvk: Option<String>
+}
+
+impl InstallationProfile {
+    /// Make profile from the installation currently used by the config
+    pub fn from_config(config: &Schema) -> Self {
+        Self {
+            game_path: config.game.path.for_edition(config.launcher.edition).to_path_buf(),
+            edition: config.launcher.edition,
+            prefix: config.game.wine.prefix.clone(),
+            voices: config.game.voices.clone(),
+            components_path: config.components.path.clone(),
+            wine: config.game.wine.selected.clone(),
+            dxvk: config.game.dxvk.selected.clone()
+        }
+    }
+
+    /// Make the config use this installation
+    pub fn apply(&self, config: &mut Schema) {
+        config.launcher.edition = self.edition;
+
+        match self.edition {
+            GameEdition::Global => config.game.path.global = self.game_path.clone(),
+            GameEdition::China  => config.game.path.china  = self.game_path.clone()
+        }
+
+        config.game.wine.prefix = self.prefix.clone();
+        config.game.voices = self.voices.clone();
+        config.components.path = self.components_path.clone();
+        config.game.wine.selected = self.wine.clone();
+        config.game.dxvk.selected = self.dxvk.clone();
+    }
+}
+
+/// Name of the profile made from the installation used before switching to another profile
+const DEFAULT_PROFILE_NAME: &str = "default";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ProfilesFile {
+    current: Option<String>,
+    profiles: HashMap<String, InstallationProfile>
+}
+
+fn read() -> anyhow::Result<ProfilesFile> {
+    if !crate::INSTALLATION_PROFILES_FILE.exists() {
+        return Ok(ProfilesFile::default());
+    }
+
+    Ok(serde_json::from_slice(&std::fs::read(crate::INSTALLATION_PROFILES_FILE.as_path())?)?)
+}
+
+fn write(file: &ProfilesFile) -> anyhow::Result<()> {
+    std::fs::write(crate::INSTALLATION_PROFILES_FILE.as_path(), serde_json::to_string_pretty(file)?)?;
+
+    Ok(())
+}
+
+/// Get free name to store the given profile with, or `None` if it's already stored
+fn get_unsaved_name(profiles: &HashMap<String, InstallationProfile>, profile: &InstallationProfile) -> Option<String> {
+    if profiles.values().any(|stored| stored == profile) {
+        return None;
+    }
+
+    let mut name = String::from(DEFAULT_PROFILE_NAME);
+    let mut i = 1;
+
+    while profiles.contains_key(&name) {
+        i += 1;
+
+        name = format!("{DEFAULT_PROFILE_NAME} {i}");
+    }
+
+    Some(name)
+}
+
+/// Get all the stored installation profiles
+#[inline]
+pub fn list() -> anyhow::Result<HashMap<String, InstallationProfile>> {
+    Ok(read()?.profiles)
+}
+
+/// Get name of the currently used profile
+#[inline]
+pub fn get_current() -> anyhow::Result<Option<String>> {
+    Ok(read()?.current)
+}
+
+/// Store installation currently used by the config as a named profile and select it
+pub fn save_current(name: impl ToString) -> anyhow::Result<()> {
+    let mut file = read()?;
+
+    file.profiles.insert(name.to_string(), InstallationProfile::from_config(&Config::get()?));
+    file.current = Some(name.to_string());
+
+    write(&file)
+}
+
+/// Remove stored profile. Config is not changed
+pub fn remove(name: impl AsRef<str>) -> anyhow::Result<()> {
+    let mut file = read()?;
+
+    file.profiles.remove(name.as_ref());
+
+    if file.current.as_deref() == Some(name.as_ref()) {
+        file.current = None;
+    }
+
+    write(&file)
+}
+
+/// Switch config to the given profile
+///
+/// Changes made to the current installation are stored to its profile before.
+/// If no profile is selected, the current installation is saved as the "default" one
+pub fn switch(name: impl AsRef<str>) -> anyhow::Result<()> {
+    let name = name.as_ref();
+
+    let mut file = read()?;
+    let mut config = Config::get()?;
+
+    if switch_profile(&mut file, name, &mut config)? {
+        crate::config_history::update_raw(config)?;
+
+        write(&file)?;
+    }
+
+    Ok(())
+}
+
+/// Apply profile named `name` to the config and select it in the profiles file
+///
+/// Return `false` if the profile is already selected and nothing was changed
+fn switch_profile(file: &mut ProfilesFile, name: &str, config: &mut Schema) -> anyhow::Result<bool> {
+    let Some(profile) = file.profiles.get(name).cloned() else {
+        anyhow::bail!("Installation profile not found: {name}");
+    };
+
+    if let Some(current) = &file.current {
+        if current == name {
+            return Ok(false);
+        }
+
+        file.profiles.insert(current.clone(), InstallationProfile::from_config(config));
+    }
+
+    // Keep the installation used before profiles were made so it's possible to switch back to it
+    else {
+        let current = InstallationProfile::from_config(config);
+
+        if let Some(current_name) = get_unsaved_name(&file.profiles, &current) {
+            tracing::info!("Saving current installation as {current_name} profile");
+
+            file.profiles.insert(current_name, current);
+        }
+    }
+
+    tracing::info!("Switching installation profile to {name}");
+
+    profile.apply(config);
+
+    file.current = Some(name.to_string());
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(game_path: &str) -> InstallationProfile {
+        InstallationProfile {
+            game_path: PathBuf::from(game_path),
+            edition: GameEdition::Global,
+            prefix: PathBuf::from("prefix"),
+            voices: vec![String::from("en-us")],
+            components_path: PathBuf::from("components"),
+            wine: None,
+            dxvk: None
+        }
+    }
+
+    #[test]
+    fn unsaved_name() {
+        let mut profiles = HashMap::new();
+
+        assert_eq!(get_unsaved_name(&profiles, &profile("game")).as_deref(), Some("default"));
+
+        profiles.insert(String::from("default"), profile("other"));
+        profiles.insert(String::from("default 2"), profile("another"));
+
+        assert_eq!(get_unsaved_name(&profiles, &profile("game")).as_deref(), Some("default 3"));
+
+        // Installation is already stored under another name
+        profiles.insert(String::from("main"), profile("game"));
+
+        assert_eq!(get_unsaved_name(&profiles, &profile("game")), None);
+    }
+
+    #[test]
+    fn apply_profile() {
+        let mut config = Schema::default();
+
+        let mut installation = profile("china");
+
+        installation.edition = GameEdition::China;
+        installation.wine = Some(String::from("wine-ge"));
+        installation.dxvk = Some(String::from("dxvk-2.3"));
+
+        let global_path = config.game.path.global.clone();
+        let telemetry_ignored = config.game.telemetry_ignored;
+
+        installation.apply(&mut config);
+
+        assert_eq!(InstallationProfile::from_config(&config), installation);
+
+        assert_eq!(config.game.path.china, PathBuf::from("china"));
+        assert_eq!(config.game.path.global, global_path);
+        assert_eq!(config.game.telemetry_ignored, telemetry_ignored);
+    }
+
+    #[test]
+    fn switch_profiles() {
+        let mut config = Schema::default();
+
+        profile("first").apply(&mut config);
+
+        let mut file = ProfilesFile::default();
+
+        file.profiles.insert(String::from("second"), profile("second"));
+
+        assert!(switch_profile(&mut file, "missing", &mut config).is_err());
+
+        // Installation used before profiles were made is stored as the default one
+        assert!(switch_profile(&mut file, "second", &mut config).unwrap());
+
+        assert_eq!(file.current.as_deref(), Some("second"));
+        assert_eq!(file.profiles.get("default"), Some(&profile("first")));
+        assert_eq!(InstallationProfile::from_config(&config), profile("second"));
+
+        // Already selected profile is not touched
+        assert!(!switch_profile(&mut file, "second", &mut config).unwrap());
+
+        // Changes of the current installation are stored to its profile
+        config.game.voices = vec![String::from("ja-jp")];
+
+        assert!(switch_profile(&mut file, "default", &mut config).unwrap());
+
+        assert_eq!(file.current.as_deref(), Some("default"));
+        assert_eq!(file.profiles.get("second").map(|profile| profile.voices.clone()), Some(vec![String::from("ja-jp")]));
+        assert_eq!(InstallationProfile::from_config(&config), profile("first"));
+    }
+}
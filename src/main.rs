@@ -1,5 +1,4 @@
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
 
 use relm4::prelude::*;
 
@@ -18,13 +17,27 @@ use anime_launcher_sdk::genshin::sessions::Sessions;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::filter::*;
 
+pub mod context;
+pub mod actions;
 pub mod move_files;
 pub mod i18n;
 pub mod background;
+pub mod news;
+pub mod config_history;
+pub mod config_validator;
+pub mod system_check;
+pub mod session_profiles;
+pub mod installation_profiles;
+pub mod shortcuts;
 pub mod ui;
 
 use ui::main::*;
 use ui::first_run::main::*;
+use ui::config_rollback::*;
+
+use actions::provision::{Provision, ProvisionUpdate};
+
+use context::AppContext;
 
 pub const APP_ID: &str = "moe.launcher.an-anime-game-launcher";
 pub const APP_RESOURCE_PATH: &str = "/moe/launcher/an-anime-game-launcher";
@@ -32,23 +45,11 @@ pub const APP_RESOURCE_PATH: &str = "/moe/launcher/an-anime-game-launcher";
 pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const APP_DEBUG: bool = cfg!(debug_assertions);
 
-/// Sets to `true` when the `App` component is ready (fully initialized)
-pub static READY: AtomicBool = AtomicBool::new(false);
-
-// TODO: get rid of using this function in all the components' events
-//       e.g. by converting preferences pages into Relm4 Components
-/// Check if the app is ready
-pub fn is_ready() -> bool {
-    READY.load(Ordering::Relaxed)
-}
-
 lazy_static::lazy_static! {
     /// Config loaded on the app's start. Use `Config::get()` to get up to date config instead.
     /// This one is used to prepare some launcher UI components on start
     pub static ref CONFIG: Schema = Config::get().expect("Failed to load config");
 
-    pub static ref GAME: Game = Game::new(CONFIG.game.path.for_edition(CONFIG.launcher.edition), CONFIG.launcher.edition);
-
     /// Path to launcher folder. Standard is `$HOME/.local/share/anime-game-launcher`
     pub static ref LAUNCHER_FOLDER: PathBuf = launcher_dir().expect("Failed to get launcher folder");
 
@@ -61,21 +62,89 @@ lazy_static::lazy_static! {
     /// Path to `background` file. Standard is `$HOME/.local/share/anime-game-launcher/background`
     pub static ref BACKGROUND_FILE: PathBuf = LAUNCHER_FOLDER.join("background");
 
+    /// Path to `.background.download` file. Background picture is downloaded there and verified
+    /// before replacing the current one
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/.background.download`
+    pub static ref BACKGROUND_DOWNLOAD_FILE: PathBuf = LAUNCHER_FOLDER.join(".background.download");
+
+    /// Path to cached background info of the official API. Standard is `$HOME/.cache/anime-game-launcher/background-info.json`
+    pub static ref BACKGROUND_CACHE_FILE: PathBuf = CACHE_FOLDER.join("background-info.json");
+
+    /// Path to cached launcher news. Standard is `$HOME/.cache/anime-game-launcher/news.json`
+    pub static ref NEWS_CACHE_FILE: PathBuf = CACHE_FOLDER.join("news.json");
+
+    /// Path to downloaded news banners. Standard is `$HOME/.cache/anime-game-launcher/news`
+    pub static ref NEWS_CACHE_FOLDER: PathBuf = CACHE_FOLDER.join("news");
+
     /// Path to `.keep-background` file. Used to mark launcher that it shouldn't update background picture
     /// 
     /// Standard is `$HOME/.local/share/anime-game-launcher/.keep-background`
     pub static ref KEEP_BACKGROUND_FILE: PathBuf = LAUNCHER_FOLDER.join(".keep-background");
 
+    /// Path to `background.json` file. Contains source, slideshow and style settings of the background picture
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/background.json`
+    pub static ref BACKGROUND_SETTINGS_FILE: PathBuf = LAUNCHER_FOLDER.join("background.json");
+
+    /// Path to `.background-slide` file. Contains name of the picture shown by the background slideshow
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/.background-slide`
+    pub static ref BACKGROUND_SLIDE_FILE: PathBuf = LAUNCHER_FOLDER.join(".background-slide");
+
+    /// Path to blurred copy of the background picture. Standard is `$HOME/.cache/anime-game-launcher/background-blurred.png`
+    pub static ref BLURRED_BACKGROUND_FILE: PathBuf = CACHE_FOLDER.join("background-blurred.png");
+
+    /// Path to `config-backups` folder. Contains rotating copies of the config file
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/config-backups`
+    pub static ref CONFIG_BACKUPS_FOLDER: PathBuf = LAUNCHER_FOLDER.join("config-backups");
+
+    /// Path to `.migration-journal` file. Contains files moved by the folder migration
+    /// so it can be resumed or rolled back if it was interrupted
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/.migration-journal`
+    pub static ref MIGRATION_JOURNAL_FILE: PathBuf = LAUNCHER_FOLDER.join(".migration-journal");
+
     /// Path to `.first-run` file. Used to mark launcher that it should run FirstRun window
     /// 
     /// Standard is `$HOME/.local/share/anime-game-launcher/.first-run`
     pub static ref FIRST_RUN_FILE: PathBuf = LAUNCHER_FOLDER.join(".first-run");
+
+    /// Path to `session-profiles.json` file. Contains launch settings overrides of the game sessions
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/session-profiles.json`
+    pub static ref SESSION_PROFILES_FILE: PathBuf = LAUNCHER_FOLDER.join("session-profiles.json");
+
+    /// Path to `.session-dxvk` file. Contains wine prefix which got DXVK of a session profile
+    /// so the config's DXVK is installed back if the launcher was closed during the game's run
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/.session-dxvk`
+    pub static ref SESSION_DXVK_JOURNAL_FILE: PathBuf = LAUNCHER_FOLDER.join(".session-dxvk");
+
+    /// Path to `installation-profiles.json` file. Contains named game installations the launcher can switch between
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/installation-profiles.json`
+    pub static ref INSTALLATION_PROFILES_FILE: PathBuf = LAUNCHER_FOLDER.join("installation-profiles.json");
+
+    /// Path to `.session-shortcuts` file. Used to mark launcher that it should keep desktop shortcuts of the game sessions
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/.session-shortcuts`
+    pub static ref SESSION_SHORTCUTS_FILE: PathBuf = LAUNCHER_FOLDER.join(".session-shortcuts");
+
+    /// Path to `cleanup.json` file. Contains storage cleanup settings
+    /// 
+    /// Standard is `$HOME/.local/share/anime-game-launcher/cleanup.json`
+    pub static ref CLEANUP_SETTINGS_FILE: PathBuf = LAUNCHER_FOLDER.join("cleanup.json");
 }
 
 fn main() -> anyhow::Result<()> {
     // Setup custom panic handler
     human_panic::setup_panic!(human_panic::metadata!());
 
+    // Use system language until the config is loaded, so messages are never translated to undefined one
+    i18n::set_lang(i18n::get_default_lang()).expect("Failed to set launcher language");
+
     // Create launcher folder if it isn't
     if !LAUNCHER_FOLDER.exists() {
         std::fs::create_dir_all(LAUNCHER_FOLDER.as_path()).expect("Failed to create launcher folder");
@@ -89,7 +158,7 @@ fn main() -> anyhow::Result<()> {
 
         config.launcher.language = i18n::format_lang(&i18n::get_default_lang());
 
-        Config::update_raw(config).expect("Failed to update config");
+        crate::config_history::update_raw(config).expect("Failed to update config");
     }
 
     // Force debug output
@@ -104,6 +173,16 @@ fn main() -> anyhow::Result<()> {
     // Force disable verbose tracing output in stdout
     let mut no_verbose_tracing = false;
 
+    // Unattended first run setup file
+    let mut provision = None;
+
+    // Run the game with the config from stdin. Used by the launcher itself
+    let mut launch_with_config = false;
+
+    // Game session and installation profile to switch to prior running the app
+    let mut session = None;
+    let mut profile = None;
+
     let args = std::env::args().collect::<Vec<_>>();
 
     // Parse arguments
@@ -117,10 +196,13 @@ fn main() -> anyhow::Result<()> {
         } else if args[i] == "--no-verbose-tracing" {
             no_verbose_tracing = true;
         } else if args[i] == "--session" {
-            // Switch active session prior running the app
-            if let Some(session) = args.get(i + 1) {
-                Sessions::set_current(session.to_owned())?;
-            }
+            session = args.get(i + 1).cloned();
+        } else if args[i] == "--profile" {
+            profile = args.get(i + 1).cloned();
+        } else if args[i] == "--provision" {
+            provision = args.get(i + 1).map(PathBuf::from);
+        } else if args[i] == actions::launch::LAUNCH_WITH_CONFIG_ARG {
+            launch_with_config = true;
         }
     }
 
@@ -130,7 +212,14 @@ fn main() -> anyhow::Result<()> {
         .with_filter({
             if APP_DEBUG || force_debug {
                 LevelFilter::TRACE
-            } else {
+            }
+
+            // Provisioning has no UI so its progress is reported to stdout
+            else if provision.is_some() {
+                LevelFilter::INFO
+            }
+
+            else {
                 LevelFilter::WARN
             }
         })
@@ -138,8 +227,13 @@ fn main() -> anyhow::Result<()> {
             !metadata.target().contains("rustls") && !no_verbose_tracing
         }));
 
-    // Prepare debug file logger
-    let file = std::fs::File::create(DEBUG_FILE.as_path())?;
+    // Prepare debug file logger. Game process started by the launcher
+    // appends to the launcher's log instead of overwriting it
+    let file = if launch_with_config {
+        std::fs::OpenOptions::new().create(true).append(true).open(DEBUG_FILE.as_path())?
+    } else {
+        std::fs::File::create(DEBUG_FILE.as_path())?
+    };
 
     let debug_log = tracing_subscriber::fmt::layer()
         .pretty()
@@ -156,6 +250,62 @@ fn main() -> anyhow::Result<()> {
 
     tracing::info!("Starting application ({APP_VERSION})");
 
+    if launch_with_config {
+        return actions::launch::run_from_stdin();
+    }
+
+    // Broken config is offered to be rolled back when the UI is initialized,
+    // so nothing should read it before
+    let config_error = Config::get().err();
+
+    if config_error.is_none() {
+        // Switch active session prior running the app
+        if let Some(session) = session {
+            Sessions::set_current(session)?;
+        }
+
+        // Switch installation profile prior running the app
+        if let Some(profile) = profile {
+            installation_profiles::switch(&profile)?;
+        }
+
+        if let Err(err) = session_profiles::restore_dxvk(&Config::get()?) {
+            tracing::error!("Failed to restore DXVK in the wine prefix: {err}");
+        }
+    }
+
+    // Run first run steps without UI, so it doesn't need a display
+    if let Some(path) = provision {
+        let settings = Provision::read(&path)?;
+
+        // Last reported progress in percents, to not flood the output
+        let mut last_progress = None;
+
+        actions::provision::provision(&settings, |update| {
+            match update {
+                ProvisionUpdate::SyncingComponents => tracing::info!("Updating components index"),
+                ProvisionUpdate::DownloadingWine(title) => tracing::info!("Installing wine: {title}"),
+                ProvisionUpdate::CreatingPrefix(path) => tracing::info!("Creating wine prefix: {path:?}"),
+                ProvisionUpdate::DownloadingDxvk(name) => tracing::info!("Installing DXVK: {name}"),
+                ProvisionUpdate::ApplyingDxvk(name) => tracing::info!("Applying DXVK: {name}"),
+
+                ProvisionUpdate::Progress { current, total } => {
+                    let progress = (current * 100).checked_div(total).unwrap_or(0) / 10 * 10;
+
+                    if last_progress != Some(progress) {
+                        last_progress = Some(progress);
+
+                        tracing::info!("Progress: {progress}% ({current} / {total})");
+                    }
+                }
+            }
+        })?;
+
+        tracing::info!("Launcher is set up");
+
+        return Ok(());
+    }
+
     adw::init().expect("Libadwaita initialization failed");
 
     // Register and include resources
@@ -171,49 +321,81 @@ fn main() -> anyhow::Result<()> {
     gtk::glib::set_program_name(Some("An Anime Game Launcher"));
 
     // Set global css
-    relm4::set_global_css(&format!("
-        progressbar > text {{
+    relm4::set_global_css("
+        progressbar > text {
             margin-bottom: 4px;
-        }}
-
-        window.classic-style {{
-            background: url(\"file://{}\");
-            background-repeat: no-repeat;
-            background-size: cover;
-        }}
+        }
 
-        window.classic-style progressbar {{
+        window.classic-style progressbar {
             background-color: #00000020;
             border-radius: 16px;
             padding: 8px 16px;
-        }}
+        }
 
-        window.classic-style progressbar:hover {{
+        window.classic-style progressbar:hover {
             background-color: #00000060;
             color: #ffffff;
             transition-duration: 0.5s;
             transition-timing-function: linear;
-        }}
+        }
 
-        .round-bin {{
+        .round-bin {
             border-radius: 24px;
-        }}
-    ", BACKGROUND_FILE.to_string_lossy()));
+        }
+    ");
+
+    // Load user-supplied translations
+    if let Err(err) = i18n::load_overrides(LAUNCHER_FOLDER.join("locales")) {
+        tracing::error!("{err}");
+    }
+
+    // Offer config rollback if it can't be loaded
+    // instead of panicking on CONFIG initialization
+    if let Some(err) = config_error {
+        tracing::error!("Failed to load config: {err}");
+
+        let app = RelmApp::new(APP_ID);
+
+        app.run::<ConfigRollbackApp>(err.to_string());
+
+        return Ok(());
+    }
+
+    background::apply_css();
 
     // Set UI language
-    let lang = CONFIG.launcher.language.parse().expect("Wrong language format used in config");
+    let lang = CONFIG.launcher.language.parse().unwrap_or_else(|_| {
+        tracing::error!("Wrong language format used in config: {}", CONFIG.launcher.language);
+
+        i18n::get_default_lang()
+    });
 
-    i18n::set_lang(lang).expect("Failed to set launcher language");
+    if let Err(err) = i18n::set_lang(lang) {
+        tracing::error!("Failed to set launcher language: {err}");
+
+        i18n::set_lang(i18n::get_default_lang()).expect("Failed to set launcher language");
+    }
 
     tracing::info!("Set UI language to {}", i18n::get_lang());
 
+    if let Err(err) = config_history::backup() {
+        tracing::warn!("Failed to backup config: {err}");
+    }
+
+    let ctx = AppContext::new(i18n::get_lang());
+
+    // Report config problems. They're shown in the main window as well
+    for issue in config_validator::validate(&ctx.config()) {
+        tracing::warn!("Config issue ({:?}) in {}: {}", issue.severity, issue.field, issue.description);
+    }
+
     // Run FirstRun window if .first-run file persist
     if FIRST_RUN_FILE.exists() {
         // Create the app
         let app = RelmApp::new(APP_ID);
 
         // Show first run window
-        app.run::<FirstRunApp>(());
+        app.run::<FirstRunApp>(ctx);
     }
 
     // Run the app if everything's ready
@@ -224,13 +406,13 @@ fn main() -> anyhow::Result<()> {
 
             match state {
                 LauncherState::Launch => {
-                    anime_launcher_sdk::genshin::game::run().expect("Failed to run the game");
+                    ctx.launch().expect("Failed to run the game");
 
                     return Ok(());
                 }
 
                 LauncherState::PredownloadAvailable { .. } if just_run_game => {
-                    anime_launcher_sdk::genshin::game::run().expect("Failed to run the game");
+                    ctx.launch().expect("Failed to run the game");
 
                     return Ok(());
                 }
@@ -243,7 +425,7 @@ fn main() -> anyhow::Result<()> {
         let app = RelmApp::new(APP_ID);
 
         // Show main window
-        app.run::<App>(());
+        app.run::<App>(ctx);
     }
 
     Ok(())
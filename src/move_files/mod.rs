@@ -0,0 +1,907 @@
+use std::path::{Path, PathBuf};
+use std::fs::{File, Metadata};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+
+use serde::{Serialize, Deserialize};
+
+use anime_launcher_sdk::anime_game_core::installer::free_space;
+
+mod transfer;
+
+/// Size of the buffer used to copy files between filesystems
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Extension added to the files while they're being copied
+const PARTIAL_EXTENSION: &str = "migrating";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotEnoughSpace {
+    pub path: PathBuf,
+    pub required: u64,
+    pub available: u64
+}
+
+impl std::fmt::Display for NotEnoughSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Not enough free space in {:?}: {} bytes required, {} bytes available", self.path, self.required, self.available)
+    }
+}
+
+impl std::error::Error for NotEnoughSpace {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFailed {
+    /// Amount of files which weren't moved
+    pub failures: usize
+}
+
+impl std::fmt::Display for TransferFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to move {} files", self.failures)
+    }
+}
+
+impl std::error::Error for TransferFailed {}
+
+/// Filesystem operations used to move the files
+///
+/// Replaced in tests to emulate moving files between different filesystems
+pub(crate) trait Filesystem {
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        std::fs::rename(from, to)
+    }
+
+    fn is_same_disk(&self, a: &Path, b: &Path) -> bool {
+        free_space::is_same_disk(a, b)
+    }
+
+    fn available(&self, path: &Path) -> Option<u64> {
+        free_space::available(path)
+    }
+
+    /// Check that the copy of the file is the same as the source one
+    fn verify(&self, source: &Path, copy: &Path) -> anyhow::Result<()> {
+        transfer::verify(source, copy)
+    }
+}
+
+/// Filesystems of the system
+pub(crate) struct SystemFilesystem;
+
+impl Filesystem for SystemFilesystem {}
+
+/// Filesystems with the given folder mounted as another disk
+#[cfg(test)]
+pub(crate) struct OtherDisk {
+    pub root: PathBuf,
+    pub available: Option<u64>,
+
+    /// Change copied files before verifying them
+    pub corrupt: bool
+}
+
+#[cfg(test)]
+impl Filesystem for OtherDisk {
+    fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+        if self.is_same_disk(from, to) {
+            std::fs::rename(from, to)
+        } else {
+            Err(std::io::Error::from_raw_os_error(libc::EXDEV))
+        }
+    }
+
+    fn is_same_disk(&self, a: &Path, b: &Path) -> bool {
+        a.starts_with(&self.root) == b.starts_with(&self.root)
+    }
+
+    fn available(&self, path: &Path) -> Option<u64> {
+        if path.starts_with(&self.root) {
+            self.available
+        } else {
+            free_space::available(path)
+        }
+    }
+
+    fn verify(&self, source: &Path, copy: &Path) -> anyhow::Result<()> {
+        if self.corrupt {
+            use std::os::unix::fs::FileExt;
+
+            std::fs::OpenOptions::new().write(true).open(copy)?.write_at(b"!", 0)?;
+        }
+
+        transfer::verify(source, copy)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransferMode {
+    /// Copy files between filesystems keeping only their permissions
+    #[default]
+    Fast,
+
+    /// Copy files with their metadata, hard links and holes,
+    /// and verify the copies before removing the source files.
+    /// Files which failed to be moved are reported and kept in the source folder
+    Verified
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveUpdate {
+    Progress {
+        moved: u64,
+        total: u64
+    },
+
+    /// File wasn't moved in the verified mode
+    Failed {
+        /// Path relative to the moving folder
+        path: PathBuf,
+        error: String
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct JournalHeader {
+    from: PathBuf,
+    to: PathBuf
+}
+
+/// Journal of the files moving
+///
+/// First line of the journal file is a json header with the moving folders,
+/// every next line is a relative path of the entry which was moved.
+/// It allows to resume an interrupted moving or to roll it back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Journal {
+    path: PathBuf,
+
+    pub from: PathBuf,
+    pub to: PathBuf,
+
+    /// Relative paths of the moved entries
+    pub moved: Vec<PathBuf>
+}
+
+impl Journal {
+    /// Read journal file if it exists
+    pub fn read(path: impl AsRef<Path>) -> anyhow::Result<Option<Self>> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let mut lines = BufReader::new(File::open(path)?).lines();
+
+        let Some(header) = lines.next() else {
+            anyhow::bail!("Journal file is empty");
+        };
+
+        let header = serde_json::from_str::<JournalHeader>(&header?)?;
+
+        let moved = lines.map_while(Result::ok)
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect();
+
+        Ok(Some(Self {
+            path: path.to_path_buf(),
+            from: header.from,
+            to: header.to,
+            moved
+        }))
+    }
+
+    /// Continue the journal of the same moving, or start a new one
+    fn open(path: &Path, from: &Path, to: &Path) -> anyhow::Result<Self> {
+        if let Some(journal) = Self::read(path)? {
+            if journal.from == from && journal.to == to {
+                tracing::info!("Resuming interrupted moving: {:?} -> {:?}", from, to);
+
+                return Ok(journal);
+            }
+
+            anyhow::bail!("Another moving was interrupted: {:?} -> {:?}", journal.from, journal.to);
+        }
+
+        let header = JournalHeader {
+            from: from.to_path_buf(),
+            to: to.to_path_buf()
+        };
+
+        std::fs::write(path, serde_json::to_string(&header)? + "\n")?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            from: header.from,
+            to: header.to,
+            moved: Vec::new()
+        })
+    }
+
+    fn append(&mut self, entry: &Path) -> std::io::Result<()> {
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&self.path)?;
+
+        writeln!(file, "{}", entry.to_string_lossy())?;
+
+        file.sync_data()?;
+
+        self.moved.push(entry.to_path_buf());
+
+        Ok(())
+    }
+
+    /// Move all the recorded entries back and remove the journal
+    ///
+    /// Files are moved in the verified mode
+    pub fn rollback(self, mut updater: impl FnMut(MoveUpdate)) -> anyhow::Result<()> {
+        let mut total = 0;
+
+        for entry in &self.moved {
+            total += get_size(self.to.join(entry)).unwrap_or(0);
+        }
+
+        let mut mover = Mover::new(None, TransferMode::Verified, total, &SystemFilesystem, &mut updater);
+
+        for entry in self.moved.iter().rev() {
+            let source = self.to.join(entry);
+
+            if !source.exists() && !source.is_symlink() {
+                continue;
+            }
+
+            let target = self.from.join(entry);
+
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            mover.move_entry(&source, &target, entry)?;
+        }
+
+        // Copies of the files which were being moved when the moving was interrupted
+        remove_partial_files(&self.to);
+        remove_empty_folders(&self.to);
+
+        // Files which failed to be moved back are kept in the target folder
+        // and listed to the user, so there's nothing to resume
+        std::fs::remove_file(&self.path)?;
+
+        if mover.failures > 0 {
+            return Err(TransferFailed { failures: mover.failures }.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Get total size of the files in the given path. Symlinks are not followed
+pub fn get_size(path: impl AsRef<Path>) -> std::io::Result<u64> {
+    let path = path.as_ref();
+    let metadata = path.symlink_metadata()?;
+
+    if metadata.is_symlink() {
+        Ok(0)
+    }
+
+    else if metadata.is_dir() {
+        let mut size = 0;
+
+        for entry in path.read_dir()?.flatten() {
+            size += get_size(entry.path())?;
+        }
+
+        Ok(size)
+    }
+
+    else {
+        Ok(metadata.len())
+    }
+}
+
+/// Get the closest existing parent of the given path
+pub fn get_existing_parent(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|path| path.exists())
+}
+
+/// Get path the file is copied to before being renamed to the target one
+fn get_partial_path(target: &Path) -> PathBuf {
+    target.with_extension(match target.extension() {
+        Some(ext) => format!("{}.{PARTIAL_EXTENSION}", ext.to_string_lossy()),
+        None => PARTIAL_EXTENSION.to_string()
+    })
+}
+
+/// Remove files left by the interrupted copying from the folder and its subfolders
+fn remove_partial_files(path: &Path) {
+    let Ok(entries) = path.read_dir() else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => remove_partial_files(&path),
+
+            Ok(kind) if kind.is_file() && path.extension().map(|ext| ext == PARTIAL_EXTENSION).unwrap_or(false) => {
+                if let Err(err) = std::fs::remove_file(&path) {
+                    tracing::warn!("Failed to remove partially copied file {:?}: {err}", path);
+                }
+            }
+
+            _ => ()
+        }
+    }
+}
+
+/// Remove the folder if it contains only empty folders
+fn remove_empty_folders(path: &Path) {
+    if let Ok(entries) = path.read_dir() {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|kind| kind.is_dir()).unwrap_or(false) {
+                remove_empty_folders(&entry.path());
+            }
+        }
+    }
+
+    let _ = std::fs::remove_dir(path);
+}
+
+struct Mover<'a, T: FnMut(MoveUpdate)> {
+    journal: Option<Journal>,
+    mode: TransferMode,
+    filesystem: &'a dyn Filesystem,
+
+    moved: u64,
+    total: u64,
+
+    /// Amount of files which weren't moved in the verified mode
+    failures: usize,
+
+    /// Targets of the already copied files with multiple hard links and amount of their
+    /// links which weren't moved yet, by their device and inode
+    hardlinks: HashMap<(u64, u64), (PathBuf, u64)>,
+
+    updater: &'a mut T
+}
+
+impl<'a, T: FnMut(MoveUpdate)> Mover<'a, T> {
+    fn new(journal: Option<Journal>, mode: TransferMode, total: u64, filesystem: &'a dyn Filesystem, updater: &'a mut T) -> Self {
+        Mover {
+            journal,
+            mode,
+            filesystem,
+            moved: 0,
+            total,
+            failures: 0,
+            hardlinks: HashMap::new(),
+            updater
+        }
+    }
+
+    fn progress(&mut self, bytes: u64) {
+        self.moved += bytes;
+
+        (self.updater)(MoveUpdate::Progress {
+            moved: self.moved,
+            total: self.total
+        });
+    }
+
+    fn entry_moved(&mut self, relative: &Path, size: u64) -> std::io::Result<()> {
+        if let Some(journal) = &mut self.journal {
+            journal.append(relative)?;
+        }
+
+        self.progress(size);
+
+        Ok(())
+    }
+
+    fn move_folder(&mut self, from: &Path, to: &Path, relative: &Path) -> anyhow::Result<()> {
+        for entry in from.read_dir()?.flatten() {
+            let relative = relative.join(entry.file_name());
+
+            self.move_entry(&entry.path(), &to.join(entry.file_name()), &relative)?;
+        }
+
+        Ok(())
+    }
+
+    fn move_entry(&mut self, source: &Path, target: &Path, relative: &Path) -> anyhow::Result<()> {
+        let size = get_size(source)?;
+
+        if self.filesystem.rename(source, target).is_ok() {
+            self.entry_moved(relative, size)?;
+
+            return Ok(());
+        }
+
+        let metadata = source.symlink_metadata()?;
+
+        if metadata.is_symlink() {
+            let link_target = std::fs::read_link(source)?;
+
+            // Left by the interrupted moving
+            if target.is_symlink() {
+                std::fs::remove_file(target)?;
+            }
+
+            std::os::unix::fs::symlink(link_target, target)?;
+
+            self.entry_moved(relative, 0)?;
+
+            std::fs::remove_file(source)?;
+        }
+
+        else if metadata.is_dir() {
+            std::fs::create_dir_all(target)?;
+
+            let failures = self.failures;
+
+            self.move_folder(source, target, relative)?;
+
+            // Fails if something wasn't moved so the source files are never lost
+            if let Err(err) = std::fs::remove_dir(source) {
+                if self.failures == failures {
+                    return Err(err.into());
+                }
+            }
+        }
+
+        else if self.mode == TransferMode::Verified {
+            let moved = self.moved;
+
+            if let Err(err) = self.transfer_file(source, target, relative, &metadata) {
+                tracing::error!("Failed to move file {:?}: {err}", source);
+
+                // Keep the progress consistent as the file is skipped
+                self.moved = moved;
+                self.failures += 1;
+
+                self.progress(size);
+
+                (self.updater)(MoveUpdate::Failed {
+                    path: relative.to_path_buf(),
+                    error: err.to_string()
+                });
+            }
+        }
+
+        else {
+            let partial = get_partial_path(target);
+
+            self.copy_file(source, &partial)?;
+
+            std::fs::set_permissions(&partial, metadata.permissions())?;
+            std::fs::rename(&partial, target)?;
+
+            // Copied bytes were already reported
+            self.entry_moved(relative, 0)?;
+
+            std::fs::remove_file(source)?;
+        }
+
+        Ok(())
+    }
+
+    /// Copy the file with its metadata, verify the copy and remove the source one
+    ///
+    /// Hard links to the already moved files are re-created instead of being copied again
+    fn transfer_file(&mut self, source: &Path, target: &Path, relative: &Path, metadata: &Metadata) -> anyhow::Result<()> {
+        let inode = (metadata.dev(), metadata.ino());
+
+        // Source links of the moved file are removed so the links count of the next ones
+        // is decreased, and it can't be used to check if the file was already moved
+        if let Some((original, remaining)) = self.hardlinks.get_mut(&inode) {
+            let original = original.clone();
+
+            *remaining -= 1;
+
+            // Forget the inode so it's not confused with a new file reusing it
+            if *remaining == 0 {
+                self.hardlinks.remove(&inode);
+            }
+
+            // Left by the interrupted moving
+            if target.exists() {
+                std::fs::remove_file(target)?;
+            }
+
+            std::fs::hard_link(&original, target)?;
+
+            self.entry_moved(relative, metadata.len())?;
+
+            std::fs::remove_file(source)?;
+
+            return Ok(());
+        }
+
+        let partial = get_partial_path(target);
+
+        let result = transfer::copy_file(source, &partial, metadata, |bytes| self.progress(bytes))
+            .map_err(anyhow::Error::from)
+            .and_then(|_| self.filesystem.verify(source, &partial));
+
+        if let Err(err) = result {
+            let _ = std::fs::remove_file(&partial);
+
+            return Err(err);
+        }
+
+        std::fs::rename(&partial, target)?;
+
+        // Copied bytes were already reported
+        self.entry_moved(relative, 0)?;
+
+        std::fs::remove_file(source)?;
+
+        if metadata.nlink() > 1 {
+            self.hardlinks.insert(inode, (target.to_path_buf(), metadata.nlink() - 1));
+        }
+
+        Ok(())
+    }
+
+    fn copy_file(&mut self, source: &Path, target: &Path) -> std::io::Result<()> {
+        let mut source = File::open(source)?;
+        let mut target = File::create(target)?;
+
+        let mut buffer = vec![0; COPY_BUFFER_SIZE];
+
+        loop {
+            let read = source.read(&mut buffer)?;
+
+            if read == 0 {
+                break;
+            }
+
+            target.write_all(&buffer[..read])?;
+
+            self.progress(read as u64);
+        }
+
+        target.sync_all()
+    }
+}
+
+/// Move files from one folder to another
+///
+/// `updater` is called with the amount of moved and total bytes, and with the files
+/// which failed to be moved in the verified mode. Such files don't abort the moving,
+/// but `TransferFailed` error is returned in the end.
+///
+/// If `journal` is given, every moved entry is recorded there, so an interrupted moving
+/// is resumed by calling this function with the same arguments again,
+/// or rolled back with `Journal::rollback`. The journal is removed when all the files are moved
+#[inline]
+pub fn move_files_with_progress(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    journal: Option<impl AsRef<Path>>,
+    mode: TransferMode,
+    updater: impl FnMut(MoveUpdate)
+) -> anyhow::Result<()> {
+    move_files_with_filesystem(from.as_ref(), to.as_ref(), journal.as_ref().map(AsRef::as_ref), mode, &SystemFilesystem, updater)
+}
+
+pub(crate) fn move_files_with_filesystem(
+    from: &Path,
+    to: &Path,
+    journal: Option<&Path>,
+    mode: TransferMode,
+    filesystem: &dyn Filesystem,
+    mut updater: impl FnMut(MoveUpdate)
+) -> anyhow::Result<()> {
+    let total = get_size(from)?;
+
+    let to_parent = get_existing_parent(to).unwrap_or(to);
+
+    // Files are copied between different filesystems
+    if !filesystem.is_same_disk(from, to_parent) {
+        if let Some(available) = filesystem.available(to_parent) {
+            if available < total {
+                return Err(NotEnoughSpace {
+                    path: to.to_path_buf(),
+                    required: total,
+                    available
+                }.into());
+            }
+        }
+    }
+
+    std::fs::create_dir_all(to)?;
+
+    let journal = match journal {
+        Some(journal) => Some(Journal::open(journal, from, to)?),
+        None => None
+    };
+
+    let mut mover = Mover::new(journal, mode, total, filesystem, &mut updater);
+
+    mover.move_folder(from, to, Path::new(""))?;
+
+    // Files which failed to be moved are kept in the source folder, so the moving
+    // is finished either way and the journal must not block the next one
+    if let Some(journal) = &mover.journal {
+        // Copies left by the interrupted moving if it was resumed
+        remove_partial_files(to);
+
+        std::fs::remove_file(&journal.path)?;
+    }
+
+    if mover.failures > 0 {
+        return Err(TransferFailed { failures: mover.failures }.into());
+    }
+
+    Ok(())
+}
+
+/// Move files from one folder to another
+#[inline]
+pub fn move_files(from: impl AsRef<Path>, to: impl AsRef<Path>) -> anyhow::Result<()> {
+    move_files_with_progress(from, to, None::<&Path>, TransferMode::Fast, |_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    /// Create empty temp folder for the test with `disk` subfolder used as another disk
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aagl-move-files-test-{name}-{}", std::process::id()));
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+
+        std::fs::create_dir_all(path.join("disk")).unwrap();
+
+        path
+    }
+
+    fn other_disk(temp: &Path) -> OtherDisk {
+        OtherDisk {
+            root: temp.join("disk"),
+            available: None,
+            corrupt: false
+        }
+    }
+
+    /// Create `a.txt` and `b/c.txt` files in the folder
+    fn create_files(path: &Path) {
+        std::fs::create_dir_all(path.join("b")).unwrap();
+
+        std::fs::write(path.join("a.txt"), "a").unwrap();
+        std::fs::write(path.join("b/c.txt"), "cc").unwrap();
+    }
+
+    fn set_xattr(path: &Path, name: &str, value: &[u8]) -> bool {
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        let name = CString::new(name).unwrap();
+
+        unsafe { libc::setxattr(path.as_ptr(), name.as_ptr(), value.as_ptr() as *const libc::c_void, value.len(), 0) == 0 }
+    }
+
+    fn get_xattr(path: &Path, name: &str) -> Option<Vec<u8>> {
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        let name = CString::new(name).unwrap();
+
+        let mut value = vec![0u8; 64];
+
+        let len = unsafe { libc::getxattr(path.as_ptr(), name.as_ptr(), value.as_mut_ptr() as *mut libc::c_void, value.len()) };
+
+        if len < 0 {
+            return None;
+        }
+
+        value.truncate(len as usize);
+
+        Some(value)
+    }
+
+    #[test]
+    fn fast_copy() {
+        let temp = temp_folder("fast");
+
+        let from = temp.join("from");
+        let to = temp.join("disk/to");
+
+        create_files(&from);
+
+        std::fs::set_permissions(from.join("a.txt"), std::fs::Permissions::from_mode(0o751)).unwrap();
+
+        let mut progress = Vec::new();
+
+        move_files_with_filesystem(&from, &to, None, TransferMode::Fast, &other_disk(&temp), |update| progress.push(update)).unwrap();
+
+        assert_eq!(std::fs::read_to_string(to.join("a.txt")).unwrap(), "a");
+        assert_eq!(std::fs::read_to_string(to.join("b/c.txt")).unwrap(), "cc");
+        assert_eq!(to.join("a.txt").metadata().unwrap().permissions().mode() & 0o777, 0o751);
+
+        assert!(!from.join("a.txt").exists());
+        assert!(!from.join("b").exists());
+
+        assert_eq!(progress.last(), Some(&MoveUpdate::Progress { moved: 3, total: 3 }));
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn verified_copy() {
+        let temp = temp_folder("verified");
+
+        let from = temp.join("from");
+        let to = temp.join("disk/to");
+
+        create_files(&from);
+
+        let file = from.join("a.txt");
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        File::options().write(true).open(&file).unwrap()
+            .set_modified(modified).unwrap();
+
+        // Not every filesystem supports user attributes
+        let xattr = set_xattr(&file, "user.aagl", b"value");
+
+        // 8 MiB file with only 2 bytes of data
+        let sparse = File::create(from.join("sparse")).unwrap();
+
+        sparse.set_len(8 * 1024 * 1024).unwrap();
+
+        {
+            use std::os::unix::fs::FileExt;
+
+            sparse.write_at(b"s", 0).unwrap();
+            sparse.write_at(b"e", 8 * 1024 * 1024 - 1).unwrap();
+        }
+
+        std::os::unix::fs::symlink("a.txt", from.join("link")).unwrap();
+
+        move_files_with_filesystem(&from, &to, None, TransferMode::Verified, &other_disk(&temp), |_| ()).unwrap();
+
+        let metadata = to.join("a.txt").metadata().unwrap();
+
+        assert_eq!(std::fs::read_to_string(to.join("a.txt")).unwrap(), "a");
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o640);
+        assert_eq!(metadata.modified().unwrap(), modified);
+
+        if xattr {
+            assert_eq!(get_xattr(&to.join("a.txt"), "user.aagl").as_deref(), Some(b"value".as_slice()));
+        }
+
+        let sparse = to.join("sparse").metadata().unwrap();
+
+        assert_eq!(sparse.len(), 8 * 1024 * 1024);
+        assert!(sparse.blocks() * 512 < sparse.len());
+
+        assert_eq!(std::fs::read_link(to.join("link")).unwrap(), PathBuf::from("a.txt"));
+
+        assert!(!from.exists() || get_size(&from).unwrap() == 0);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn hardlinks() {
+        let temp = temp_folder("hardlinks");
+
+        let from = temp.join("from");
+        let to = temp.join("disk/to");
+
+        create_files(&from);
+
+        std::fs::hard_link(from.join("a.txt"), from.join("b/a.txt")).unwrap();
+
+        move_files_with_filesystem(&from, &to, None, TransferMode::Verified, &other_disk(&temp), |_| ()).unwrap();
+
+        let original = to.join("a.txt").metadata().unwrap();
+        let link = to.join("b/a.txt").metadata().unwrap();
+
+        assert_eq!(original.ino(), link.ino());
+        assert_eq!(original.nlink(), 2);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn corrupted_copy() {
+        let temp = temp_folder("corrupted");
+
+        let from = temp.join("from");
+        let to = temp.join("disk/to");
+
+        create_files(&from);
+
+        let filesystem = OtherDisk {
+            corrupt: true,
+            ..other_disk(&temp)
+        };
+
+        let mut failed = Vec::new();
+
+        let result = move_files_with_filesystem(&from, &to, None, TransferMode::Verified, &filesystem, |update| {
+            if let MoveUpdate::Failed { path, error } = update {
+                failed.push((path, error));
+            }
+        });
+
+        assert_eq!(result.unwrap_err().downcast_ref::<TransferFailed>(), Some(&TransferFailed { failures: 2 }));
+
+        failed.sort();
+
+        assert_eq!(failed[0].0, PathBuf::from("a.txt"));
+        assert_eq!(failed[1].0, PathBuf::from("b/c.txt"));
+        assert!(failed[0].1.contains("hash mismatch"));
+
+        // Source files are kept and neither broken copies nor partial files are left
+        assert_eq!(std::fs::read_to_string(from.join("a.txt")).unwrap(), "a");
+        assert_eq!(std::fs::read_to_string(from.join("b/c.txt")).unwrap(), "cc");
+        assert_eq!(get_size(&to).unwrap(), 0);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn not_enough_space() {
+        let temp = temp_folder("space");
+
+        let from = temp.join("from");
+        let to = temp.join("disk/to");
+
+        create_files(&from);
+
+        let filesystem = OtherDisk {
+            available: Some(2),
+            ..other_disk(&temp)
+        };
+
+        let result = move_files_with_filesystem(&from, &to, None, TransferMode::Fast, &filesystem, |_| ());
+
+        assert_eq!(result.unwrap_err().downcast_ref::<NotEnoughSpace>(), Some(&NotEnoughSpace {
+            path: to.clone(),
+            required: 3,
+            available: 2
+        }));
+
+        assert!(!to.exists());
+        assert_eq!(get_size(&from).unwrap(), 3);
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+
+    #[test]
+    fn verify_copies() {
+        let temp = temp_folder("verify");
+
+        std::fs::write(temp.join("source"), "abc").unwrap();
+        std::fs::write(temp.join("same"), "abc").unwrap();
+        std::fs::write(temp.join("shorter"), "ab").unwrap();
+        std::fs::write(temp.join("changed"), "abd").unwrap();
+
+        assert!(transfer::verify(&temp.join("source"), &temp.join("same")).is_ok());
+
+        let err = transfer::verify(&temp.join("source"), &temp.join("shorter")).unwrap_err();
+
+        assert!(err.to_string().contains("size mismatch"));
+
+        let err = transfer::verify(&temp.join("source"), &temp.join("changed")).unwrap_err();
+
+        assert!(err.to_string().contains("hash mismatch"));
+
+        std::fs::remove_dir_all(temp).unwrap();
+    }
+}
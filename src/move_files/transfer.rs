@@ -0,0 +1,252 @@
+use std::path::Path;
+use std::fs::{File, FileTimes, Metadata};
+use std::io::{Error, Read, Result};
+use std::ffi::CString;
+use std::os::unix::prelude::*;
+
+use md5::{Md5, Digest};
+
+/// Amount of bytes copied at once, so the progress is reported between the calls
+const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// `ioctl` request making a copy-on-write clone of the file
+const FICLONE: libc::c_ulong = 0x40049409;
+
+/// Try to make a copy-on-write clone of the file
+///
+/// Works only on the same filesystem with reflinks support (btrfs, xfs),
+/// for example when moving files between btrfs subvolumes
+fn reflink(source: &File, target: &File) -> bool {
+    unsafe { libc::ioctl(target.as_raw_fd(), FICLONE as _, source.as_raw_fd()) == 0 }
+}
+
+/// Get `[start, end)` ranges of the file which contain data
+///
+/// Holes of the sparse files are skipped. If the filesystem doesn't support
+/// holes lookup then the whole file is returned as a single range
+fn get_data_ranges(file: &File, len: u64) -> Vec<(u64, u64)> {
+    let fd = file.as_raw_fd();
+
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+
+    while offset < len {
+        let start = unsafe { libc::lseek(fd, offset as libc::off_t, libc::SEEK_DATA) };
+
+        if start < 0 {
+            // ENXIO means there's no more data after the offset
+            if Error::last_os_error().raw_os_error() != Some(libc::ENXIO) {
+                ranges.push((offset, len));
+            }
+
+            break;
+        }
+
+        let end = unsafe { libc::lseek(fd, start, libc::SEEK_HOLE) };
+        let end = if end < 0 { len } else { (end as u64).min(len) };
+
+        ranges.push((start as u64, end));
+
+        offset = end;
+    }
+
+    ranges
+}
+
+/// Copy the range of the file using `copy_file_range`,
+/// or reading and writing it if the syscall is not supported
+fn copy_range(source: &File, target: &File, start: u64, end: u64, progress: &mut impl FnMut(u64)) -> Result<()> {
+    let mut offset_in = start as libc::loff_t;
+    let mut offset_out = start as libc::loff_t;
+
+    while (offset_in as u64) < end {
+        let len = ((end - offset_in as u64) as usize).min(CHUNK_SIZE);
+
+        let copied = unsafe {
+            libc::copy_file_range(source.as_raw_fd(), &mut offset_in, target.as_raw_fd(), &mut offset_out, len, 0)
+        };
+
+        if copied < 0 {
+            let err = Error::last_os_error();
+
+            if matches!(err.raw_os_error(), Some(libc::ENOSYS | libc::EXDEV | libc::EINVAL | libc::EOPNOTSUPP)) {
+                return copy_range_fallback(source, target, offset_in as u64, end, progress);
+            }
+
+            return Err(err);
+        }
+
+        // Source file was truncated while copying
+        if copied == 0 {
+            break;
+        }
+
+        progress(copied as u64);
+    }
+
+    Ok(())
+}
+
+fn copy_range_fallback(source: &File, target: &File, start: u64, end: u64, progress: &mut impl FnMut(u64)) -> Result<()> {
+    let mut buffer = vec![0; CHUNK_SIZE.min((end - start) as usize)];
+    let mut offset = start;
+
+    while offset < end {
+        let len = buffer.len().min((end - offset) as usize);
+        let read = source.read_at(&mut buffer[..len], offset)?;
+
+        if read == 0 {
+            break;
+        }
+
+        target.write_all_at(&buffer[..read], offset)?;
+
+        offset += read as u64;
+
+        progress(read as u64);
+    }
+
+    Ok(())
+}
+
+/// Copy extended attributes of the file. Symlinks are not followed
+///
+/// Attributes not supported by the target filesystem
+/// or requiring additional privileges are skipped
+fn copy_xattrs(source: &Path, target: &Path) -> Result<()> {
+    let source = CString::new(source.as_os_str().as_bytes())?;
+    let target = CString::new(target.as_os_str().as_bytes())?;
+
+    let len = unsafe { libc::llistxattr(source.as_ptr(), std::ptr::null_mut(), 0) };
+
+    if len < 0 {
+        let err = Error::last_os_error();
+
+        return match err.raw_os_error() {
+            Some(libc::ENOTSUP) => Ok(()),
+            _ => Err(err)
+        };
+    }
+
+    let mut names = vec![0u8; len as usize];
+
+    let len = unsafe { libc::llistxattr(source.as_ptr(), names.as_mut_ptr() as *mut libc::c_char, names.len()) };
+
+    if len < 0 {
+        return Err(Error::last_os_error());
+    }
+
+    for name in names[..len as usize].split(|byte| *byte == 0).filter(|name| !name.is_empty()) {
+        let name = CString::new(name)?;
+
+        let len = unsafe { libc::lgetxattr(source.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0) };
+
+        if len < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let mut value = vec![0u8; len as usize];
+
+        let len = unsafe { libc::lgetxattr(source.as_ptr(), name.as_ptr(), value.as_mut_ptr() as *mut libc::c_void, value.len()) };
+
+        if len < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let result = unsafe {
+            libc::lsetxattr(target.as_ptr(), name.as_ptr(), value.as_ptr() as *const libc::c_void, len as usize, 0)
+        };
+
+        if result < 0 {
+            let err = Error::last_os_error();
+
+            if !matches!(err.raw_os_error(), Some(libc::ENOTSUP | libc::EPERM)) {
+                return Err(err);
+            }
+
+            tracing::warn!("Failed to copy extended attribute {name:?} of {source:?}: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy file preserving its holes, permissions, timestamps and extended attributes
+///
+/// `progress` is called with the amount of newly copied bytes
+pub fn copy_file(source: &Path, target: &Path, metadata: &Metadata, mut progress: impl FnMut(u64)) -> Result<()> {
+    let source_file = File::open(source)?;
+    let target_file = File::create(target)?;
+
+    let len = metadata.len();
+
+    if reflink(&source_file, &target_file) {
+        progress(len);
+    }
+
+    else {
+        let mut copied = 0;
+
+        for (start, end) in get_data_ranges(&source_file, len) {
+            copy_range(&source_file, &target_file, start, end, &mut |bytes| {
+                copied += bytes;
+
+                progress(bytes);
+            })?;
+        }
+
+        // Keep the holes at the end of the sparse file
+        target_file.set_len(len)?;
+
+        // Holes weren't copied but they're a part of the file size
+        progress(len.saturating_sub(copied));
+    }
+
+    target_file.sync_all()?;
+
+    copy_xattrs(source, target)?;
+
+    target_file.set_permissions(metadata.permissions())?;
+
+    target_file.set_times(FileTimes::new()
+        .set_accessed(metadata.accessed()?)
+        .set_modified(metadata.modified()?))?;
+
+    Ok(())
+}
+
+/// Get md5 hash of the file's content
+fn get_hash(path: &Path) -> Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let mut hasher = Md5::new();
+
+    let mut buffer = vec![0; CHUNK_SIZE];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+
+        if read == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize().to_vec())
+}
+
+/// Check that the copied file has the same size and content as the source one
+pub fn verify(source: &Path, target: &Path) -> anyhow::Result<()> {
+    let source_size = source.metadata()?.len();
+    let target_size = target.metadata()?.len();
+
+    if source_size != target_size {
+        anyhow::bail!("Copied file size mismatch: {source_size} bytes expected, {target_size} bytes copied");
+    }
+
+    if get_hash(source)? != get_hash(target)? {
+        anyhow::bail!("Copied file hash mismatch");
+    }
+
+    Ok(())
+}
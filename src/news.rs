@@ -0,0 +1,197 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Deserialize};
+
+use anime_launcher_sdk::anime_game_core::minreq;
+
+use md5::{Md5, Digest};
+
+/// Seconds the news fetched from the API are used before requesting them again
+const NEWS_CACHE_EXPIRY: u64 = 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Banner {
+    pub name: String,
+
+    /// Uri of the banner picture
+    pub image: String,
+
+    /// Link opened when the banner is clicked
+    pub url: String
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PostKind {
+    Event,
+    Announcement,
+    Info
+}
+
+impl PostKind {
+    #[inline]
+    pub fn list() -> &'static [Self] {
+        &[Self::Event, Self::Announcement, Self::Info]
+    }
+
+    fn from_api(kind: &str) -> Self {
+        match kind {
+            "POST_TYPE_ACTIVITY" => Self::Event,
+            "POST_TYPE_ANNOUNCE" => Self::Announcement,
+
+            _ => Self::Info
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Post {
+    pub kind: PostKind,
+    pub title: String,
+    pub url: String,
+
+    /// Date in the `MM/DD` format as it's returned by the API
+    pub date: String
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct News {
+    pub banners: Vec<Banner>,
+    pub posts: Vec<Post>
+}
+
+impl News {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.banners.is_empty() && self.posts.is_empty()
+    }
+
+    /// Get posts of the given kind
+    pub fn posts(&self, kind: PostKind) -> impl Iterator<Item = &Post> {
+        self.posts.iter().filter(move |post| post.kind == kind)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct NewsCache {
+    /// API uri the news were requested from
+    uri: String,
+
+    news: Option<News>,
+
+    /// Timestamp of the last successful request
+    fetched_at: u64
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default()
+}
+
+/// Get news of the currently selected game edition in the launcher language
+///
+/// News are cached on disk for `NEWS_CACHE_EXPIRY` seconds,
+/// and the cached ones are returned if the API is unavailable
+pub fn get_news() -> anyhow::Result<News> {
+    let uri = crate::background::get_uri();
+    let now = timestamp();
+
+    let cache = std::fs::read(crate::NEWS_CACHE_FILE.as_path()).ok()
+        .and_then(|cache| serde_json::from_slice::<NewsCache>(&cache).ok())
+        .filter(|cache| cache.uri == uri);
+
+    if let Some(NewsCache { news: Some(news), fetched_at, .. }) = &cache {
+        if now.saturating_sub(*fetched_at) < NEWS_CACHE_EXPIRY {
+            return Ok(news.clone());
+        }
+    }
+
+    match fetch_news(&uri) {
+        Ok(news) => {
+            let cache = NewsCache {
+                uri,
+                news: Some(news.clone()),
+                fetched_at: now
+            };
+
+            std::fs::create_dir_all(crate::CACHE_FOLDER.as_path())?;
+            std::fs::write(crate::NEWS_CACHE_FILE.as_path(), serde_json::to_string_pretty(&cache)?)?;
+
+            Ok(news)
+        }
+
+        Err(err) => match cache.and_then(|cache| cache.news) {
+            Some(news) => {
+                tracing::warn!("Failed to fetch news, using the cached ones: {err}");
+
+                Ok(news)
+            }
+
+            None => Err(err)
+        }
+    }
+}
+
+fn fetch_news(uri: &str) -> anyhow::Result<News> {
+    let json = serde_json::from_slice::<serde_json::Value>(minreq::get(uri).with_timeout(10).send()?.as_bytes())?;
+
+    let Some(data) = json.get("data") else {
+        anyhow::bail!("Failed to get launcher content");
+    };
+
+    let banners = data["banner"].as_array()
+        .map(|banners| banners.iter()
+            .filter_map(|banner| Some(Banner {
+                name: banner["name"].as_str().unwrap_or_default().to_owned(),
+                image: banner["img"].as_str()?.to_owned(),
+                url: banner["url"].as_str()?.to_owned()
+            }))
+            .collect())
+        .unwrap_or_default();
+
+    let posts = data["post"].as_array()
+        .map(|posts| posts.iter()
+            .filter_map(|post| Some(Post {
+                kind: PostKind::from_api(post["type"].as_str().unwrap_or_default()),
+
+                // The API has a typo in this field's name
+                title: post["title"].as_str()
+                    .filter(|title| !title.is_empty())
+                    .or_else(|| post["tittle"].as_str())?
+                    .to_owned(),
+
+                url: post["url"].as_str()?.to_owned(),
+                date: post["show_time"].as_str().unwrap_or_default().to_owned()
+            }))
+            .collect())
+        .unwrap_or_default();
+
+    Ok(News {
+        banners,
+        posts
+    })
+}
+
+/// Get path to the downloaded banner picture, downloading it if needed
+pub fn get_banner_picture(banner: &Banner) -> anyhow::Result<PathBuf> {
+    let hash = format!("{:x}", Md5::digest(banner.image.as_bytes()));
+
+    let path = crate::NEWS_CACHE_FOLDER.join(hash);
+
+    if !path.exists() {
+        let picture = minreq::get(&banner.image).with_timeout(10).send()?;
+
+        if picture.status_code != 200 {
+            anyhow::bail!("Failed to download banner picture: {} {}", picture.status_code, picture.reason_phrase);
+        }
+
+        std::fs::create_dir_all(crate::NEWS_CACHE_FOLDER.as_path())?;
+        std::fs::write(&path, picture.as_bytes())?;
+    }
+
+    Ok(path)
+}
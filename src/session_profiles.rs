@@ -0,0 +1,472 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Serialize, Deserialize};
+
+use anime_launcher_sdk::wincompatlib::prelude::*;
+
+use anime_launcher_sdk::genshin::config::Schema;
+use anime_launcher_sdk::config::schema_blanks::prelude::Gamescope;
+
+use anime_launcher_sdk::components::wine::UnifiedWine;
+
+use anime_launcher_sdk::sessions::SessionsExt;
+use anime_launcher_sdk::genshin::sessions::Sessions;
+
+use crate::tr;
+
+/// Optional launch settings attached to a game session
+///
+/// Every `Some` field overrides the related value from the global config
+/// when the game is launched with this session selected
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionProfile {
+    /// Variables added on top of `game.environment`
+    pub environment: Option<HashMap<String, String>>,
+
+    /// Name of the wine build from `game.wine.builds`
+    pub wine: Option<String>,
+
+    /// Name of the DXVK build from `game.dxvk.builds`
+    pub dxvk: Option<String>,
+
+    pub gamescope: Option<Gamescope>,
+    pub fps: Option<u64>,
+
+    /// Replacement for `game.command`
+    pub command: Option<String>,
+
+    /// Shell command executed before the game's launch
+    pub pre_launch: Option<String>,
+
+    /// Shell command executed after the game was closed
+    pub post_launch: Option<String>,
+
+    /// Picture shown in the main window instead of the background from the launcher settings
+    pub background: Option<PathBuf>
+}
+
+impl SessionProfile {
+    pub fn is_empty(&self) -> bool {
+        self.environment.as_ref().map(|env| env.is_empty()).unwrap_or(true) &&
+        self.wine.is_none() &&
+        self.dxvk.is_none() &&
+        self.gamescope.is_none() &&
+        self.fps.is_none() &&
+        self.command.is_none() &&
+        self.pre_launch.is_none() &&
+        self.post_launch.is_none() &&
+        self.background.is_none()
+    }
+
+    /// Merge profile's overrides into the given config
+    pub fn apply(&self, config: &mut Schema) {
+        if let Some(environment) = &self.environment {
+            for (key, value) in environment {
+                config.game.environment.insert(key.clone(), value.clone());
+            }
+        }
+
+        if let Some(wine) = &self.wine {
+            config.game.wine.selected = Some(wine.clone());
+        }
+
+        if let Some(dxvk) = &self.dxvk {
+            config.game.dxvk.selected = Some(dxvk.clone());
+        }
+
+        if let Some(gamescope) = &self.gamescope {
+            config.game.enhancements.gamescope = gamescope.clone();
+        }
+
+        if let Some(fps) = self.fps {
+            config.game.enhancements.fps_unlocker.config.fps = fps;
+        }
+
+        if let Some(command) = &self.command {
+            config.game.command = Some(command.clone());
+        }
+    }
+
+    /// Get list of human readable changes this profile makes to the given config
+    pub fn diff(&self, config: &Schema) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        if let Some(environment) = &self.environment {
+            for (key, value) in environment {
+                match config.game.environment.get(key) {
+                    Some(global) if global == value => (),
+
+                    Some(global) => changes.push(format!("{key}: {global} → {value}")),
+                    None => changes.push(format!("{key}={value}"))
+                }
+            }
+        }
+
+        if let Some(wine) = &self.wine {
+            if config.game.wine.selected.as_ref() != Some(wine) {
+                changes.push(format!("{}: {} → {wine}", tr!("wine"), config.game.wine.selected.as_deref().unwrap_or("?")));
+            }
+        }
+
+        if let Some(dxvk) = &self.dxvk {
+            if config.game.dxvk.selected.as_ref() != Some(dxvk) {
+                changes.push(format!("DXVK: {} → {dxvk}", config.game.dxvk.selected.as_deref().unwrap_or("?")));
+            }
+        }
+
+        if let Some(gamescope) = &self.gamescope {
+            if gamescope.enabled != config.game.enhancements.gamescope.enabled {
+                changes.push(format!("{}: {}", tr!("gamescope"), if gamescope.enabled { tr!("enabled") } else { tr!("disabled") }));
+            }
+
+            else {
+                changes.push(tr!("gamescope"));
+            }
+        }
+
+        if let Some(fps) = self.fps {
+            if fps != config.game.enhancements.fps_unlocker.config.fps {
+                changes.push(format!("FPS: {} → {fps}", config.game.enhancements.fps_unlocker.config.fps));
+            }
+        }
+
+        if let Some(command) = &self.command {
+            if config.game.command.as_ref() != Some(command) {
+                changes.push(format!("%command%: {command}"));
+            }
+        }
+
+        if let Some(command) = &self.pre_launch {
+            changes.push(format!("{}: {command}", tr!("pre-launch-hook")));
+        }
+
+        if let Some(command) = &self.post_launch {
+            changes.push(format!("{}: {command}", tr!("post-launch-hook")));
+        }
+
+        if let Some(background) = &self.background {
+            changes.push(format!("{}: {}", tr!("background"), background.to_string_lossy()));
+        }
+
+        changes
+    }
+}
+
+/// Parse environment variables from `KEY=value` lines
+///
+/// Values are kept as is, so they can contain `;` and `=`
+pub fn parse_environment(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+/// Format environment variables as `KEY=value` lines sorted by keys
+pub fn format_environment(environment: &HashMap<String, String>) -> String {
+    let mut variables = environment.iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>();
+
+    variables.sort();
+
+    variables.join("\n")
+}
+
+/// Get all the stored session profiles
+pub fn list() -> anyhow::Result<HashMap<String, SessionProfile>> {
+    if !crate::SESSION_PROFILES_FILE.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let profiles = std::fs::read(crate::SESSION_PROFILES_FILE.as_path())?;
+
+    Ok(serde_json::from_slice(&profiles)?)
+}
+
+/// Get profile of the given session, or an empty one if it wasn't set
+pub fn get(name: impl AsRef<str>) -> anyhow::Result<SessionProfile> {
+    Ok(list()?.remove(name.as_ref()).unwrap_or_default())
+}
+
+/// Store profile for the given session. Empty profiles are removed
+pub fn set(name: impl ToString, profile: SessionProfile) -> anyhow::Result<()> {
+    let mut profiles = list()?;
+
+    if profile.is_empty() {
+        profiles.remove(&name.to_string());
+    } else {
+        profiles.insert(name.to_string(), profile);
+    }
+
+    std::fs::write(crate::SESSION_PROFILES_FILE.as_path(), serde_json::to_string_pretty(&profiles)?)?;
+
+    Ok(())
+}
+
+/// Remove profile of the given session
+pub fn remove(name: impl AsRef<str>) -> anyhow::Result<()> {
+    let mut profiles = list()?;
+
+    if profiles.remove(name.as_ref()).is_some() {
+        std::fs::write(crate::SESSION_PROFILES_FILE.as_path(), serde_json::to_string_pretty(&profiles)?)?;
+    }
+
+    Ok(())
+}
+
+/// Get profile of the currently selected session
+pub fn current() -> anyhow::Result<Option<(String, SessionProfile)>> {
+    match Sessions::get_current()? {
+        Some(name) => {
+            let profile = get(&name)?;
+
+            Ok(Some((name, profile)))
+        }
+
+        None => Ok(None)
+    }
+}
+
+fn run_hook(command: &str) {
+    tracing::info!("Running launch hook: {command}");
+
+    match std::process::Command::new("bash").arg("-c").arg(command).status() {
+        Ok(status) if !status.success() => tracing::warn!("Launch hook exited with status {status}"),
+        Err(err) => tracing::error!("Failed to run launch hook: {err}"),
+
+        _ => ()
+    }
+}
+
+/// Install DXVK build with the given name into the config's wine prefix,
+/// or remove DXVK from the prefix if `None` is given
+fn install_dxvk(config: &Schema, dxvk: Option<&str>) -> anyhow::Result<()> {
+    let wine = match config.get_selected_wine()? {
+        Some(version) => match version.to_wine(&config.components.path, Some(config.game.wine.builds.join(&version.name))) {
+            UnifiedWine::Default(wine) => wine,
+
+            // Proton uses its own DXVK
+            UnifiedWine::Proton(_) => return Ok(())
+        }
+
+        None => Wine::default()
+    };
+
+    let wine = wine.with_prefix(&config.game.wine.prefix);
+
+    match dxvk {
+        Some(dxvk) => {
+            tracing::info!("Installing DXVK to the wine prefix: {dxvk}");
+
+            Dxvk::install(&wine, config.game.dxvk.builds.join(dxvk), InstallParams::default())?;
+        }
+
+        None => {
+            tracing::info!("Removing DXVK from the wine prefix");
+
+            Dxvk::uninstall(&wine, InstallParams::default())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prefix which got DXVK of a session profile for the game's run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DxvkJournal {
+    /// Launcher process which runs the game
+    pid: u32,
+
+    prefix: PathBuf
+}
+
+/// Install the config's DXVK back into the wine prefix changed by a session profile
+///
+/// Prefix is only restored after the game was closed, so the journal
+/// of another running launcher process is skipped
+pub fn restore_dxvk(config: &Schema) -> anyhow::Result<()> {
+    let path = crate::SESSION_DXVK_JOURNAL_FILE.as_path();
+
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let journal: DxvkJournal = serde_json::from_slice(&std::fs::read(path)?)?;
+
+    if journal.pid != std::process::id() && PathBuf::from(format!("/proc/{}", journal.pid)).exists() {
+        return Ok(());
+    }
+
+    let mut config = config.clone();
+
+    config.game.wine.prefix = journal.prefix;
+
+    install_dxvk(&config, config.game.dxvk.selected.as_deref())?;
+
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+/// Get profile of the current session if it overrides anything
+pub fn active() -> Option<(String, SessionProfile)> {
+    match current() {
+        Ok(Some((name, profile))) if !profile.is_empty() => Some((name, profile)),
+
+        Ok(_) => None,
+
+        Err(err) => {
+            tracing::error!("Failed to get current session profile: {err}");
+
+            None
+        }
+    }
+}
+
+/// Run the game with the session profile merged over the given config
+///
+/// Merged config only exists in memory and is passed to the `run` callback.
+/// DXVK from the session profile is installed into the wine prefix for the game's run,
+/// and the config's one is installed back after the game was closed. Changed prefix
+/// is recorded to the journal so it's restored by [`restore_dxvk`] if the launcher was killed
+pub fn run_with(config: &Schema, profile: &SessionProfile, run: impl FnOnce(&Schema) -> anyhow::Result<()>) -> anyhow::Result<()> {
+    let mut merged = config.clone();
+
+    profile.apply(&mut merged);
+
+    if let Some(wine) = &profile.wine {
+        if !merged.game.wine.builds.join(wine).exists() {
+            anyhow::bail!(tr!("session-wine-not-downloaded", {
+                "wine" = wine.clone()
+            }));
+        }
+    }
+
+    let dxvk = profile.dxvk.as_ref()
+        .filter(|dxvk| config.game.dxvk.selected.as_ref() != Some(*dxvk));
+
+    if let Some(dxvk) = dxvk {
+        if !merged.game.dxvk.builds.join(dxvk).exists() {
+            anyhow::bail!(tr!("session-dxvk-not-downloaded", {
+                "dxvk" = dxvk.clone()
+            }));
+        }
+
+        let journal = DxvkJournal {
+            pid: std::process::id(),
+            prefix: merged.game.wine.prefix.clone()
+        };
+
+        std::fs::write(crate::SESSION_DXVK_JOURNAL_FILE.as_path(), serde_json::to_vec(&journal)?)?;
+
+        if let Err(err) = install_dxvk(&merged, Some(dxvk)) {
+            if let Err(err) = restore_dxvk(config) {
+                tracing::error!("Failed to restore DXVK in the wine prefix: {err}");
+            }
+
+            return Err(err);
+        }
+    }
+
+    if let Some(command) = &profile.pre_launch {
+        run_hook(command);
+    }
+
+    let result = run(&merged);
+
+    // Wine prefix is shared between sessions so it should have the config's DXVK
+    if dxvk.is_some() {
+        if let Err(err) = restore_dxvk(config) {
+            tracing::error!("Failed to restore DXVK in the wine prefix: {err}");
+        }
+    }
+
+    if let Some(command) = &profile.post_launch {
+        run_hook(command);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_lines() {
+        let environment = parse_environment("DXVK_HUD=fps;frametimes\n\n  WINEDLLOVERRIDES=d3d11=n,b;dxgi=n\nbroken line\n=value");
+
+        assert_eq!(environment.len(), 2);
+        assert_eq!(environment["DXVK_HUD"], "fps;frametimes");
+        assert_eq!(environment["WINEDLLOVERRIDES"], "d3d11=n,b;dxgi=n");
+
+        assert_eq!(format_environment(&environment), "DXVK_HUD=fps;frametimes\nWINEDLLOVERRIDES=d3d11=n,b;dxgi=n");
+        assert_eq!(parse_environment(&format_environment(&environment)), environment);
+    }
+
+    #[test]
+    fn apply_profile() {
+        let mut config = Schema::default();
+
+        config.game.environment = HashMap::from([
+            (String::from("GLOBAL"), String::from("1")),
+            (String::from("SHARED"), String::from("global"))
+        ]);
+
+        config.game.wine.selected = Some(String::from("global-wine"));
+        config.game.dxvk.selected = Some(String::from("global-dxvk"));
+        config.game.enhancements.gamescope.enabled = false;
+        config.game.enhancements.fps_unlocker.config.fps = 60;
+        config.game.command = None;
+
+        let mut gamescope = config.game.enhancements.gamescope.clone();
+
+        gamescope.enabled = true;
+
+        let profile = SessionProfile {
+            environment: Some(parse_environment("SHARED=session\nSESSION=a;b")),
+            wine: Some(String::from("session-wine")),
+            dxvk: Some(String::from("session-dxvk")),
+            gamescope: Some(gamescope),
+
+            ..SessionProfile::default()
+        };
+
+        let mut merged = config.clone();
+
+        profile.apply(&mut merged);
+
+        assert_eq!(merged.game.environment, HashMap::from([
+            (String::from("GLOBAL"), String::from("1")),
+            (String::from("SHARED"), String::from("session")),
+            (String::from("SESSION"), String::from("a;b"))
+        ]));
+
+        assert_eq!(merged.game.wine.selected.as_deref(), Some("session-wine"));
+        assert_eq!(merged.game.dxvk.selected.as_deref(), Some("session-dxvk"));
+        assert!(merged.game.enhancements.gamescope.enabled);
+
+        // Not overridden values are kept
+        assert_eq!(merged.game.enhancements.fps_unlocker.config.fps, 60);
+        assert_eq!(merged.game.command, None);
+
+        assert_eq!(merged.game.wine.prefix, config.game.wine.prefix);
+        assert_eq!(merged.game.wine.builds, config.game.wine.builds);
+        assert_eq!(merged.game.path.global, config.game.path.global);
+        assert_eq!(merged.launcher.language, config.launcher.language);
+
+        // Empty profile changes nothing
+        let mut unchanged = config.clone();
+
+        SessionProfile::default().apply(&mut unchanged);
+
+        assert_eq!(unchanged.game.environment, config.game.environment);
+        assert_eq!(unchanged.game.wine.selected, config.game.wine.selected);
+        assert_eq!(unchanged.game.dxvk.selected, config.game.dxvk.selected);
+        assert!(!unchanged.game.enhancements.gamescope.enabled);
+    }
+}
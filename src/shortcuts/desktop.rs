@@ -0,0 +1,227 @@
+use std::path::PathBuf;
+
+use anime_launcher_sdk::sessions::SessionsExt;
+use anime_launcher_sdk::genshin::sessions::Sessions;
+
+use crate::tr;
+
+use super::ShortcutAction;
+
+/// Key in the desktop entry used to store the session name it was made for
+const SESSION_KEY: &str = "X-AnimeGameLauncher-Session";
+
+/// Path to the user's applications folder. Standard is `$HOME/.local/share/applications`
+///
+/// Flatpak sets `XDG_DATA_HOME` to the sandbox's folder, so host's one is used there
+pub fn applications_folder() -> anyhow::Result<PathBuf> {
+    let variable = if super::is_flatpak() {
+        "HOST_XDG_DATA_HOME"
+    } else {
+        "XDG_DATA_HOME"
+    };
+
+    let data = match std::env::var(variable) {
+        Ok(path) if !path.is_empty() => PathBuf::from(path),
+
+        _ => match std::env::var("HOME") {
+            Ok(home) => PathBuf::from(home).join(".local/share"),
+            Err(_) => anyhow::bail!("Failed to find XDG_DATA_HOME folder")
+        }
+    };
+
+    Ok(data.join("applications"))
+}
+
+/// Check if the desktop shortcuts generation is enabled
+#[inline]
+pub fn is_enabled() -> bool {
+    crate::SESSION_SHORTCUTS_FILE.exists()
+}
+
+/// Enable or disable desktop shortcuts generation
+pub fn set_enabled(enabled: bool) -> anyhow::Result<()> {
+    if enabled {
+        std::fs::write(crate::SESSION_SHORTCUTS_FILE.as_path(), "")?;
+
+        sync()
+    }
+
+    else {
+        if crate::SESSION_SHORTCUTS_FILE.exists() {
+            std::fs::remove_file(crate::SESSION_SHORTCUTS_FILE.as_path())?;
+        }
+
+        for (path, _) in list()? {
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escape argument according to the desktop entry specification
+///
+/// Quoted arguments are escaped once more as `Exec` is a string value,
+/// so a literal backslash takes four of them
+fn escape_arg(arg: &str) -> String {
+    if arg.chars().any(|c| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c)) {
+        let mut escaped = String::from("\"");
+
+        for c in arg.chars() {
+            if "\"`$\\".contains(c) {
+                escaped.push('\\');
+            }
+
+            escaped.push(c);
+        }
+
+        escaped.push('"');
+
+        // Backslashes are escaped by string values, and percent sign is reserved for field codes
+        escaped.replace('\\', "\\\\").replace('%', "%%")
+    }
+
+    else {
+        arg.replace('%', "%%")
+    }
+}
+
+fn get_exec(action: ShortcutAction, session: &str) -> anyhow::Result<String> {
+    let mut command = super::launcher_command()?;
+
+    command.append(&mut action.args(session));
+
+    Ok(command.iter()
+        .map(|arg| escape_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+/// Get desktop entry file name for the given session
+///
+/// Names with replaced characters get the original name's checksum
+/// so sessions like `a b` and `a_b` don't share the same file
+fn get_file_name(session: &str) -> String {
+    let name = session.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect::<String>();
+
+    if name == session {
+        format!("{}.session-{name}.desktop", crate::APP_ID)
+    } else {
+        format!("{}.session-{name}-{:08x}.desktop", crate::APP_ID, super::crc32(session.as_bytes()))
+    }
+}
+
+/// Generate desktop entry content for the given session
+pub fn generate(session: &str) -> anyhow::Result<String> {
+    let icon = super::get_icon()?;
+
+    let mut entry = [
+        String::from("[Desktop Entry]"),
+        String::from("Type=Application"),
+        format!("Name={}", tr!("shortcut-play-session", { "session" = session })),
+        format!("Comment={}", tr!("shortcut-play-session-description", { "session" = session })),
+        format!("Icon={}", icon.to_string_lossy()),
+        format!("Exec={}", get_exec(ShortcutAction::RunGame, session)?),
+        String::from("Categories=Game;"),
+        String::from("Terminal=false"),
+        String::from("Actions=open-launcher;"),
+        format!("{SESSION_KEY}={session}"),
+        String::new()
+    ].join("\n");
+
+    entry += &[
+        String::from("[Desktop Action open-launcher]"),
+        format!("Name={}", tr!("shortcut-open-launcher")),
+        format!("Exec={}", get_exec(ShortcutAction::OpenLauncher, session)?),
+        String::new()
+    ].join("\n");
+
+    Ok(entry)
+}
+
+/// List launcher-made desktop entries with their session names
+pub fn list() -> anyhow::Result<Vec<(PathBuf, String)>> {
+    let folder = applications_folder()?;
+
+    if !folder.exists() {
+        return Ok(Vec::new());
+    }
+
+    let prefix = format!("{}.session-", crate::APP_ID);
+    let mut entries = Vec::new();
+
+    for entry in folder.read_dir()?.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with(&prefix) {
+            continue;
+        }
+
+        let session = std::fs::read_to_string(entry.path())?
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("{SESSION_KEY}=")).map(String::from));
+
+        if let Some(session) = session {
+            entries.push((entry.path(), session));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Create desktop entries for all the game sessions
+/// and remove ones made for renamed or deleted sessions
+pub fn sync() -> anyhow::Result<()> {
+    let folder = applications_folder()?;
+
+    if !folder.exists() {
+        std::fs::create_dir_all(&folder)?;
+    }
+
+    let sessions = Sessions::list()?;
+
+    for (path, session) in list()? {
+        if !sessions.contains_key(&session) {
+            tracing::debug!("Removing desktop shortcut of the deleted session: {session}");
+
+            std::fs::remove_file(path)?;
+        }
+
+        // Entry could be made with another file name by older launcher versions
+        else if path != folder.join(get_file_name(&session)) {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    for session in sessions.keys() {
+        std::fs::write(folder.join(get_file_name(session)), generate(session)?)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exec_arguments() {
+        assert_eq!(escape_arg("--session"), "--session");
+        assert_eq!(escape_arg("100%"), "100%%");
+
+        assert_eq!(escape_arg("Main session"), "\"Main session\"");
+        assert_eq!(escape_arg("say \"hi\""), "\"say \\\\\"hi\\\\\"\"");
+        assert_eq!(escape_arg("$HOME"), "\"\\\\$HOME\"");
+        assert_eq!(escape_arg("C:\\Games"), "\"C:\\\\\\\\Games\"");
+    }
+
+    #[test]
+    fn file_names() {
+        assert_eq!(get_file_name("main_1"), format!("{}.session-main_1.desktop", crate::APP_ID));
+
+        assert_ne!(get_file_name("a b"), get_file_name("a_b"));
+        assert_ne!(get_file_name("a b"), get_file_name("a/b"));
+
+        assert!(get_file_name("a/b").starts_with(&format!("{}.session-a_b-", crate::APP_ID)));
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+
+use anime_launcher_sdk::sessions::SessionsExt;
+use anime_launcher_sdk::genshin::sessions::Sessions;
+
+pub mod desktop;
+pub mod steam;
+
+/// Launcher icon bundled into the binary
+const ICON: &[u8] = include_bytes!("../../assets/images/icon.png");
+
+/// Actions shortcuts can perform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShortcutAction {
+    /// Open the launcher with the session selected
+    OpenLauncher,
+
+    /// Run the game with the session selected if it's ready
+    RunGame
+}
+
+impl ShortcutAction {
+    /// Launcher arguments used to perform this action with the given session
+    pub fn args(&self, session: impl ToString) -> Vec<String> {
+        let mut args = vec![
+            String::from("--session"),
+            session.to_string()
+        ];
+
+        if *self == Self::RunGame {
+            args.push(String::from("--run-game"));
+        }
+
+        args
+    }
+}
+
+/// Check if the launcher is running inside of the flatpak sandbox
+#[inline]
+pub fn is_flatpak() -> bool {
+    PathBuf::from("/.flatpak-info").exists()
+}
+
+/// Get command used to start the launcher
+///
+/// Returns `flatpak run <app id>` when running inside of the flatpak sandbox
+pub fn launcher_command() -> anyhow::Result<Vec<String>> {
+    if is_flatpak() {
+        return Ok(vec![
+            String::from("flatpak"),
+            String::from("run"),
+            crate::APP_ID.to_string()
+        ]);
+    }
+
+    // AppImage sets this variable to the original image's path
+    if let Ok(appimage) = std::env::var("APPIMAGE") {
+        return Ok(vec![appimage]);
+    }
+
+    Ok(vec![std::env::current_exe()?.to_string_lossy().to_string()])
+}
+
+/// CRC32 (IEEE) checksum, also used by Steam to generate shortcuts ids
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFFu32;
+
+    for byte in data {
+        crc ^= *byte as u32;
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+/// Write launcher icon to the launcher folder and return path to it
+pub fn get_icon() -> anyhow::Result<PathBuf> {
+    let path = crate::LAUNCHER_FOLDER.join("icon.png");
+
+    if !path.exists() || path.metadata()?.len() != ICON.len() as u64 {
+        std::fs::write(&path, ICON)?;
+    }
+
+    Ok(path)
+}
+
+/// Update all the shortcuts to match current game sessions
+pub fn sync() {
+    if desktop::is_enabled() {
+        if let Err(err) = desktop::sync() {
+            tracing::error!("Failed to sync desktop shortcuts: {err}");
+        }
+    }
+
+    if steam::is_available() {
+        let sessions = Sessions::list()
+            .map(|sessions| sessions.into_keys().collect::<Vec<_>>());
+
+        match sessions {
+            Ok(sessions) => if let Err(err) = steam::sync(&sessions) {
+                tracing::error!("Failed to sync Steam shortcuts: {err}");
+            }
+
+            Err(err) => tracing::error!("Failed to list game sessions: {err}")
+        }
+    }
+}
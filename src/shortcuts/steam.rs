@@ -0,0 +1,540 @@
+use std::path::{Path, PathBuf};
+
+use crate::tr;
+
+use super::ShortcutAction;
+
+/// Binary VDF value as stored in the `shortcuts.vdf` file
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Map(Vec<(String, Value)>),
+    String(String),
+    Int(u32),
+    Float(f32),
+    Long(u64)
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Map(entries) => entries.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value),
+
+            _ => None
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None
+        }
+    }
+}
+
+const TYPE_MAP: u8 = 0x00;
+const TYPE_STRING: u8 = 0x01;
+const TYPE_INT: u8 = 0x02;
+const TYPE_FLOAT: u8 = 0x03;
+const TYPE_LONG: u8 = 0x07;
+const TYPE_END: u8 = 0x08;
+
+fn read_string(data: &[u8], offset: &mut usize) -> anyhow::Result<String> {
+    let Some(len) = data[*offset..].iter().position(|byte| *byte == 0) else {
+        anyhow::bail!("Unterminated string in VDF file");
+    };
+
+    let value = String::from_utf8_lossy(&data[*offset..*offset + len]).to_string();
+
+    *offset += len + 1;
+
+    Ok(value)
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: &mut usize) -> anyhow::Result<[u8; N]> {
+    let Some(bytes) = data.get(*offset..*offset + N) else {
+        anyhow::bail!("Unexpected end of VDF file");
+    };
+
+    *offset += N;
+
+    Ok(bytes.try_into()?)
+}
+
+fn read_map(data: &[u8], offset: &mut usize) -> anyhow::Result<Vec<(String, Value)>> {
+    let mut entries = Vec::new();
+
+    loop {
+        let Some(kind) = data.get(*offset).copied() else {
+            // Root map may not be closed by some tools
+            return Ok(entries);
+        };
+
+        *offset += 1;
+
+        if kind == TYPE_END {
+            return Ok(entries);
+        }
+
+        let key = read_string(data, offset)?;
+
+        let value = match kind {
+            TYPE_MAP    => Value::Map(read_map(data, offset)?),
+            TYPE_STRING => Value::String(read_string(data, offset)?),
+            TYPE_INT    => Value::Int(u32::from_le_bytes(read_bytes(data, offset)?)),
+            TYPE_FLOAT  => Value::Float(f32::from_le_bytes(read_bytes(data, offset)?)),
+            TYPE_LONG   => Value::Long(u64::from_le_bytes(read_bytes(data, offset)?)),
+
+            _ => anyhow::bail!("Unsupported VDF value type: {kind:#04x}")
+        };
+
+        entries.push((key, value));
+    }
+}
+
+fn write_map(entries: &[(String, Value)], output: &mut Vec<u8>) {
+    for (key, value) in entries {
+        let kind = match value {
+            Value::Map(_)    => TYPE_MAP,
+            Value::String(_) => TYPE_STRING,
+            Value::Int(_)    => TYPE_INT,
+            Value::Float(_)  => TYPE_FLOAT,
+            Value::Long(_)   => TYPE_LONG
+        };
+
+        output.push(kind);
+        output.extend_from_slice(key.as_bytes());
+        output.push(0);
+
+        match value {
+            Value::Map(entries) => write_map(entries, output),
+
+            Value::String(value) => {
+                output.extend_from_slice(value.as_bytes());
+                output.push(0);
+            }
+
+            Value::Int(value)   => output.extend_from_slice(&value.to_le_bytes()),
+            Value::Float(value) => output.extend_from_slice(&value.to_le_bytes()),
+            Value::Long(value)  => output.extend_from_slice(&value.to_le_bytes())
+        }
+    }
+
+    output.push(TYPE_END);
+}
+
+/// Parse binary VDF file
+pub fn parse(data: &[u8]) -> anyhow::Result<Value> {
+    Ok(Value::Map(read_map(data, &mut 0)?))
+}
+
+/// Serialize value into binary VDF format
+pub fn serialize(value: &Value) -> Vec<u8> {
+    let mut output = Vec::new();
+
+    if let Value::Map(entries) = value {
+        write_map(entries, &mut output);
+    }
+
+    output
+}
+
+/// Get Steam installation folders which exist in the system
+fn steam_folders() -> Vec<PathBuf> {
+    let Ok(home) = std::env::var("HOME") else {
+        return Vec::new();
+    };
+
+    let home = PathBuf::from(home);
+
+    let mut folders = Vec::new();
+
+    for folder in [".steam/steam", ".local/share/Steam", ".var/app/com.valvesoftware.Steam/data/Steam"] {
+        let folder = home.join(folder);
+
+        // `.steam/steam` is usually a symlink to the `.local/share/Steam`
+        if let Ok(folder) = folder.canonicalize() {
+            if folder.join("userdata").exists() && !folders.contains(&folder) {
+                folders.push(folder);
+            }
+        }
+    }
+
+    folders
+}
+
+/// Get paths to `shortcuts.vdf` files of all the Steam users
+pub fn shortcuts_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    for folder in steam_folders() {
+        let Ok(users) = folder.join("userdata").read_dir() else {
+            continue;
+        };
+
+        for user in users.flatten() {
+            // "0" is used for anonymous user
+            if user.file_name() != "0" && user.path().join("config").exists() {
+                files.push(user.path().join("config/shortcuts.vdf"));
+            }
+        }
+    }
+
+    files
+}
+
+/// Check if Steam is installed
+#[inline]
+pub fn is_available() -> bool {
+    !shortcuts_files().is_empty()
+}
+
+/// Check if Steam is running
+///
+/// Steam keeps shortcuts in memory and overwrites `shortcuts.vdf` on exit,
+/// so changes made while it's running are lost
+pub fn is_running() -> bool {
+    let Ok(processes) = Path::new("/proc").read_dir() else {
+        return false;
+    };
+
+    processes.flatten().any(|process| {
+        std::fs::read_to_string(process.path().join("comm"))
+            .map(|name| name.trim() == "steam")
+            .unwrap_or(false)
+    })
+}
+
+/// Get name of the non-Steam game shortcut running the given session
+fn get_name(session: &str) -> String {
+    format!("An Anime Game Launcher ({session})")
+}
+
+/// Get session name of the launcher-made shortcut
+fn get_session(shortcut: &Value) -> Option<&str> {
+    shortcut.get("AppName")
+        .and_then(Value::as_str)?
+        .strip_prefix("An Anime Game Launcher (")?
+        .strip_suffix(')')
+}
+
+/// Generate non-Steam game shortcut running the given session
+fn generate(session: &str, icon: &Path) -> anyhow::Result<Value> {
+    let mut command = super::launcher_command()?;
+
+    let exe = format!("\"{}\"", command.remove(0));
+
+    let mut args = command;
+
+    args.append(&mut ShortcutAction::RunGame.args(session));
+
+    let args = args.into_iter()
+        .map(|arg| if arg.contains(' ') { format!("\"{arg}\"") } else { arg })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let name = get_name(session);
+
+    let appid = super::crc32(format!("{exe}{name}").as_bytes()) | 0x80000000;
+
+    Ok(Value::Map(vec![
+        (String::from("appid"),               Value::Int(appid)),
+        (String::from("AppName"),             Value::String(name)),
+        (String::from("Exe"),                 Value::String(exe)),
+        (String::from("StartDir"),            Value::String(format!("\"{}\"", crate::LAUNCHER_FOLDER.to_string_lossy()))),
+        (String::from("icon"),                Value::String(icon.to_string_lossy().to_string())),
+        (String::from("ShortcutPath"),        Value::String(String::new())),
+        (String::from("LaunchOptions"),       Value::String(args)),
+        (String::from("IsHidden"),            Value::Int(0)),
+        (String::from("AllowDesktopConfig"),  Value::Int(1)),
+        (String::from("AllowOverlay"),        Value::Int(1)),
+        (String::from("OpenVR"),              Value::Int(0)),
+        (String::from("Devkit"),              Value::Int(0)),
+        (String::from("DevkitGameID"),        Value::String(String::new())),
+        (String::from("DevkitOverrideAppID"), Value::Int(0)),
+        (String::from("LastPlayTime"),        Value::Int(0)),
+        (String::from("FlatpakAppID"),        Value::String(String::new())),
+        (String::from("tags"),                Value::Map(Vec::new()))
+    ]))
+}
+
+/// Read shortcuts list from the `shortcuts.vdf` file
+fn read_shortcuts(path: &Path) -> anyhow::Result<Vec<(String, Value)>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    match parse(&std::fs::read(path)?)?.get("shortcuts") {
+        Some(Value::Map(shortcuts)) => Ok(shortcuts.clone()),
+        _ => Ok(Vec::new())
+    }
+}
+
+/// Write shortcuts list to the `shortcuts.vdf` file, keeping backup of the previous one
+fn write_shortcuts(path: &Path, shortcuts: Vec<(String, Value)>) -> anyhow::Result<()> {
+    // Renumber entries as Steam expects them to be sequential
+    let shortcuts = shortcuts.into_iter()
+        .enumerate()
+        .map(|(i, (_, value))| (i.to_string(), value))
+        .collect();
+
+    let data = serialize(&Value::Map(vec![
+        (String::from("shortcuts"), Value::Map(shortcuts))
+    ]));
+
+    if path.exists() {
+        std::fs::copy(path, path.with_extension("vdf.bak"))?;
+    }
+
+    std::fs::write(path, data)?;
+
+    Ok(())
+}
+
+/// Add or replace non-Steam game shortcut running the given session
+pub fn add_to_file(path: impl AsRef<Path>, session: &str, icon: &Path) -> anyhow::Result<()> {
+    let path = path.as_ref();
+
+    let mut shortcuts = read_shortcuts(path)?;
+
+    let shortcut = generate(session, icon)?;
+
+    let existing = shortcuts.iter()
+        .position(|(_, value)| get_session(value) == Some(session));
+
+    match existing {
+        Some(i) => shortcuts[i].1 = shortcut,
+        None => shortcuts.push((shortcuts.len().to_string(), shortcut))
+    }
+
+    write_shortcuts(path, shortcuts)
+}
+
+/// Update launcher-made shortcuts in the file and remove ones made for renamed or deleted sessions
+///
+/// Return `true` if the file was changed
+pub fn sync_file(path: impl AsRef<Path>, sessions: &[String], icon: &Path) -> anyhow::Result<bool> {
+    let path = path.as_ref();
+
+    let shortcuts = read_shortcuts(path)?;
+
+    let mut updated = Vec::with_capacity(shortcuts.len());
+
+    for (key, shortcut) in &shortcuts {
+        match get_session(shortcut) {
+            Some(session) if !sessions.iter().any(|name| name == session) => {
+                tracing::debug!("Removing Steam shortcut of the deleted session: {session}");
+            }
+
+            // Keep user's playtime and settings but update the launch command
+            Some(session) => {
+                let mut shortcut = shortcut.clone();
+
+                if let (Value::Map(entries), Value::Map(generated)) = (&mut shortcut, generate(session, icon)?) {
+                    for (name, value) in generated {
+                        if ["Exe", "StartDir", "icon", "LaunchOptions"].contains(&name.as_str()) {
+                            match entries.iter_mut().find(|(key, _)| key.eq_ignore_ascii_case(&name)) {
+                                Some(entry) => entry.1 = value,
+                                None => entries.push((name, value))
+                            }
+                        }
+                    }
+                }
+
+                updated.push((key.clone(), shortcut));
+            }
+
+            None => updated.push((key.clone(), shortcut.clone()))
+        }
+    }
+
+    if updated == shortcuts {
+        return Ok(false);
+    }
+
+    write_shortcuts(path, updated)?;
+
+    Ok(true)
+}
+
+/// Sync launcher-made shortcuts of all the Steam users with the given sessions
+///
+/// Fails if Steam is running as it would overwrite the changes
+pub fn sync(sessions: &[String]) -> anyhow::Result<()> {
+    if is_running() {
+        anyhow::bail!("Steam is running and would overwrite updated shortcuts on exit");
+    }
+
+    let icon = super::get_icon()?;
+
+    for file in shortcuts_files() {
+        if sync_file(&file, sessions, &icon)? {
+            tracing::info!("Updated Steam shortcuts in {file:?}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Add non-Steam game shortcut running the given session to all the Steam users
+///
+/// Fails if Steam is running as it would overwrite the changes
+pub fn add(session: &str) -> anyhow::Result<()> {
+    let files = shortcuts_files();
+
+    if files.is_empty() {
+        anyhow::bail!("Steam installation not found");
+    }
+
+    if is_running() {
+        anyhow::bail!(tr!("steam-shortcut-steam-running"));
+    }
+
+    let icon = super::get_icon()?;
+
+    for file in files {
+        tracing::info!("Adding Steam shortcut for session {session} to {file:?}");
+
+        add_to_file(file, session, &icon)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create empty temp folder for the test
+    fn temp_folder(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aagl-steam-test-{name}-{}", std::process::id()));
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+
+        std::fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    fn shortcut(name: &str) -> Value {
+        Value::Map(vec![
+            (String::from("appid"),         Value::Int(0x80001234)),
+            (String::from("AppName"),       Value::String(name.to_string())),
+            (String::from("Exe"),           Value::String(String::from("\"/usr/bin/game\""))),
+            (String::from("LaunchOptions"), Value::String(String::from("--run"))),
+            (String::from("LastPlayTime"),  Value::Int(1700000000)),
+            (String::from("tags"),          Value::Map(vec![
+                (String::from("0"), Value::String(String::from("favorite")))
+            ]))
+        ])
+    }
+
+    fn shortcuts(names: &[&str]) -> Value {
+        Value::Map(vec![
+            (String::from("shortcuts"), Value::Map(names.iter()
+                .enumerate()
+                .map(|(i, name)| (i.to_string(), shortcut(name)))
+                .collect()))
+        ])
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = Value::Map(vec![
+            (String::from("string"), Value::String(String::from("value"))),
+            (String::from("int"),    Value::Int(u32::MAX)),
+            (String::from("float"),  Value::Float(1.5)),
+            (String::from("long"),   Value::Long(u64::MAX)),
+            (String::from("empty"),  Value::Map(Vec::new())),
+            (String::from("nested"), shortcuts(&["Game", "Other game"]))
+        ]);
+
+        let data = serialize(&value);
+
+        assert_eq!(parse(&data).unwrap(), value);
+        assert_eq!(serialize(&parse(&data).unwrap()), data);
+    }
+
+    #[test]
+    fn binary_layout() {
+        let value = Value::Map(vec![
+            (String::from("a"), Value::Map(vec![
+                (String::from("b"), Value::String(String::from("c"))),
+                (String::from("d"), Value::Int(1))
+            ]))
+        ]);
+
+        let data = [
+            TYPE_MAP, b'a', 0,
+                TYPE_STRING, b'b', 0, b'c', 0,
+                TYPE_INT, b'd', 0, 1, 0, 0, 0,
+            TYPE_END,
+            TYPE_END
+        ];
+
+        assert_eq!(serialize(&value), data);
+        assert_eq!(parse(&data).unwrap(), value);
+
+        // Root map may not be closed
+        assert_eq!(parse(&data[..data.len() - 1]).unwrap(), value);
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert!(parse(&[TYPE_STRING, b'a', 0, b'b']).is_err());
+        assert!(parse(&[TYPE_INT, b'a', 0, 1, 0]).is_err());
+        assert!(parse(&[0x42, b'a', 0]).is_err());
+    }
+
+    #[test]
+    fn session_names() {
+        assert_eq!(get_session(&shortcut(&get_name("Main (alt)"))), Some("Main (alt)"));
+        assert_eq!(get_session(&shortcut("Other game")), None);
+    }
+
+    #[test]
+    fn sync_removes_deleted_sessions() {
+        let path = temp_folder("sync").join("shortcuts.vdf");
+        let icon = path.with_file_name("icon.png");
+
+        let data = serialize(&shortcuts(&["Other game", &get_name("deleted")]));
+
+        std::fs::write(&path, &data).unwrap();
+
+        assert!(sync_file(&path, &[String::from("main")], &icon).unwrap());
+
+        let updated = read_shortcuts(&path).unwrap();
+
+        assert_eq!(updated, vec![(String::from("0"), shortcut("Other game"))]);
+        assert_eq!(std::fs::read(path.with_extension("vdf.bak")).unwrap(), data);
+
+        // File isn't written when there's nothing to change
+        assert!(!sync_file(&path, &[String::from("main")], &icon).unwrap());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn sync_updates_existing_sessions() {
+        let path = temp_folder("update").join("shortcuts.vdf");
+        let icon = path.with_file_name("icon.png");
+
+        std::fs::write(&path, serialize(&shortcuts(&[&get_name("main")]))).unwrap();
+
+        assert!(sync_file(&path, &[String::from("main")], &icon).unwrap());
+
+        let updated = read_shortcuts(&path).unwrap();
+        let generated = generate("main", &icon).unwrap();
+
+        // Launch command is replaced but user's data is kept
+        assert_eq!(updated[0].1.get("LaunchOptions"), generated.get("LaunchOptions"));
+        assert_eq!(updated[0].1.get("icon"), Some(&Value::String(icon.to_string_lossy().to_string())));
+        assert_eq!(updated[0].1.get("LastPlayTime"), Some(&Value::Int(1700000000)));
+        assert_eq!(updated[0].1.get("tags"), shortcut("").get("tags"));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
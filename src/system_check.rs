@@ -0,0 +1,561 @@
+use std::path::Path;
+
+use anime_launcher_sdk::config::schema_blanks::prelude::*;
+use anime_launcher_sdk::genshin::config::Schema;
+
+use anime_launcher_sdk::anime_game_core::installer::free_space;
+
+use anime_launcher_sdk::is_available;
+
+use crate::i18n::{format_size, SizeUnits};
+use crate::tr;
+
+/// Recommended `vm.max_map_count` value. Some games crash with lower limits
+pub const RECOMMENDED_MAX_MAP_COUNT: u64 = 1048576;
+
+/// Minimal hard limit of opened files needed by esync
+pub const ESYNC_MIN_NOFILE: u64 = 524288;
+
+/// First kernel version with `futex_waitv` syscall needed by fsync
+pub const FUTEX_WAITV_KERNEL: (u32, u32) = (5, 16);
+
+/// Oldest glibc version wine builds from the components index are known to work with
+pub const MIN_GLIBC_VERSION: (u32, u32) = (2, 31);
+
+/// Minimal amount of free space in the game folder before warning the user
+pub const MIN_GAME_FREE_SPACE: u64 = 20 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Passed,
+
+    /// Optional feature is not available but it's not used
+    Info,
+
+    Warning,
+    Error
+}
+
+impl Severity {
+    pub fn icon_name(&self) -> &'static str {
+        match self {
+            Self::Passed  => "emblem-ok-symbolic",
+            Self::Info    => "dialog-information-symbolic",
+            Self::Warning => "dialog-warning-symbolic",
+            Self::Error   => "dialog-error-symbolic"
+        }
+    }
+}
+
+/// Distro family, used to get packages names and install commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distro {
+    Arch,
+    Debian,
+    Fedora,
+    OpenSuse,
+    Gentoo,
+    NixOS,
+    Unknown
+}
+
+impl Distro {
+    pub fn detect() -> Self {
+        let Some(distro) = whatadistro::identify() else {
+            return Self::Unknown;
+        };
+
+        if distro.is_similar("arch") {
+            Self::Arch
+        } else if distro.is_similar("debian") {
+            Self::Debian
+        } else if distro.is_similar("fedora") {
+            Self::Fedora
+        } else if distro.is_similar("opensuse") || distro.is_similar("suse") {
+            Self::OpenSuse
+        } else if distro.is_similar("gentoo") {
+            Self::Gentoo
+        } else if distro.is_similar("nixos") {
+            Self::NixOS
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Get command installing given packages which can be copied to the terminal,
+    /// or `None` if the distro is unknown
+    pub fn install_command(&self, packages: &[&str]) -> Option<String> {
+        match self {
+            // Packages should be added to the system configuration instead,
+            // but this makes them available for the current user
+            Self::NixOS => Some(format!("nix-env -iA {}", packages.iter()
+                .map(|package| format!("nixos.{package}"))
+                .collect::<Vec<_>>()
+                .join(" "))),
+
+            _ => self.install_args(packages)
+                .map(|args| format!("sudo {}", args.join(" ")))
+        }
+    }
+
+    /// Get non-interactive command installing given packages which should be run as root,
+    /// or `None` if packages can't be installed this way
+    pub fn install_args(&self, packages: &[&str]) -> Option<Vec<String>> {
+        let command: &[&str] = match self {
+            Self::Arch     => &["pacman", "-S", "--needed", "--noconfirm"],
+            Self::Debian   => &["apt-get", "install", "-y"],
+            Self::Fedora   => &["dnf", "install", "-y"],
+            Self::OpenSuse => &["zypper", "--non-interactive", "install"],
+            Self::Gentoo   => &["emerge", "--noreplace"],
+
+            Self::NixOS | Self::Unknown => return None
+        };
+
+        Some(command.iter().chain(packages).map(|arg| arg.to_string()).collect())
+    }
+}
+
+/// System package checked by the launcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Package {
+    Git,
+    Xdelta3,
+    P7zip,
+    Pkexec,
+    Bubblewrap,
+    Gamescope,
+    Gamemode,
+    MangoHud,
+    VulkanLoader
+}
+
+impl Package {
+    /// Name of the package in the distro's repositories
+    pub fn name(&self, distro: Distro) -> &'static str {
+        match (self, distro) {
+            (Self::Git, Distro::Gentoo) => "dev-vcs/git",
+            (Self::Xdelta3, Distro::Gentoo) => "dev-util/xdelta",
+            (Self::P7zip, Distro::Gentoo) => "app-arch/p7zip",
+            (Self::Pkexec, Distro::Gentoo) => "sys-auth/polkit",
+            (Self::Bubblewrap, Distro::Gentoo) => "sys-apps/bubblewrap",
+            (Self::Gamescope, Distro::Gentoo) => "gui-wm/gamescope",
+            (Self::Gamemode, Distro::Gentoo) => "games-util/gamemode",
+            (Self::MangoHud, Distro::Gentoo) => "games-util/mangohud",
+            (Self::VulkanLoader, Distro::Gentoo) => "media-libs/vulkan-loader",
+
+            (Self::Git, _) => "git",
+
+            (Self::Xdelta3, Distro::Fedora | Distro::NixOS) => "xdelta",
+            (Self::Xdelta3, _) => "xdelta3",
+
+            (Self::P7zip, Distro::Debian | Distro::OpenSuse) => "p7zip-full",
+            (Self::P7zip, _) => "p7zip",
+
+            (Self::Pkexec, Distro::Debian) => "pkexec",
+            (Self::Pkexec, _) => "polkit",
+
+            (Self::Bubblewrap, _) => "bubblewrap",
+            (Self::Gamescope, _) => "gamescope",
+            (Self::Gamemode, _) => "gamemode",
+            (Self::MangoHud, _) => "mangohud",
+
+            (Self::VulkanLoader, Distro::Arch) => "vulkan-icd-loader",
+            (Self::VulkanLoader, Distro::Fedora | Distro::NixOS) => "vulkan-loader",
+            (Self::VulkanLoader, _) => "libvulkan1"
+        }
+    }
+}
+
+/// Check if system packages can be installed by the launcher itself
+///
+/// It's not possible from the flatpak sandbox, on immutable systems
+/// and on distros with declarative packages management
+pub fn can_install_packages(distro: Distro) -> bool {
+    let immutable = Path::new("/.flatpak-info").exists() ||
+        Path::new("/run/ostree-booted").exists() ||
+        is_available("steamos-readonly");
+
+    !immutable && is_available("pkexec") && distro.install_args(&[]).is_some()
+}
+
+/// Install packages using the distro's package manager through `pkexec`
+///
+/// Blocks until the package manager is finished
+pub fn install_packages(distro: Distro, packages: &[Package]) -> anyhow::Result<()> {
+    let packages = packages.iter()
+        .map(|package| package.name(distro))
+        .collect::<Vec<_>>();
+
+    let Some(args) = distro.install_args(&packages) else {
+        anyhow::bail!("Packages installation is not supported on this distro");
+    };
+
+    tracing::info!("Installing packages: {:?}", args);
+
+    let output = std::process::Command::new("pkexec")
+        .args(args)
+        .output()?;
+
+    if !output.status.success() {
+        anyhow::bail!("Package manager exited with {}: {}", output.status, String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(())
+}
+
+/// Result of a single system check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// Checked thing, e.g. `gamescope` or `vm.max_map_count`
+    pub name: String,
+
+    pub severity: Severity,
+    pub description: String,
+
+    /// Package which should be installed to solve the problem
+    pub package: Option<Package>,
+
+    /// Command or instruction solving the problem on the detected distro
+    pub remedy: Option<String>
+}
+
+impl Check {
+    #[inline]
+    fn new(name: impl ToString, severity: Severity, description: String) -> Self {
+        Self {
+            name: name.to_string(),
+            severity,
+            description,
+            package: None,
+            remedy: None
+        }
+    }
+
+    #[inline]
+    fn with_remedy(mut self, remedy: impl ToString) -> Self {
+        if self.severity != Severity::Passed {
+            self.remedy = Some(remedy.to_string());
+        }
+
+        self
+    }
+
+    #[inline]
+    fn with_package(mut self, package: Package, distro: Distro) -> Self {
+        if self.severity != Severity::Passed {
+            self.package = Some(package);
+            self.remedy = distro.install_command(&[package.name(distro)]);
+        }
+
+        self
+    }
+}
+
+/// Parse `major.minor` prefix of the version string
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+
+    Some((major, minor))
+}
+
+fn read_proc(path: &str) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+        .map(|value| value.trim().to_string())
+}
+
+#[cfg(target_env = "gnu")]
+fn get_glibc_version() -> Option<String> {
+    let version = unsafe { std::ffi::CStr::from_ptr(libc::gnu_get_libc_version()) };
+
+    version.to_str().ok().map(String::from)
+}
+
+#[cfg(not(target_env = "gnu"))]
+fn get_glibc_version() -> Option<String> {
+    None
+}
+
+fn is_vulkan_available() -> bool {
+    unsafe {
+        let handle = libc::dlopen(b"libvulkan.so.1\0".as_ptr() as *const libc::c_char, libc::RTLD_NOW | libc::RTLD_LOCAL);
+
+        if handle.is_null() {
+            return false;
+        }
+
+        libc::dlclose(handle);
+    }
+
+    true
+}
+
+/// Get hard limit of opened files of the launcher process
+fn get_nofile_limit() -> Option<u64> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0
+    };
+
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        return None;
+    }
+
+    Some(limit.rlim_max as u64)
+}
+
+fn check_binary(checks: &mut Vec<Check>, distro: Distro, binary: &str, package: Package, missing: Severity) {
+    let check = if is_available(binary) {
+        Check::new(binary, Severity::Passed, tr!("system-check-binary-available"))
+    } else {
+        Check::new(binary, missing, tr!("system-check-binary-missing"))
+    };
+
+    checks.push(check.with_package(package, distro));
+}
+
+fn check_free_space(checks: &mut Vec<Check>, name: &str, path: &Path, required: u64) {
+    let Some(path) = crate::move_files::get_existing_parent(path) else {
+        return;
+    };
+
+    let Some(available) = free_space::available(path) else {
+        return;
+    };
+
+    let description = tr!("system-check-free-space", {
+        "path" = path.to_string_lossy().to_string(),
+        "size" = format_size(available, SizeUnits::Binary)
+    });
+
+    let severity = if available < required {
+        Severity::Warning
+    } else {
+        Severity::Passed
+    };
+
+    checks.push(Check::new(name, severity, description)
+        .with_remedy(tr!("system-check-free-space-remedy", {
+            "size" = format_size(required, SizeUnits::Binary)
+        })));
+}
+
+/// Check system packages, libraries and settings the launcher and the game depend on
+///
+/// Severity of the optional features depends on whether they're enabled in the config
+pub fn run(config: &Schema) -> Vec<Check> {
+    let distro = Distro::detect();
+
+    let mut checks = Vec::new();
+
+    // Required binaries
+    check_binary(&mut checks, distro, "git", Package::Git, Severity::Error);
+    check_binary(&mut checks, distro, "xdelta3", Package::Xdelta3, Severity::Error);
+
+    // 7z sometimes has different binaries
+    if is_available("7za") {
+        check_binary(&mut checks, distro, "7za", Package::P7zip, Severity::Error);
+    } else {
+        check_binary(&mut checks, distro, "7z", Package::P7zip, Severity::Error);
+    }
+
+    // Optional binaries
+    let enhancements = &config.game.enhancements;
+
+    let optional = |enabled: bool| if enabled { Severity::Error } else { Severity::Info };
+
+    check_binary(&mut checks, distro, "pkexec", Package::Pkexec, Severity::Warning);
+    check_binary(&mut checks, distro, "bwrap", Package::Bubblewrap, optional(config.sandbox.enabled));
+    check_binary(&mut checks, distro, "gamescope", Package::Gamescope, optional(enhancements.gamescope.enabled));
+    check_binary(&mut checks, distro, "gamemoderun", Package::Gamemode, optional(enhancements.gamemode));
+    check_binary(&mut checks, distro, "mangohud", Package::MangoHud, optional(enhancements.hud == HUD::MangoHUD));
+
+    // Vulkan loader
+    let check = if is_vulkan_available() {
+        Check::new("vulkan", Severity::Passed, tr!("system-check-vulkan-available"))
+    } else {
+        Check::new("vulkan", Severity::Error, tr!("system-check-vulkan-missing"))
+    };
+
+    checks.push(check.with_package(Package::VulkanLoader, distro));
+
+    // glibc
+    if let Some(version) = get_glibc_version() {
+        let severity = match parse_version(&version) {
+            Some(parsed) if parsed < MIN_GLIBC_VERSION => Severity::Warning,
+            _ => Severity::Passed
+        };
+
+        checks.push(Check::new("glibc", severity, tr!("system-check-glibc", {
+            "version" = version
+        })).with_remedy(tr!("system-check-update-system")));
+    }
+
+    // Kernel
+    let kernel = read_proc("/proc/sys/kernel/osrelease");
+
+    if let Some(kernel) = &kernel {
+        checks.push(Check::new("kernel", Severity::Passed, tr!("system-check-kernel", {
+            "version" = kernel.clone()
+        })));
+    }
+
+    // vm.max_map_count
+    if let Some(count) = read_proc("/proc/sys/vm/max_map_count").and_then(|count| count.parse::<u64>().ok()) {
+        let severity = if count < RECOMMENDED_MAX_MAP_COUNT {
+            Severity::Warning
+        } else {
+            Severity::Passed
+        };
+
+        checks.push(Check::new("vm.max_map_count", severity, tr!("system-check-max-map-count", {
+            "count" = count,
+            "recommended" = RECOMMENDED_MAX_MAP_COUNT
+        })).with_remedy(format!("echo 'vm.max_map_count = {RECOMMENDED_MAX_MAP_COUNT}' | sudo tee /etc/sysctl.d/80-max-map-count.conf && sudo sysctl --system")));
+    }
+
+    // esync
+    let sync = config.game.wine.sync;
+
+    if let Some(limit) = get_nofile_limit() {
+        let severity = match (limit < ESYNC_MIN_NOFILE, sync) {
+            (false, _) => Severity::Passed,
+            (true, WineSync::ESync) => Severity::Error,
+            (true, _) => Severity::Info
+        };
+
+        checks.push(Check::new("esync", severity, tr!("system-check-esync", {
+            "limit" = limit,
+            "required" = ESYNC_MIN_NOFILE
+        })).with_remedy(tr!("system-check-esync-remedy", {
+            "limit" = ESYNC_MIN_NOFILE
+        })));
+    }
+
+    // fsync
+    if let Some(version) = kernel.as_deref().and_then(parse_version) {
+        let severity = match (version < FUTEX_WAITV_KERNEL, sync) {
+            (false, _) => Severity::Passed,
+            (true, WineSync::FSync | WineSync::Futex2) => Severity::Error,
+            (true, _) => Severity::Info
+        };
+
+        checks.push(Check::new("fsync", severity, tr!("system-check-fsync", {
+            "version" = format!("{}.{}", FUTEX_WAITV_KERNEL.0, FUTEX_WAITV_KERNEL.1)
+        })).with_remedy(tr!("system-check-update-system")));
+    }
+
+    // Free space
+    check_free_space(&mut checks, "launcher", &crate::LAUNCHER_FOLDER, crate::config_validator::MIN_TEMP_FREE_SPACE);
+    check_free_space(&mut checks, "game", config.game.path.for_edition(config.launcher.edition), MIN_GAME_FREE_SPACE);
+
+    let temp = config.launcher.temp.clone().unwrap_or_else(std::env::temp_dir);
+
+    check_free_space(&mut checks, "temp", &temp, crate::config_validator::MIN_TEMP_FREE_SPACE);
+
+    checks
+}
+
+/// Get the distro's pretty name from `/etc/os-release`
+fn get_os_name() -> Option<String> {
+    std::fs::read_to_string("/etc/os-release").ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+        .map(|name| name.trim_matches('"').to_string())
+}
+
+/// Format checks results as a plain text report which can be attached to bug reports
+pub fn report(checks: &[Check]) -> String {
+    let mut report = format!(
+        "Launcher: {}\nSystem: {}\n\n",
+        crate::APP_VERSION,
+        get_os_name().unwrap_or_else(|| String::from("unknown"))
+    );
+
+    for check in checks {
+        let severity = match check.severity {
+            Severity::Passed  => "ok",
+            Severity::Info    => "info",
+            Severity::Warning => "warning",
+            Severity::Error   => "error"
+        };
+
+        report += &format!("[{severity}] {}: {}\n", check.name, check.description);
+
+        if let Some(remedy) = &check.remedy {
+            report += &format!("    {remedy}\n");
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKAGES: &[&str] = &["git", "xdelta3"];
+
+    #[test]
+    fn install_args() {
+        let args = |distro: Distro| distro.install_args(PACKAGES);
+
+        let expected = |args: &[&str]| Some(args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>());
+
+        assert_eq!(args(Distro::Arch), expected(&["pacman", "-S", "--needed", "--noconfirm", "git", "xdelta3"]));
+        assert_eq!(args(Distro::Debian), expected(&["apt-get", "install", "-y", "git", "xdelta3"]));
+        assert_eq!(args(Distro::Fedora), expected(&["dnf", "install", "-y", "git", "xdelta3"]));
+        assert_eq!(args(Distro::OpenSuse), expected(&["zypper", "--non-interactive", "install", "git", "xdelta3"]));
+        assert_eq!(args(Distro::Gentoo), expected(&["emerge", "--noreplace", "git", "xdelta3"]));
+
+        assert_eq!(args(Distro::NixOS), None);
+        assert_eq!(args(Distro::Unknown), None);
+    }
+
+    #[test]
+    fn install_command() {
+        assert_eq!(Distro::Arch.install_command(PACKAGES).as_deref(), Some("sudo pacman -S --needed --noconfirm git xdelta3"));
+        assert_eq!(Distro::Debian.install_command(PACKAGES).as_deref(), Some("sudo apt-get install -y git xdelta3"));
+
+        assert_eq!(Distro::NixOS.install_command(PACKAGES).as_deref(), Some("nix-env -iA nixos.git nixos.xdelta3"));
+
+        assert_eq!(Distro::Unknown.install_command(PACKAGES), None);
+    }
+
+    #[test]
+    fn packages_names() {
+        let names = |package: Package| [
+            Distro::Arch,
+            Distro::Debian,
+            Distro::Fedora,
+            Distro::OpenSuse,
+            Distro::Gentoo,
+            Distro::NixOS
+        ].map(|distro| package.name(distro));
+
+        assert_eq!(names(Package::Git), ["git", "git", "git", "git", "dev-vcs/git", "git"]);
+        assert_eq!(names(Package::Xdelta3), ["xdelta3", "xdelta3", "xdelta", "xdelta3", "dev-util/xdelta", "xdelta"]);
+        assert_eq!(names(Package::P7zip), ["p7zip", "p7zip-full", "p7zip", "p7zip-full", "app-arch/p7zip", "p7zip"]);
+        assert_eq!(names(Package::Pkexec), ["polkit", "pkexec", "polkit", "polkit", "sys-auth/polkit", "polkit"]);
+        assert_eq!(names(Package::Bubblewrap), ["bubblewrap", "bubblewrap", "bubblewrap", "bubblewrap", "sys-apps/bubblewrap", "bubblewrap"]);
+        assert_eq!(names(Package::Gamescope), ["gamescope", "gamescope", "gamescope", "gamescope", "gui-wm/gamescope", "gamescope"]);
+        assert_eq!(names(Package::Gamemode), ["gamemode", "gamemode", "gamemode", "gamemode", "games-util/gamemode", "gamemode"]);
+        assert_eq!(names(Package::MangoHud), ["mangohud", "mangohud", "mangohud", "mangohud", "games-util/mangohud", "mangohud"]);
+        assert_eq!(names(Package::VulkanLoader), ["vulkan-icd-loader", "libvulkan1", "vulkan-loader", "libvulkan1", "media-libs/vulkan-loader", "vulkan-loader"]);
+    }
+
+    #[test]
+    fn unknown_distro() {
+        assert_eq!(Distro::Unknown.install_args(&[]), None);
+        assert_eq!(Distro::Unknown.install_command(&["git"]), None);
+    }
+
+    #[test]
+    fn parse_versions() {
+        assert_eq!(parse_version("2.38"), Some((2, 38)));
+        assert_eq!(parse_version("6.5.0-arch1-1"), Some((6, 5)));
+        assert_eq!(parse_version("unknown"), None);
+    }
+}
@@ -4,11 +4,13 @@ pub mod list;
 pub mod group;
 pub mod version;
 pub mod progress_bar;
+pub mod news;
 
 pub use list::*;
 pub use group::*;
 pub use version::*;
 pub use progress_bar::*;
+pub use news::*;
 
 use anime_launcher_sdk::components::*;
 
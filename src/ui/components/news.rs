@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+
+use relm4::prelude::*;
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use gtk::glib::clone;
+
+use crate::news::*;
+use crate::*;
+
+pub struct NewsPanel {
+    news: News,
+
+    carousel: adw::Carousel,
+    posts_stack: gtk::Stack,
+
+    /// Lists of the posts in the same order as `PostKind::list`
+    posts_lists: Vec<gtk::ListBox>
+}
+
+#[derive(Debug)]
+pub enum NewsPanelMsg {
+    SetNews(News),
+
+    /// Banner picture with given index was downloaded
+    SetBannerPicture(usize, PathBuf)
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for NewsPanel {
+    type Init = ();
+    type Input = NewsPanelMsg;
+    type Output = ();
+
+    view! {
+        #[root]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 8,
+
+            #[watch]
+            set_visible: !model.news.is_empty(),
+
+            #[local_ref]
+            carousel -> adw::Carousel {
+                set_height_request: 180,
+                set_spacing: 8,
+
+                set_allow_long_swipes: true,
+
+                #[watch]
+                set_visible: !model.news.banners.is_empty()
+            },
+
+            adw::CarouselIndicatorDots {
+                set_carousel: Some(carousel),
+
+                #[watch]
+                set_visible: model.news.banners.len() > 1
+            },
+
+            gtk::StackSwitcher {
+                set_stack: Some(posts_stack),
+
+                #[watch]
+                set_visible: !model.news.posts.is_empty()
+            },
+
+            #[local_ref]
+            posts_stack -> gtk::Stack {
+                set_vhomogeneous: false,
+
+                #[watch]
+                set_visible: !model.news.posts.is_empty()
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: &Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = Self {
+            news: News::default(),
+
+            carousel: adw::Carousel::new(),
+            posts_stack: gtk::Stack::new(),
+
+            posts_lists: PostKind::list().iter()
+                .map(|_| gtk::ListBox::builder()
+                    .css_classes(["boxed-list"])
+                    .selection_mode(gtk::SelectionMode::None)
+                    .build())
+                .collect()
+        };
+
+        let carousel = &model.carousel;
+        let posts_stack = &model.posts_stack;
+
+        for list in &model.posts_lists {
+            model.posts_stack.add_child(list);
+        }
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            NewsPanelMsg::SetNews(news) => {
+                for i in (0..self.carousel.n_pages()).rev() {
+                    self.carousel.remove(&self.carousel.nth_page(i));
+                }
+
+                for (i, banner) in news.banners.iter().enumerate() {
+                    let picture = gtk::Picture::builder()
+                        .content_fit(gtk::ContentFit::Cover)
+                        .hexpand(true)
+                        .tooltip_text(&banner.name)
+                        .css_classes(["card"])
+                        .build();
+
+                    let click = gtk::GestureClick::new();
+
+                    let url = banner.url.clone();
+
+                    click.connect_released(move |_, _, _, _| open_url(&url));
+
+                    picture.add_controller(click);
+
+                    self.carousel.append(&picture);
+
+                    let banner = banner.clone();
+
+                    std::thread::spawn(clone!(@strong sender => move || {
+                        match get_banner_picture(&banner) {
+                            Ok(path) => sender.input(NewsPanelMsg::SetBannerPicture(i, path)),
+                            Err(err) => tracing::warn!("Failed to download news banner: {err}")
+                        }
+                    }));
+                }
+
+                for (kind, list) in PostKind::list().iter().zip(&self.posts_lists) {
+                    list.remove_all();
+
+                    for post in news.posts(*kind) {
+                        let row = adw::ActionRow::builder()
+                            .title(gtk::glib::markup_escape_text(&post.title))
+                            .subtitle(&post.date)
+                            .activatable(true)
+                            .build();
+
+                        row.add_suffix(&gtk::Image::from_icon_name("adw-external-link-symbolic"));
+
+                        let url = post.url.clone();
+
+                        row.connect_activated(move |_| open_url(&url));
+
+                        list.append(&row);
+                    }
+
+                    let page = self.posts_stack.page(list);
+
+                    page.set_title(&match kind {
+                        PostKind::Event => tr!("news-events"),
+                        PostKind::Announcement => tr!("news-announcements"),
+                        PostKind::Info => tr!("news-info")
+                    });
+
+                    page.set_visible(news.posts(*kind).next().is_some());
+                }
+
+                self.news = news;
+            }
+
+            NewsPanelMsg::SetBannerPicture(index, path) => {
+                let index = index as u32;
+
+                if index < self.carousel.n_pages() {
+                    if let Some(picture) = self.carousel.nth_page(index).downcast_ref::<gtk::Picture>() {
+                        picture.set_filename(Some(&path));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn open_url(url: &str) {
+    if let Err(err) = open::that(url) {
+        tracing::error!("Failed to open news link {url}: {err}");
+    }
+}
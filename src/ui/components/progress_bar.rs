@@ -5,6 +5,7 @@ use anime_launcher_sdk::anime_game_core::prelude::*;
 use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
 
 use crate::*;
+use crate::i18n::{format_number, format_size, SizeUnits};
 
 pub struct ProgressBarInit {
     pub caption: Option<String>,
@@ -72,7 +73,7 @@ impl SimpleAsyncComponent for ProgressBar {
             set_text: Some(&match model.caption.clone() {
                 Some(mut caption) => {
                     if model.display_progress {
-                        caption = format!("{caption}: {:.2}%", model.fraction * 100.0);
+                        caption = format!("{caption}: {}%", format_number(model.fraction * 100.0, 2));
                     }
 
                     if model.display_fraction {
@@ -123,8 +124,8 @@ impl SimpleAsyncComponent for ProgressBar {
                 self.fraction = curr as f64 / total as f64;
 
                 self.downloaded = Some((
-                    prettify_bytes(curr),
-                    prettify_bytes(total)
+                    format_size(curr, SizeUnits::Binary),
+                    format_size(total, SizeUnits::Binary)
                 ));
             }
 
@@ -157,8 +158,8 @@ impl SimpleAsyncComponent for ProgressBar {
                         self.fraction = curr as f64 / total as f64;
 
                         self.downloaded = Some((
-                            prettify_bytes(curr),
-                            prettify_bytes(total)
+                            format_size(curr, SizeUnits::Binary),
+                            format_size(total, SizeUnits::Binary)
                         ));
                     }
 
@@ -13,6 +13,8 @@ use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
 use anime_launcher_sdk::config::ConfigExt;
 use anime_launcher_sdk::genshin::config::Config;
 
+use crate::actions::cleanup::ActivePath;
+
 use super::ComponentGroupMsg;
 use super::progress_bar::ProgressBarMsg;
 
@@ -148,13 +150,19 @@ impl SimpleAsyncComponent for ComponentVersion {
 
                     VersionState::NotDownloaded => {
                         if let Ok(config) = Config::get() {
+                            let temp = config.launcher.temp.unwrap_or_else(std::env::temp_dir);
+
                             // todo
                             let mut installer = Installer::new(&self.download_uri)
                                 .expect("Failed to create installer instance for this version")
-                                .with_temp_folder(config.launcher.temp.unwrap_or_else(std::env::temp_dir));
+                                .with_temp_folder(temp.clone());
+
+                            let mut active = Vec::from(ActivePath::component(&self.download_uri, &temp, self.download_folder.join(&self.name)));
 
                             if let Some(filename) = &self.download_filename {
                                 installer = installer.with_filename(filename.to_owned());
+
+                                active.push(ActivePath::new(temp.join(filename)));
                             }
 
                             self.state = VersionState::Downloading;
@@ -166,6 +174,9 @@ impl SimpleAsyncComponent for ComponentVersion {
                                 progress_bar_sender.send(ProgressBarMsg::Reset);
                                 progress_bar_sender.send(ProgressBarMsg::SetVisible(true));
 
+                                // Keep downloading files from being removed by the cleanup until installation is finished
+                                let _active = active;
+
                                 installer.install(download_folder, move |state| {
                                     match &state {
                                         InstallerUpdate::UnpackingFinished |
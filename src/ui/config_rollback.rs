@@ -0,0 +1,190 @@
+use relm4::prelude::*;
+
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use crate::config_history::{self, Backup};
+use crate::*;
+
+/// Window shown instead of the launcher when the config file can't be loaded
+pub struct ConfigRollbackApp {
+    toast_overlay: adw::ToastOverlay,
+
+    backups: Vec<Backup>,
+    error: String
+}
+
+#[derive(Debug, Clone)]
+pub enum ConfigRollbackAppMsg {
+    Restore(usize),
+    Reset,
+    Exit
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for ConfigRollbackApp {
+    /// Config loading error
+    type Init = String;
+    type Input = ConfigRollbackAppMsg;
+    type Output = ();
+
+    view! {
+        adw::ApplicationWindow {
+            set_default_size: (780, 560),
+            set_title: Some(&tr!("config-broken")),
+
+            #[local_ref]
+            toast_overlay -> adw::ToastOverlay {
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+
+                    adw::HeaderBar {
+                        add_css_class: "flat"
+                    },
+
+                    adw::PreferencesPage {
+                        add = &adw::PreferencesGroup {
+                            adw::StatusPage {
+                                set_icon_name: Some("dialog-warning-symbolic"),
+                                set_title: &tr!("config-broken"),
+
+                                #[watch]
+                                set_description: Some(&tr!("config-broken-description", {
+                                    "error" = model.error.clone()
+                                }))
+                            }
+                        },
+
+                        add = &adw::PreferencesGroup {
+                            set_title: &tr!("config-backups"),
+
+                            #[watch]
+                            set_visible: !model.backups.is_empty(),
+
+                            #[local_ref]
+                            backups_list -> gtk::ListBox {
+                                add_css_class: "boxed-list",
+                                set_selection_mode: gtk::SelectionMode::None
+                            }
+                        },
+
+                        add = &adw::PreferencesGroup {
+                            set_valign: gtk::Align::Center,
+                            set_vexpand: true,
+
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Horizontal,
+                                set_halign: gtk::Align::Center,
+                                set_spacing: 8,
+
+                                gtk::Button {
+                                    set_label: &tr!("reset-config"),
+                                    add_css_class: "pill",
+                                    add_css_class: "destructive-action",
+
+                                    connect_clicked => ConfigRollbackAppMsg::Reset
+                                },
+
+                                gtk::Button {
+                                    set_label: &tr!("exit"),
+                                    add_css_class: "pill",
+
+                                    connect_clicked => ConfigRollbackAppMsg::Exit
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        error: Self::Init,
+        root: &Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        tracing::info!("Initializing config rollback window");
+
+        let model = Self {
+            toast_overlay: adw::ToastOverlay::new(),
+
+            backups: config_history::list().unwrap_or_default(),
+            error
+        };
+
+        let toast_overlay = &model.toast_overlay;
+        let backups_list = gtk::ListBox::new();
+
+        for (i, backup) in model.backups.iter().enumerate() {
+            let row = adw::ActionRow::new();
+
+            row.set_title(&crate::i18n::format_datetime(backup.timestamp));
+
+            let button = gtk::Button::with_label(&tr!("restore"));
+
+            button.set_valign(gtk::Align::Center);
+
+            button.connect_clicked(gtk::glib::clone!(@strong sender => move |_| {
+                sender.input(ConfigRollbackAppMsg::Restore(i));
+            }));
+
+            row.add_suffix(&button);
+
+            backups_list.append(&row);
+        }
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        let result = match msg {
+            ConfigRollbackAppMsg::Restore(i) => match self.backups.get(i) {
+                Some(backup) => config_history::restore(backup),
+                None => return
+            }
+
+            ConfigRollbackAppMsg::Reset => config_history::reset(),
+
+            ConfigRollbackAppMsg::Exit => {
+                relm4::main_application().quit();
+
+                return;
+            }
+        };
+
+        match result {
+            // Restart the app with the same arguments
+            Ok(()) => {
+                let result = std::env::current_exe().and_then(|exe| {
+                    std::process::Command::new(exe)
+                        .args(std::env::args().skip(1))
+                        .spawn()
+                });
+
+                match result {
+                    Ok(_) => relm4::main_application().quit(),
+
+                    Err(err) => {
+                        tracing::error!("Failed to restart the launcher: {err}");
+
+                        let toast = adw::Toast::new(&format!("{}: {err}", tr!("launcher-restart-failed")));
+
+                        // Config is already restored so it's enough to start the launcher manually
+                        toast.set_timeout(0);
+
+                        self.toast_overlay.add_toast(toast);
+                    }
+                }
+            }
+
+            Err(err) => {
+                tracing::error!("Failed to restore config: {err}");
+
+                self.error = err.to_string();
+            }
+        }
+    }
+}
@@ -5,6 +5,9 @@ use adw::prelude::*;
 
 use crate::*;
 use crate::ui::components::progress_bar::*;
+use crate::actions::migrate::*;
+use crate::ui::main::migrate_folder::get_error_description;
+use crate::i18n::{format_size, SizeUnits};
 
 use super::main::*;
 
@@ -15,6 +18,13 @@ pub struct DefaultPathsApp {
     migrate_installation: bool,
     show_progress: bool,
 
+    /// Migration plan is being calculated in background
+    calculating_plan: bool,
+
+    /// Folders migration plan shown before moving anything
+    plan: Option<MigrationPlan>,
+    plan_list: gtk::ListBox,
+
     launcher: PathBuf,
     runners: PathBuf,
     dxvks: PathBuf,
@@ -43,7 +53,21 @@ pub enum Folders {
 pub enum DefaultPathsAppMsg {
     ToggleShowAdditional,
     ChoosePath(Folders),
+
+    /// Use game folder of the imported installation
+    SetGamePath(GameEdition, PathBuf),
+
     Continue,
+
+    /// Migration plan is calculated. Contains title keys of the folders in the plan's order
+    ShowPlan {
+        titles: Vec<&'static str>,
+        plan: MigrationPlan
+    },
+
+    /// Folders migration is finished. Contains error description if it failed
+    MigrationFinished(Result<(), String>),
+
     Exit
 }
 
@@ -63,7 +87,13 @@ impl SimpleAsyncComponent for DefaultPathsApp {
                 set_vexpand: true,
 
                 gtk::Label {
-                    set_label: &tr!("choose-default-paths"),
+                    #[watch]
+                    set_label: &if model.plan.is_some() {
+                        tr!("migration-plan")
+                    } else {
+                        tr!("choose-default-paths")
+                    },
+
                     add_css_class: "title-1"
                 }
             },
@@ -73,7 +103,10 @@ impl SimpleAsyncComponent for DefaultPathsApp {
                 set_vexpand: true,
 
                 #[watch]
-                set_sensitive: !model.show_progress,
+                set_visible: model.plan.is_none(),
+
+                #[watch]
+                set_sensitive: !model.show_progress && !model.calculating_plan,
 
                 adw::ActionRow {
                     set_title: &tr!("launcher-folder"),
@@ -94,6 +127,25 @@ impl SimpleAsyncComponent for DefaultPathsApp {
                 set_valign: gtk::Align::Start,
                 set_vexpand: true,
 
+                #[watch]
+                set_visible: model.plan.is_none(),
+
+                adw::ActionRow {
+                    set_title: &tr!("import-installation"),
+                    set_subtitle: &tr!("import-installation-description"),
+                    set_activatable: true,
+
+                    set_visible: !model.migrate_installation,
+
+                    connect_activated[sender] => move |_| {
+                        sender.output(FirstRunAppMsg::OpenImportInstallation).unwrap();
+                    },
+
+                    add_prefix = &gtk::Image {
+                        set_icon_name: Some("document-open-symbolic")
+                    }
+                },
+
                 adw::ActionRow {
                     set_title: &tr!("show-all-folders"),
                     set_subtitle: &tr!("show-all-folders-subtitle"),
@@ -111,10 +163,10 @@ impl SimpleAsyncComponent for DefaultPathsApp {
                 set_vexpand: true,
 
                 #[watch]
-                set_visible: model.show_additional,
+                set_visible: model.show_additional && model.plan.is_none(),
 
                 #[watch]
-                set_sensitive: !model.show_progress,
+                set_sensitive: !model.show_progress && !model.calculating_plan,
 
                 adw::ActionRow {
                     set_title: &tr!("runners-folder"),
@@ -229,6 +281,25 @@ impl SimpleAsyncComponent for DefaultPathsApp {
                 },
             },
 
+            add = &adw::PreferencesGroup {
+                set_valign: gtk::Align::Center,
+                set_vexpand: true,
+
+                set_description: Some(&tr!("migration-plan-description")),
+
+                #[watch]
+                set_visible: model.plan.is_some(),
+
+                #[watch]
+                set_sensitive: !model.show_progress,
+
+                #[local_ref]
+                plan_list -> gtk::ListBox {
+                    add_css_class: "boxed-list",
+                    set_selection_mode: gtk::SelectionMode::None
+                }
+            },
+
             add = &adw::PreferencesGroup {
                 set_valign: gtk::Align::Center,
                 set_vexpand: true,
@@ -250,12 +321,23 @@ impl SimpleAsyncComponent for DefaultPathsApp {
 
                         set_css_classes: &["suggested-action", "pill"],
 
+                        // Migration can't be started until all the conflicts are resolved
+                        #[watch]
+                        set_sensitive: !model.calculating_plan && !model.plan.as_ref().map(MigrationPlan::has_conflicts).unwrap_or(false),
+
                         connect_clicked => DefaultPathsAppMsg::Continue
                     },
 
+                    gtk::Spinner {
+                        set_spinning: true,
+
+                        #[watch]
+                        set_visible: model.calculating_plan
+                    },
+
                     gtk::Button {
                         set_label: &if model.migrate_installation {
-                            tr!("close", { "form" = "noun" })
+                            tr!("back")
                         } else {
                             tr!("exit")
                         },
@@ -263,7 +345,7 @@ impl SimpleAsyncComponent for DefaultPathsApp {
                         add_css_class: "pill",
 
                         #[watch]
-                        set_visible: !model.migrate_installation,
+                        set_visible: !model.migrate_installation || model.plan.is_some(),
 
                         connect_clicked => DefaultPathsAppMsg::Exit
                     }
@@ -290,7 +372,7 @@ impl SimpleAsyncComponent for DefaultPathsApp {
     async fn init(
         init: Self::Init,
         root: Self::Root,
-        _sender: AsyncComponentSender<Self>,
+        sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
         let model = Self {
             progress_bar: ProgressBar::builder()
@@ -306,6 +388,11 @@ impl SimpleAsyncComponent for DefaultPathsApp {
             migrate_installation: init,
             show_progress: false,
 
+            calculating_plan: false,
+
+            plan: None,
+            plan_list: gtk::ListBox::new(),
+
             launcher: LAUNCHER_FOLDER.to_path_buf(),
             runners: CONFIG.game.wine.builds.clone(),
             dxvks: CONFIG.game.dxvk.builds.clone(),
@@ -322,6 +409,8 @@ impl SimpleAsyncComponent for DefaultPathsApp {
         // Set progress bar width
         model.progress_bar.widget().set_width_request(400);
 
+        let plan_list = &model.plan_list;
+
         let widgets = view_output!();
 
         AsyncComponentParts { model, widgets }
@@ -365,46 +454,84 @@ impl SimpleAsyncComponent for DefaultPathsApp {
                 }
             }
 
-            #[allow(unused_must_use)]
-            DefaultPathsAppMsg::Continue => {
+            DefaultPathsAppMsg::SetGamePath(edition, path) => match edition {
+                GameEdition::Global => self.game_global = path,
+                GameEdition::China  => self.game_china  = path
+            }
+
+            // Show the migration plan before moving anything
+            // Folders sizes are calculated recursively so it's done in background
+            DefaultPathsAppMsg::Continue if self.migrate_installation && self.plan.is_none() => {
+                if self.calculating_plan {
+                    return;
+                }
+
+                self.calculating_plan = true;
+
                 let old_config = Config::get().unwrap_or_else(|_| CONFIG.clone());
 
+                let folders = self.get_migration_folders(&old_config);
+
+                std::thread::spawn(move || {
+                    let plan = MigrationPlan::new(folders.iter()
+                        .map(|(_, from, to, movable)| (from.clone(), to.clone(), *movable)));
+
+                    sender.input(DefaultPathsAppMsg::ShowPlan {
+                        titles: folders.iter().map(|(title, ..)| *title).collect(),
+                        plan
+                    });
+                });
+            }
+
+            DefaultPathsAppMsg::ShowPlan { titles, plan } => {
+                self.calculating_plan = false;
+
+                self.set_plan(&titles, plan);
+            }
+
+            #[allow(unused_must_use)]
+            DefaultPathsAppMsg::Continue => {
                 match self.update_config() {
                     Ok(_) => {
-                        if self.migrate_installation {
+                        if let Some(plan) = &self.plan {
                             self.progress_bar.sender().send(ProgressBarMsg::SetVisible(true));
 
                             self.show_progress = true;
 
-                            let folders = [
-                                (old_config.game.wine.builds, &self.runners),
-                                (old_config.game.dxvk.builds, &self.dxvks),
-                                (old_config.game.wine.prefix, &self.prefix),
-                                (old_config.game.path.global, &self.game_global),
-                                (old_config.game.path.china,  &self.game_china),
-                                (old_config.components.path,  &self.components),
-
-                                (old_config.game.enhancements.fps_unlocker.path, &self.fps_unlocker)
-                            ];
-
-                            #[allow(clippy::expect_fun_call)]
-                            for (i, (from, to)) in folders.iter().enumerate() {
-                                self.progress_bar.sender().send(ProgressBarMsg::UpdateCaption(Some(
-                                    from.to_str().map(|str| str.to_string()).unwrap_or_else(|| format!("{:?}", from))
-                                )));
-
-                                if &from != to && from.exists() {
-                                    move_files::move_files(from, to).expect(&format!("Failed to move folder: {:?} -> {:?}", from, to));
-                                }
+                            let folders = plan.moved_folders().cloned().collect::<Vec<_>>();
+                            let progress_bar_input = self.progress_bar.sender().to_owned();
 
-                                self.progress_bar.sender().send(ProgressBarMsg::UpdateProgress(i as u64 + 1, folders.len() as u64));
-                            }
+                            std::thread::spawn(move || {
+                                for folder in folders {
+                                    progress_bar_input.send(ProgressBarMsg::UpdateCaption(Some(
+                                        folder.from.to_str().map(|str| str.to_string()).unwrap_or_else(|| format!("{:?}", folder.from))
+                                    )));
 
-                            // Restart the app
+                                    let mut failed = Vec::new();
 
-                            std::process::Command::new(std::env::current_exe().unwrap()).spawn().unwrap();
+                                    let result = actions::migrate::migrate_folder(&folder.from, &folder.to, None::<PathBuf>, |update| {
+                                        match update {
+                                            MigrateUpdate::Moving { moved, total } => {
+                                                progress_bar_input.send(ProgressBarMsg::UpdateProgress(moved, total));
+                                            }
 
-                            relm4::main_application().quit();
+                                            MigrateUpdate::FileFailed { path, error } => failed.push(format!("{}: {error}", path.to_string_lossy())),
+
+                                            _ => ()
+                                        }
+                                    });
+
+                                    if let Err(err) = result {
+                                        tracing::error!("Failed to move folder: {:?} -> {:?}: {err}", folder.from, folder.to);
+
+                                        sender.input(DefaultPathsAppMsg::MigrationFinished(Err(get_error_description(&err, &failed))));
+
+                                        return;
+                                    }
+                                }
+
+                                sender.input(DefaultPathsAppMsg::MigrationFinished(Ok(())));
+                            });
                         }
 
                         else {
@@ -421,10 +548,36 @@ impl SimpleAsyncComponent for DefaultPathsApp {
                 }
             }
 
+            #[allow(unused_must_use)]
+            DefaultPathsAppMsg::MigrationFinished(result) => match result {
+                // Restart the app
+                Ok(()) => match std::env::current_exe().and_then(|exe| std::process::Command::new(exe).spawn()) {
+                    Ok(_) => relm4::main_application().quit(),
+
+                    Err(err) => {
+                        sender.output(Self::Output::Toast {
+                            title: tr!("launcher-restart-failed"),
+                            description: Some(err.to_string())
+                        });
+                    }
+                }
+
+                Err(description) => {
+                    self.show_progress = false;
+
+                    self.progress_bar.sender().send(ProgressBarMsg::SetVisible(false));
+
+                    sender.output(Self::Output::Toast {
+                        title: tr!("folder-migration-failed"),
+                        description: Some(description)
+                    });
+                }
+            }
+
             DefaultPathsAppMsg::Exit => {
+                // Return from the migration plan to the folders selection
                 if self.migrate_installation {
-                    // TODO: this shit should return message to general preferences component somehow to close MigrateInstallation window
-                    todo!();
+                    self.plan = None;
                 }
 
                 else {
@@ -436,6 +589,86 @@ impl SimpleAsyncComponent for DefaultPathsApp {
 }
 
 impl DefaultPathsApp {
+    /// Get title keys, source and target paths of the migrated folders, and whether their files are moved
+    ///
+    /// Temp folder's files are never moved as it could be the system one
+    fn get_migration_folders(&self, old_config: &Config) -> [(&'static str, PathBuf, PathBuf, bool); 8] {
+        #[allow(clippy::or_fun_call)]
+        let old_temp = old_config.launcher.temp.clone().unwrap_or(std::env::temp_dir());
+
+        [
+            ("runners-folder",                   old_config.game.wine.builds.clone(), self.runners.clone(),     true),
+            ("dxvks-folder",                     old_config.game.dxvk.builds.clone(), self.dxvks.clone(),       true),
+            ("wine-prefix-folder",               old_config.game.wine.prefix.clone(), self.prefix.clone(),      true),
+            ("global-game-installation-folder",  old_config.game.path.global.clone(), self.game_global.clone(), true),
+            ("chinese-game-installation-folder", old_config.game.path.china.clone(),  self.game_china.clone(),  true),
+            ("components-index",                 old_config.components.path.clone(),  self.components.clone(),  true),
+
+            ("fps-unlocker-folder", old_config.game.enhancements.fps_unlocker.path.clone(), self.fps_unlocker.clone(), true),
+
+            ("temp-folder", old_temp, self.temp.clone(), false)
+        ]
+    }
+
+    /// Show the migration plan. `titles` are keys of the folders titles in the plan's order
+    fn set_plan(&mut self, titles: &[&str], plan: MigrationPlan) {
+        self.plan_list.remove_all();
+
+        for (title, folder) in titles.iter().zip(&plan.folders) {
+            let mut details = match folder.transfer {
+                FolderTransfer::Rename   => tr!("migration-rename"),
+                FolderTransfer::Copy     => tr!("migration-copy"),
+                FolderTransfer::PathOnly => tr!("migration-path-only"),
+                FolderTransfer::Skip     => tr!("migration-skip")
+            };
+
+            if folder.size > 0 {
+                details += &format!(", {}", format_size(folder.size, SizeUnits::Binary));
+            }
+
+            if folder.required_space > 0 {
+                details += &format!(", {}", tr!("migration-required-space", {
+                    "size" = format_size(folder.required_space, SizeUnits::Binary)
+                }));
+            }
+
+            let mut subtitle = vec![
+                format!("{} → {}", folder.from.to_string_lossy(), folder.to.to_string_lossy()),
+                details
+            ];
+
+            for conflict in &folder.conflicts {
+                subtitle.push(match conflict {
+                    MigrationConflict::TargetNotEmpty => tr!("migration-conflict-target-not-empty"),
+                    MigrationConflict::TargetInsideSource => tr!("migration-conflict-target-inside-source"),
+                    MigrationConflict::SameTarget => tr!("migration-conflict-same-target"),
+
+                    MigrationConflict::NotEnoughSpace { required, available } => tr!("migration-conflict-not-enough-space", {
+                        "required" = format_size(*required, SizeUnits::Binary),
+                        "available" = format_size(*available, SizeUnits::Binary)
+                    })
+                });
+            }
+
+            let row = adw::ActionRow::builder()
+                .title(tr!(title))
+                .subtitle(gtk::glib::markup_escape_text(&subtitle.join("\n")))
+                .build();
+
+            if !folder.conflicts.is_empty() {
+                let icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
+
+                icon.add_css_class("warning");
+
+                row.add_suffix(&icon);
+            }
+
+            self.plan_list.append(&row);
+        }
+
+        self.plan = Some(plan);
+    }
+
     pub fn update_config(&self) -> anyhow::Result<()> {
         let mut config = Config::get()?;
 
@@ -449,6 +682,6 @@ impl DefaultPathsApp {
 
         config.game.enhancements.fps_unlocker.path = self.fps_unlocker.clone();
 
-        Config::update_raw(config)
+        crate::config_history::update_raw(config)
     }
 }
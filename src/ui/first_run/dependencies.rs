@@ -1,27 +1,107 @@
 use relm4::prelude::*;
+use gtk::prelude::*;
 use adw::prelude::*;
 
-use anime_launcher_sdk::is_available;
+use crate::system_check::{self, Check, Distro, Package, Severity};
+use crate::ui::preferences::general::system_check::copy_to_clipboard;
 
 use crate::*;
 
 use super::main::FirstRunAppMsg;
 
 pub struct DependenciesApp {
-    show_arch: bool,
-    show_debian: bool,
-    show_fedora: bool
+    ctx: AppContext,
+
+    checks_group: adw::PreferencesGroup,
+    checks_rows: Vec<adw::ActionRow>,
+
+    checks: Vec<Check>,
+    distro: Distro,
+
+    /// Missing required packages
+    missing: Vec<Package>,
+
+    /// Command installing all the missing required packages on the detected distro
+    install_command: Option<String>,
+
+    /// Whether the launcher can run package manager itself
+    can_install: bool,
+    installing: bool
 }
 
 #[derive(Debug, Clone)]
 pub enum DependenciesAppMsg {
+    Check,
+    CopyReport,
+    CopyRemedy(usize),
+
+    /// Install missing packages through `pkexec`, or copy the command
+    /// if the launcher can't do it itself
+    InstallPackages,
+
+    /// Packages installation finished, with an error if it failed
+    PackagesInstalled(Option<String>),
+
     Continue,
     Exit
 }
 
+impl DependenciesApp {
+    /// Run system checks and render found problems
+    fn check(&mut self, sender: &AsyncComponentSender<Self>) {
+        for row in self.checks_rows.drain(..) {
+            self.checks_group.remove(&row);
+        }
+
+        self.checks = system_check::run(&self.ctx.config());
+
+        self.missing = self.checks.iter()
+            .filter(|check| check.severity == Severity::Error)
+            .filter_map(|check| check.package)
+            .collect();
+
+        let packages = self.missing.iter()
+            .map(|package| package.name(self.distro))
+            .collect::<Vec<_>>();
+
+        self.install_command = if packages.is_empty() {
+            None
+        } else {
+            self.distro.install_command(&packages)
+        };
+
+        // Only show problems on this page
+        for (i, check) in self.checks.iter().enumerate().filter(|(_, check)| check.severity >= Severity::Warning) {
+            let row = adw::ActionRow::new();
+
+            row.set_title(&check.name);
+            row.set_subtitle(&check.description);
+
+            row.add_prefix(&gtk::Image::from_icon_name(check.severity.icon_name()));
+
+            if let Some(remedy) = &check.remedy {
+                let button = gtk::Button::from_icon_name("edit-copy-symbolic");
+
+                button.add_css_class("flat");
+                button.set_valign(gtk::Align::Center);
+                button.set_tooltip_text(Some(remedy));
+
+                button.connect_clicked(gtk::glib::clone!(@strong sender => move |_| {
+                    sender.input(DependenciesAppMsg::CopyRemedy(i));
+                }));
+
+                row.add_suffix(&button);
+            }
+
+            self.checks_group.add(&row);
+            self.checks_rows.push(row);
+        }
+    }
+}
+
 #[relm4::component(async, pub)]
 impl SimpleAsyncComponent for DependenciesApp {
-    type Init = ();
+    type Init = AppContext;
     type Input = DependenciesAppMsg;
     type Output = FirstRunAppMsg;
 
@@ -40,78 +120,69 @@ impl SimpleAsyncComponent for DependenciesApp {
 
                 gtk::Label {
                     set_label: &tr!("missing-dependencies-message"),
-    
+
                     set_justify: gtk::Justification::Center,
                     set_wrap: true,
                     set_margin_top: 32
                 }
             },
 
-            add = &adw::PreferencesGroup {
+            #[local_ref]
+            add = checks_group -> adw::PreferencesGroup {
                 set_valign: gtk::Align::Center,
                 set_vexpand: true,
 
-                gtk::Box {
-                    set_orientation: gtk::Orientation::Vertical,
-                    set_spacing: 16,
-
-                    gtk::Box {
-                        set_orientation: gtk::Orientation::Vertical,
-                        set_spacing: 16,
+                #[wrap(Some)]
+                set_header_suffix = &gtk::Button {
+                    add_css_class: "flat",
 
-                        #[watch]
-                        set_visible: model.show_arch,
+                    set_valign: gtk::Align::Center,
 
-                        gtk::Label {
-                            set_label: "Arch (pacman)"
-                        },
-
-                        gtk::Entry {
-                            set_text: "sudo pacman -Syu git xdelta3 p7zip",
-                            set_editable: false
-                        }
+                    adw::ButtonContent {
+                        set_icon_name: "edit-copy-symbolic",
+                        set_label: &tr!("copy-report")
                     },
 
-                    gtk::Box {
-                        set_orientation: gtk::Orientation::Vertical,
-                        set_spacing: 16,
+                    connect_clicked => DependenciesAppMsg::CopyReport
+                }
+            },
 
-                        #[watch]
-                        set_visible: model.show_debian,
+            add = &adw::PreferencesGroup {
+                set_valign: gtk::Align::Center,
+                set_vexpand: true,
 
-                        gtk::Label {
-                            set_label: "Debian / Ubuntu (apt)"
-                        },
+                #[watch]
+                set_visible: model.install_command.is_some(),
 
-                        gtk::Entry {
-                            set_text: "sudo apt install git xdelta3 p7zip-full",
-                            set_editable: false
-                        }
-                    },
+                gtk::Entry {
+                    #[watch]
+                    set_text: model.install_command.as_deref().unwrap_or_default(),
 
-                    gtk::Box {
-                        set_orientation: gtk::Orientation::Vertical,
-                        set_spacing: 16,
+                    set_editable: false
+                },
 
-                        #[watch]
-                        set_visible: model.show_fedora,
+                gtk::Button {
+                    set_halign: gtk::Align::Center,
+                    set_margin_top: 16,
+                    add_css_class: "pill",
 
-                        gtk::Label {
-                            set_label: "Fedora (dnf)"
-                        },
+                    set_label: &if model.can_install {
+                        tr!("install-missing-packages")
+                    } else {
+                        tr!("copy-command")
+                    },
 
-                        gtk::Entry {
-                            set_text: "sudo dnf install git xdelta p7zip",
-                            set_editable: false
-                        }
-                    }
+                    #[watch]
+                    set_sensitive: !model.installing,
+
+                    connect_clicked => DependenciesAppMsg::InstallPackages
                 }
             },
 
             add = &adw::PreferencesGroup {
                 set_valign: gtk::Align::Center,
                 set_vexpand: true,
-    
+
                 gtk::Box {
                     set_orientation: gtk::Orientation::Horizontal,
                     set_halign: gtk::Align::Center,
@@ -136,60 +207,116 @@ impl SimpleAsyncComponent for DependenciesApp {
     }
 
     async fn init(
-        _init: Self::Init,
+        ctx: Self::Init,
         root: Self::Root,
-        _sender: AsyncComponentSender<Self>,
+        sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
-        let distro = whatadistro::identify();
+        let distro = Distro::detect();
 
         let model = Self {
-            show_arch: match &distro {
-                Some(distro) => distro.is_similar("arch"),
-                None => false
-            },
+            ctx,
 
-            show_debian: match &distro {
-                Some(distro) => distro.is_similar("debian"),
-                None => false
-            },
+            checks_group: adw::PreferencesGroup::new(),
+            checks_rows: Vec::new(),
 
-            show_fedora: match &distro {
-                Some(distro) => distro.is_similar("fedora"),
-                None => false
-            }
+            checks: Vec::new(),
+            distro,
+
+            missing: Vec::new(),
+            install_command: None,
+
+            can_install: system_check::can_install_packages(distro),
+            installing: false
         };
 
+        let checks_group = &model.checks_group;
+
         let widgets = view_output!();
 
+        sender.input(DependenciesAppMsg::Check);
+
         AsyncComponentParts { model, widgets }
     }
 
     async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
         match msg {
+            DependenciesAppMsg::Check => self.check(&sender),
+
             #[allow(unused_must_use)]
-            DependenciesAppMsg::Continue => {
-                let packages = ["git", "xdelta3"];
+            DependenciesAppMsg::CopyReport => {
+                copy_to_clipboard(&system_check::report(&self.checks));
+
+                sender.output(Self::Output::Toast {
+                    title: tr!("report-copied"),
+                    description: None
+                });
+            }
+
+            #[allow(unused_must_use)]
+            DependenciesAppMsg::CopyRemedy(i) => {
+                if let Some(Some(remedy)) = self.checks.get(i).map(|check| &check.remedy) {
+                    copy_to_clipboard(remedy);
+
+                    sender.output(Self::Output::Toast {
+                        title: tr!("remedy-copied"),
+                        description: None
+                    });
+                }
+            }
+
+            #[allow(unused_must_use)]
+            DependenciesAppMsg::InstallPackages => {
+                if !self.can_install {
+                    if let Some(command) = &self.install_command {
+                        copy_to_clipboard(command);
 
-                for package in packages {
-                    if !is_available(package) {
                         sender.output(Self::Output::Toast {
-                            title: tr!("package-not-available", {
-                                "package" = package
-                            }),
-                            description: None
+                            title: tr!("command-copied"),
+                            description: Some(tr!("command-copied-description"))
                         });
-
-                        return;
                     }
+
+                    return;
                 }
 
-                // 7z sometimes has different binaries
-                if !is_available("7z") && !is_available("7za") {
+                self.installing = true;
+
+                let distro = self.distro;
+                let packages = self.missing.clone();
+
+                std::thread::spawn(move || {
+                    let result = system_check::install_packages(distro, &packages);
+
+                    sender.input(DependenciesAppMsg::PackagesInstalled(result.err().map(|err| err.to_string())));
+                });
+            }
+
+            #[allow(unused_must_use)]
+            DependenciesAppMsg::PackagesInstalled(error) => {
+                self.installing = false;
+
+                if let Some(error) = error {
+                    tracing::error!("Failed to install missing packages: {error}");
+
+                    sender.output(Self::Output::Toast {
+                        title: tr!("packages-install-failed"),
+                        description: Some(error)
+                    });
+                }
+
+                self.check(&sender);
+            }
+
+            #[allow(unused_must_use)]
+            DependenciesAppMsg::Continue => {
+                self.check(&sender);
+
+                if let Some(check) = self.checks.iter().find(|check| check.severity == Severity::Error) {
                     sender.output(Self::Output::Toast {
                         title: tr!("package-not-available", {
-                            "package" = "7z"
+                            "package" = check.name.clone()
                         }),
-                        description: None
+                        description: Some(check.description.clone())
                     });
 
                     return;
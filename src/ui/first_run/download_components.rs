@@ -1,13 +1,9 @@
-use std::path::PathBuf;
-
 use relm4::prelude::*;
 use adw::prelude::*;
 
 use anime_launcher_sdk::anime_game_core::prelude::*;
-use anime_launcher_sdk::wincompatlib::prelude::*;
 
 use anime_launcher_sdk::components::*;
-use anime_launcher_sdk::components::wine::UnifiedWine;
 
 use anime_launcher_sdk::config::ConfigExt;
 use anime_launcher_sdk::genshin::config::Config;
@@ -17,12 +13,11 @@ use anime_launcher_sdk::integrations::steam;
 use super::main::FirstRunAppMsg;
 
 use crate::ui::components::*;
+use crate::actions::storage_plan::*;
+use crate::actions::components::{self, InstallError};
+use crate::i18n::{format_size, SizeUnits};
 use crate::*;
 
-fn get_installer(uri: &str, temp: Option<PathBuf>) -> anyhow::Result<Installer> {
-    Ok(Installer::new(uri)?.with_temp_folder(temp.unwrap_or_else(std::env::temp_dir)))
-}
-
 pub struct DownloadComponentsApp {
     progress_bar: AsyncController<ProgressBar>,
 
@@ -35,6 +30,11 @@ pub struct DownloadComponentsApp {
     selected_wine: Option<wine::Version>,
     selected_dxvk: Option<dxvk::Version>,
 
+    /// Free space needed by the selected components, `None` while it's being calculated
+    storage_plan: Option<StoragePlan>,
+    storage_plan_group: adw::PreferencesGroup,
+    storage_plan_rows: Vec<adw::ActionRow>,
+
     /// `None` - default,
     /// `Some(false)` - processing,
     /// `Some(true)` - done
@@ -66,6 +66,11 @@ pub struct DownloadComponentsApp {
 pub enum DownloadComponentsAppMsg {
     // Flow: Typical
     UpdateVersionsLists,
+
+    /// Calculate free space needed by the selected components in background
+    UpdateStoragePlan,
+    SetStoragePlan(StoragePlan),
+
     DownloadWine,
     CreatePrefix,
     DownloadDXVK,
@@ -116,7 +121,9 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
                     set_model: Some(&gtk::StringList::new(model.wine_versions.iter()
                         .map(|version| version.title.as_ref())
                         .collect::<Vec<&str>>()
-                        .as_slice()))
+                        .as_slice())),
+
+                    connect_selected_notify => DownloadComponentsAppMsg::UpdateStoragePlan
                 },
 
                 #[local_ref]
@@ -130,10 +137,35 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
                     set_model: Some(&gtk::StringList::new(model.dxvk_versions.iter()
                         .map(|version| version.name.as_ref())
                         .collect::<Vec<&str>>()
-                        .as_slice()))
+                        .as_slice())),
+
+                    connect_selected_notify => DownloadComponentsAppMsg::UpdateStoragePlan
                 }
             },
 
+            #[local_ref]
+            add = storage_plan_group -> adw::PreferencesGroup {
+                set_valign: gtk::Align::Center,
+                set_vexpand: true,
+
+                set_title: &tr!("storage-plan"),
+
+                #[watch]
+                set_description: Some(&match &model.storage_plan {
+                    Some(plan) => tr!("storage-plan-download-size", {
+                        "size" = match plan.download_size {
+                            Some(size) => format_size(size, SizeUnits::Binary),
+                            None => tr!("unknown-size")
+                        }
+                    }),
+
+                    None => tr!("storage-plan-calculating")
+                }),
+
+                #[watch]
+                set_visible: !model.downloading && !model.is_managed
+            },
+
             add = &adw::PreferencesGroup {
                 set_valign: gtk::Align::Center,
                 set_vexpand: true,
@@ -302,6 +334,10 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
             selected_wine: None,
             selected_dxvk: None,
 
+            storage_plan: None,
+            storage_plan_group: adw::PreferencesGroup::new(),
+            storage_plan_rows: Vec::new(),
+
             downloading_wine: None,
             downloading_wine_version: String::new(),
 
@@ -325,6 +361,7 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
 
         let wine_combo = &model.wine_combo;
         let dxvk_combo = &model.dxvk_combo;
+        let storage_plan_group = &model.storage_plan_group;
 
         let widgets = view_output!();
 
@@ -349,6 +386,53 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
                     .take(4)
                     .flat_map(|group| group.versions.into_iter().take(4))
                     .collect();
+
+                sender.input(DownloadComponentsAppMsg::UpdateStoragePlan);
+            }
+
+            DownloadComponentsAppMsg::UpdateStoragePlan => {
+                let config = Config::get().unwrap_or_else(|_| CONFIG.clone());
+
+                let wine = self.wine_versions.get(self.wine_combo.selected() as usize)
+                    .filter(|wine| !wine.is_downloaded_in(&config.game.wine.builds))
+                    .map(|wine| wine.uri.clone());
+
+                let dxvk = self.dxvk_versions.get(self.dxvk_combo.selected() as usize)
+                    .filter(|dxvk| !dxvk.is_downloaded_in(&config.game.dxvk.builds))
+                    .map(|dxvk| dxvk.uri.clone());
+
+                self.storage_plan = None;
+
+                std::thread::spawn(move || {
+                    let components = wine.iter().map(|uri| (StorageUsage::WineBuilds, uri.as_str()))
+                        .chain(dxvk.iter().map(|uri| (StorageUsage::DxvkBuilds, uri.as_str())));
+
+                    sender.input(DownloadComponentsAppMsg::SetStoragePlan(StoragePlan::for_components(&config, components)));
+                });
+            }
+
+            DownloadComponentsAppMsg::SetStoragePlan(plan) => {
+                for row in self.storage_plan_rows.drain(..) {
+                    self.storage_plan_group.remove(&row);
+                }
+
+                for filesystem in &plan.filesystems {
+                    let (folders, space) = crate::ui::storage_plan::describe_filesystem(filesystem);
+
+                    let row = adw::ActionRow::new();
+
+                    row.set_title(&folders);
+                    row.set_subtitle(&space);
+
+                    if filesystem.status() >= StorageStatus::Low {
+                        row.add_prefix(&gtk::Image::from_icon_name("dialog-warning-symbolic"));
+                    }
+
+                    self.storage_plan_group.add(&row);
+                    self.storage_plan_rows.push(row);
+                }
+
+                self.storage_plan = Some(plan);
             }
 
             DownloadComponentsAppMsg::LoadManagedVersionLists => {
@@ -371,6 +455,17 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
 
             #[allow(unused_must_use)]
             DownloadComponentsAppMsg::DownloadWine => {
+                if let Some(plan) = &self.storage_plan {
+                    if plan.status() == StorageStatus::NotEnough {
+                        sender.output(Self::Output::Toast {
+                            title: tr!("storage-plan-not-enough"),
+                            description: Some(crate::ui::storage_plan::describe(plan))
+                        });
+
+                        return;
+                    }
+                }
+
                 let config = Config::get().unwrap_or_else(|_| CONFIG.clone());
 
                 self.selected_wine = Some(self.wine_versions[self.wine_combo.selected() as usize].clone());
@@ -394,7 +489,7 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
 
                     config.game.wine.selected = Some(wine.name);
 
-                    if let Err(err) = Config::update_raw(config) {
+                    if let Err(err) = crate::config_history::update_raw(config) {
                         tracing::error!("Failed to update config: {err}");
 
                         sender.output(Self::Output::Toast {
@@ -411,69 +506,42 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
                     std::thread::spawn(move || {
                         tracing::info!("Installing wine: {}", wine.name);
 
-                        // Install wine
-                        match get_installer(&wine.uri, config.launcher.temp.clone()) {
-                            Ok(mut installer) => {
-                                // Create wine builds folder
-                                if config.game.wine.builds.exists() {
-                                    std::fs::create_dir_all(&config.game.wine.builds)
-                                        .expect("Failed to create wine builds directory");
-                                }
-
-                                installer.install(&config.game.wine.builds, move |update| {
-                                    match &update {
-                                        InstallerUpdate::DownloadingError(err) => {
-                                            tracing::error!("Failed to download wine: {err}");
-
-                                            sender.output(Self::Output::Toast {
-                                                title: tr!("wine-download-error"),
-                                                description: Some(err.to_string())
-                                            });
-                                        }
-
-                                        InstallerUpdate::UnpackingError(err) => {
-                                            tracing::error!("Failed to unpack wine: {err}");
-
-                                            sender.output(Self::Output::Toast {
-                                                title: tr!("wine-unpack-errror"),
-                                                description: Some(err.clone())
-                                            });
-                                        }
-
-                                        // Create prefix
-                                        InstallerUpdate::UnpackingFinished => {
-                                            let mut config = Config::get().unwrap_or_else(|_| CONFIG.clone());
-
-                                            config.game.wine.selected = Some(wine.name.clone());
-
-                                            if let Err(err) = Config::update_raw(config) {
-                                                tracing::error!("Failed to update config: {err}");
-
-                                                sender.output(Self::Output::Toast {
-                                                    title: tr!("config-update-error"),
-                                                    description: Some(err.to_string())
-                                                });
-                                            }
-
-                                            sender.input(DownloadComponentsAppMsg::CreatePrefix);
-                                        },
-
-                                        _ => ()
-                                    }
-
-                                    progress_bar_input.send(ProgressBarMsg::UpdateFromState(DiffUpdate::InstallerUpdate(update)));
-                                });
-                            }
+                        let result = components::install(&wine.uri, &wine.name, &config.game.wine.builds, config.launcher.temp.clone(), |update| {
+                            progress_bar_input.send(ProgressBarMsg::UpdateFromState(DiffUpdate::InstallerUpdate(update)));
+                        });
 
-                            Err(err) => {
-                                tracing::error!("Failed to initialize wine installer: {err}");
+                        if let Err(err) = result {
+                            tracing::error!("Failed to install wine: {err}");
 
-                                sender.output(Self::Output::Toast {
-                                    title: tr!("wine-install-failed"),
-                                    description: Some(err.to_string())
-                                });
-                            }
+                            let title = match &err {
+                                InstallError::Download(_) => tr!("wine-download-error"),
+                                InstallError::Unpack(_) => tr!("wine-unpack-errror"),
+                                _ => tr!("wine-install-failed")
+                            };
+
+                            sender.output(Self::Output::Toast {
+                                title,
+                                description: Some(err.to_string())
+                            });
+
+                            return;
+                        }
+
+                        let mut config = Config::get().unwrap_or_else(|_| CONFIG.clone());
+
+                        config.game.wine.selected = Some(wine.name);
+
+                        if let Err(err) = crate::config_history::update_raw(config) {
+                            tracing::error!("Failed to update config: {err}");
+
+                            sender.output(Self::Output::Toast {
+                                title: tr!("config-update-error"),
+                                description: Some(err.to_string())
+                            });
                         }
+
+                        // Create prefix
+                        sender.input(DownloadComponentsAppMsg::CreatePrefix);
                     });
                 }
             }
@@ -488,7 +556,7 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
 
                 config.game.wine.selected = Some(wine.name);
 
-                if let Err(err) = Config::update_raw(config) {
+                if let Err(err) = crate::config_history::update_raw(config) {
                     tracing::error!("Failed to update config: {err}");
 
                     sender.output(Self::Output::Toast {
@@ -510,16 +578,10 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
 
                 tracing::info!("Creating wine prefix");
 
-                let wine = self.selected_wine.as_ref().unwrap();
-
-                let wine = wine
-                    .to_wine(config.components.path, Some(config.game.wine.builds.join(&wine.name)))
-                    .with_prefix(&config.game.wine.prefix)
-                    .with_loader(WineLoader::Current)
-                    .with_arch(WineArch::Win64);
+                let wine = self.selected_wine.clone().unwrap();
 
                 std::thread::spawn(move || {
-                    match wine.init_prefix(None::<&str>) {
+                    match components::create_prefix(&config, &wine) {
                         // Download DXVK
                         Ok(_) => sender.input(DownloadComponentsAppMsg::DownloadDXVK),
 
@@ -553,57 +615,27 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
 
                 else {
                     std::thread::spawn(move || {
-                        // Install DXVK
                         tracing::info!("Installing DXVK: {}", dxvk.name);
 
-                        match get_installer(&dxvk.uri, config.launcher.temp.clone()) {
-                            Ok(mut installer) => {
-                                let progress_bar_input = progress_bar_input.clone();
-                                let sender = sender.clone();
-
-                                // Create DXVK builds folder
-                                if config.game.dxvk.builds.exists() {
-                                    std::fs::create_dir_all(&config.game.dxvk.builds)
-                                        .expect("Failed to create DXVK builds directory");
-                                }
-
-                                installer.install(&config.game.dxvk.builds, move |update| {
-                                    match &update {
-                                        InstallerUpdate::DownloadingError(err) => {
-                                            tracing::error!("Failed to download dxvk: {err}");
-
-                                            sender.output(Self::Output::Toast {
-                                                title: tr!("dxvk-download-error"),
-                                                description: Some(err.to_string())
-                                            });
-                                        }
-
-                                        InstallerUpdate::UnpackingError(err) => {
-                                            tracing::error!("Failed to unpack dxvk: {err}");
-    
-                                            sender.output(Self::Output::Toast {
-                                                title: tr!("dxvk-unpack-error"),
-                                                description: Some(err.clone())
-                                            });
-                                        }
-
-                                        // Apply DXVK
-                                        InstallerUpdate::UnpackingFinished => {
-                                            sender.input(DownloadComponentsAppMsg::ApplyDXVK);
-                                        }
-
-                                        _ => ()
-                                    }
-
-                                    progress_bar_input.send(ProgressBarMsg::UpdateFromState(DiffUpdate::InstallerUpdate(update)));
-                                });
-                            }
+                        let result = components::install(&dxvk.uri, &dxvk.name, &config.game.dxvk.builds, config.launcher.temp.clone(), |update| {
+                            progress_bar_input.send(ProgressBarMsg::UpdateFromState(DiffUpdate::InstallerUpdate(update)));
+                        });
+
+                        match result {
+                            // Apply DXVK
+                            Ok(()) => sender.input(DownloadComponentsAppMsg::ApplyDXVK),
 
                             Err(err) => {
-                                tracing::error!("Failed to initialize dxvk installer: {err}");
+                                tracing::error!("Failed to install dxvk: {err}");
+
+                                let title = match &err {
+                                    InstallError::Download(_) => tr!("dxvk-download-error"),
+                                    InstallError::Unpack(_) => tr!("dxvk-unpack-error"),
+                                    _ => tr!("dxvk-install-failed")
+                                };
 
                                 sender.output(Self::Output::Toast {
-                                    title: tr!("dxvk-install-failed"),
+                                    title,
                                     description: Some(err.to_string())
                                 });
                             }
@@ -624,52 +656,34 @@ impl SimpleAsyncComponent for DownloadComponentsApp {
                 let wine = self.selected_wine.clone().unwrap();
                 let dxvk = self.selected_dxvk.clone().unwrap();
 
-                let group = wine.find_group(&config.components.path).unwrap().unwrap();
-
-                // Apply DXVK if we need it
-                if wine.features_in(&group).unwrap_or_default().need_dxvk {
-                    let wine = wine
-                        .to_wine(config.components.path, Some(config.game.wine.builds.join(&wine.name)))
-                        .with_loader(WineLoader::Current)
-                        .with_arch(WineArch::Win64)
-                        .with_prefix(config.game.wine.prefix);
-
-                    std::thread::spawn(move || {
-                        let params = InstallParams {
-                            // We just created prefix so don't need to repair it
-                            repair_dlls: false,
-
-                            ..InstallParams::default()
-                        };
-
-                        let UnifiedWine::Default(wine) = wine else {
-                            sender.input(DownloadComponentsAppMsg::Continue);
-
-                            return;
-                        };
-
-                        match wine.install_dxvk(config.game.dxvk.builds.join(&dxvk.name), params) {
-                            // Go to next page
-                            Ok(_) => sender.input(DownloadComponentsAppMsg::Continue),
+                std::thread::spawn(move || {
+                    // Skip DXVK applying if we don't need it
+                    let result = components::need_dxvk(&config, &wine).and_then(|need_dxvk| {
+                        if need_dxvk {
+                            components::apply_dxvk(&config, &wine, &dxvk)
+                        }
 
-                            Err(err) => {
-                                tracing::error!("Failed to apply DXVK: {err}");
+                        else {
+                            tracing::info!("Selected wine version has `need_dxvk = false` feature. Skipping DXVK applying...");
 
-                                sender.output(Self::Output::Toast {
-                                    title: tr!("dxvk-apply-error"),
-                                    description: Some(err.to_string())
-                                });
-                            }
+                            Ok(())
                         }
                     });
-                }
 
-                // Skip DXVK applying if we don't need it
-                else {
-                    tracing::info!("Selected wine version has `need_dxvk = false` feature. Skipping DXVK applying...");
+                    match result {
+                        // Go to next page
+                        Ok(_) => sender.input(DownloadComponentsAppMsg::Continue),
 
-                    sender.input(DownloadComponentsAppMsg::Continue);
-                }
+                        Err(err) => {
+                            tracing::error!("Failed to apply DXVK: {err}");
+
+                            sender.output(Self::Output::Toast {
+                                title: tr!("dxvk-apply-error"),
+                                description: Some(err.to_string())
+                            });
+                        }
+                    }
+                });
             }
 
             #[allow(unused_must_use)]
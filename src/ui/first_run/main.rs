@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use relm4::prelude::*;
 
 use gtk::prelude::*;
@@ -6,6 +8,7 @@ use adw::prelude::*;
 use anime_launcher_sdk::components::loader::ComponentsLoader;
 
 use crate::*;
+use crate::ui::import_installation::*;
 
 use super::welcome::*;
 use super::dependencies::*;
@@ -14,17 +17,18 @@ use super::select_voiceovers::*;
 use super::download_components::*;
 use super::finish::*;
 
-pub static mut MAIN_WINDOW: Option<adw::ApplicationWindow> = None;
-
 // TODO: add special page for launcher style selection
 
 pub struct FirstRunApp {
+    ctx: AppContext,
+
     welcome: AsyncController<WelcomeApp>,
     dependencies: AsyncController<DependenciesApp>,
     default_paths: AsyncController<DefaultPathsApp>,
     select_voiceovers: AsyncController<SelectVoiceoversApp>,
     download_components: AsyncController<DownloadComponentsApp>,
     finish: AsyncController<FinishApp>,
+    import_installation: AsyncController<ImportInstallationApp>,
 
     toast_overlay: adw::ToastOverlay,
     carousel: adw::Carousel,
@@ -44,6 +48,15 @@ pub enum FirstRunAppMsg {
     ScrollToDownloadSteamComponents,
     ScrollToFinish,
 
+    OpenImportInstallation,
+
+    /// Existing game installation was imported from the given folder
+    InstallationImported {
+        edition: GameEdition,
+        path: PathBuf,
+        voices: Vec<VoiceLocale>
+    },
+
     Toast {
         title: String,
         description: Option<String>
@@ -52,7 +65,7 @@ pub enum FirstRunAppMsg {
 
 #[relm4::component(pub)]
 impl SimpleComponent for FirstRunApp {
-    type Init = ();
+    type Init = AppContext;
     type Input = FirstRunAppMsg;
     type Output = ();
 
@@ -117,7 +130,7 @@ impl SimpleComponent for FirstRunApp {
     }
 
     fn init(
-        _parent: Self::Init,
+        ctx: Self::Init,
         root: &Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -127,12 +140,14 @@ impl SimpleComponent for FirstRunApp {
         let carousel = adw::Carousel::new();
 
         let model = Self {
+            ctx: ctx.clone(),
+
             welcome: WelcomeApp::builder()
-                .launch(())
+                .launch(ctx.clone())
                 .forward(sender.input_sender(), std::convert::identity),
 
             dependencies: DependenciesApp::builder()
-                .launch(())
+                .launch(ctx.clone())
                 .forward(sender.input_sender(), std::convert::identity),
 
             default_paths: DefaultPathsApp::builder()
@@ -151,6 +166,16 @@ impl SimpleComponent for FirstRunApp {
                 .launch(())
                 .forward(sender.input_sender(), std::convert::identity),
 
+            import_installation: ImportInstallationApp::builder()
+                .launch(())
+                .forward(sender.input_sender(), |msg| match msg {
+                    ImportInstallationAppOutput::Imported { installation, path } => FirstRunAppMsg::InstallationImported {
+                        edition: installation.edition,
+                        path,
+                        voices: installation.voices
+                    }
+                }),
+
             toast_overlay,
             carousel,
 
@@ -163,11 +188,8 @@ impl SimpleComponent for FirstRunApp {
 
         let widgets = view_output!();
 
-        unsafe {
-            MAIN_WINDOW = Some(widgets.window.clone());
-        }
-
-        crate::READY.store(true, Ordering::Relaxed);
+        ctx.set_main_window(widgets.window.clone());
+        ctx.set_ready(true);
 
         tracing::info!("First run window initialized. App is ready");
 
@@ -271,7 +293,19 @@ impl SimpleComponent for FirstRunApp {
                 self.carousel.scroll_to(self.finish.widget(), true);
             }
 
-            FirstRunAppMsg::Toast { title, description } => unsafe {
+            FirstRunAppMsg::OpenImportInstallation => {
+                self.import_installation.widget().set_transient_for(self.ctx.main_window().as_ref());
+                self.import_installation.widget().present();
+            }
+
+            FirstRunAppMsg::InstallationImported { edition, path, voices } => {
+                self.import_installation.widget().close();
+
+                self.default_paths.emit(DefaultPathsAppMsg::SetGamePath(edition, path));
+                self.select_voiceovers.emit(SelectVoiceoversAppMsg::SetVoices(voices));
+            }
+
+            FirstRunAppMsg::Toast { title, description } => {
                 let toast = adw::Toast::new(&title);
 
                 toast.set_timeout(4);
@@ -279,7 +313,7 @@ impl SimpleComponent for FirstRunApp {
                 if let Some(description) = description {
                     toast.set_button_label(Some(&tr!("details")));
 
-                    let dialog = adw::MessageDialog::new(MAIN_WINDOW.as_ref(), Some(&title), Some(&description));
+                    let dialog = adw::MessageDialog::new(self.ctx.main_window().as_ref(), Some(&title), Some(&description));
 
                     dialog.add_response("close", &tr!("close", { "form" = "noun" }));
                     dialog.add_response("save", &tr!("save"));
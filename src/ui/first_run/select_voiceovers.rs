@@ -19,6 +19,9 @@ pub struct SelectVoiceoversApp {
 
 #[derive(Debug, Clone)]
 pub enum SelectVoiceoversAppMsg {
+    /// Select voice packages of the imported installation
+    SetVoices(Vec<VoiceLocale>),
+
     Continue,
     Exit
 }
@@ -136,6 +139,13 @@ impl SimpleAsyncComponent for SelectVoiceoversApp {
 
     async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
         match msg {
+            SelectVoiceoversAppMsg::SetVoices(voices) => {
+                self.english.set_active(voices.contains(&VoiceLocale::English));
+                self.japanese.set_active(voices.contains(&VoiceLocale::Japanese));
+                self.korean.set_active(voices.contains(&VoiceLocale::Korean));
+                self.chinese.set_active(voices.contains(&VoiceLocale::Chinese));
+            }
+
             #[allow(unused_must_use)]
             SelectVoiceoversAppMsg::Continue => {
                 match self.update_config() {
@@ -180,6 +190,6 @@ impl SelectVoiceoversApp {
             config.game.voices.push(String::from("zh-cn"));
         }
 
-        Config::update_raw(config)
+        crate::config_history::update_raw(config)
     }
 }
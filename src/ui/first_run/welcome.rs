@@ -1,13 +1,15 @@
 use relm4::prelude::*;
 use adw::prelude::*;
 
-use anime_launcher_sdk::is_available;
+use crate::system_check::{self, Severity};
 
 use crate::*;
 
 use super::main::FirstRunAppMsg;
 
-pub struct WelcomeApp;
+pub struct WelcomeApp {
+    ctx: AppContext
+}
 
 #[derive(Debug, Clone)]
 pub enum WelcomeAppMsg {
@@ -16,7 +18,7 @@ pub enum WelcomeAppMsg {
 
 #[relm4::component(async, pub)]
 impl SimpleAsyncComponent for WelcomeApp {
-    type Init = ();
+    type Init = AppContext;
     type Input = WelcomeAppMsg;
     type Output = FirstRunAppMsg;
 
@@ -69,11 +71,11 @@ impl SimpleAsyncComponent for WelcomeApp {
     }
 
     async fn init(
-        _init: Self::Init,
+        ctx: Self::Init,
         root: Self::Root,
         _sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
-        let model = Self;
+        let model = Self { ctx };
         let widgets = view_output!();
 
         AsyncComponentParts { model, widgets }
@@ -83,10 +85,8 @@ impl SimpleAsyncComponent for WelcomeApp {
         match msg {
             #[allow(unused_must_use)]
             WelcomeAppMsg::Continue => {
-                let installed =
-                    is_available("git") &&
-                    is_available("xdelta3") &&
-                    (is_available("7z") || is_available("7za"));
+                let installed = !system_check::run(&self.ctx.config()).iter()
+                    .any(|check| check.severity == Severity::Error);
 
                 if installed {
                     sender.output(Self::Output::ScrollToDefaultPaths);
@@ -0,0 +1,461 @@
+use std::path::PathBuf;
+
+use relm4::prelude::*;
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use anime_launcher_sdk::anime_game_core::genshin::consts::GameEdition;
+
+use crate::*;
+use crate::ui::components::progress_bar::*;
+use crate::actions::import::*;
+
+pub struct ImportInstallationApp {
+    progress_bar: AsyncController<ProgressBar>,
+    toast_overlay: adw::ToastOverlay,
+
+    path: Option<PathBuf>,
+    installation: Option<DetectedInstallation>,
+
+    /// Amount of broken files found by the integrity check,
+    /// `None` while checking or if the check failed
+    broken: Option<usize>,
+
+    /// Move game files to the `target` folder instead of using them in place
+    move_files: bool,
+    target: Option<PathBuf>,
+
+    busy: bool
+}
+
+#[derive(Debug, Clone)]
+pub enum ImportInstallationAppMsg {
+    SelectFolder,
+    SelectTarget,
+    SetMoveFiles(bool),
+
+    SetInstallation(Option<DetectedInstallation>),
+    SetBroken(Option<usize>),
+    SetBusy(bool),
+
+    Import,
+
+    Toast {
+        title: String,
+        description: Option<String>
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ImportInstallationAppOutput {
+    /// Config was updated to use the imported installation located in the `path` folder
+    Imported {
+        installation: DetectedInstallation,
+        path: PathBuf
+    }
+}
+
+#[relm4::component(async, pub)]
+impl SimpleAsyncComponent for ImportInstallationApp {
+    type Init = ();
+    type Input = ImportInstallationAppMsg;
+    type Output = ImportInstallationAppOutput;
+
+    view! {
+        adw::Window {
+            set_default_size: (780, 560),
+            set_modal: true,
+            set_hide_on_close: true,
+
+            #[watch]
+            set_title: Some(&tr!("import-installation")),
+
+            #[local_ref]
+            toast_overlay -> adw::ToastOverlay {
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+
+                    adw::HeaderBar {
+                        add_css_class: "flat"
+                    },
+
+                    adw::PreferencesPage {
+                        add = &adw::PreferencesGroup {
+                            #[watch]
+                            set_description: Some(&tr!("import-installation-description")),
+
+                            #[watch]
+                            set_sensitive: !model.busy,
+
+                            adw::ActionRow {
+                                #[watch]
+                                set_title: &tr!("game-folder"),
+
+                                #[watch]
+                                set_subtitle: &model.path.as_ref()
+                                    .map(|path| path.to_string_lossy().to_string())
+                                    .unwrap_or_else(|| tr!("not-selected")),
+
+                                set_activatable: true,
+
+                                connect_activated => ImportInstallationAppMsg::SelectFolder,
+
+                                add_prefix = &gtk::Image {
+                                    set_icon_name: Some("folder-symbolic")
+                                }
+                            }
+                        },
+
+                        add = &adw::PreferencesGroup {
+                            #[watch]
+                            set_title: &tr!("detected-installation"),
+
+                            #[watch]
+                            set_visible: model.installation.is_some(),
+
+                            adw::ActionRow {
+                                #[watch]
+                                set_title: &tr!("game-edition"),
+
+                                #[watch]
+                                set_subtitle: &match model.installation.as_ref().map(|installation| installation.edition) {
+                                    Some(GameEdition::China) => tr!("china"),
+                                    _ => tr!("global")
+                                }
+                            },
+
+                            adw::ActionRow {
+                                #[watch]
+                                set_title: &tr!("game-version"),
+
+                                #[watch]
+                                set_subtitle: &model.installation.as_ref()
+                                    .map(|installation| installation.version.to_string())
+                                    .unwrap_or_default()
+                            },
+
+                            adw::ActionRow {
+                                #[watch]
+                                set_title: &tr!("game-voiceovers"),
+
+                                #[watch]
+                                set_subtitle: &match &model.installation {
+                                    Some(installation) if !installation.voices.is_empty() => installation.voices.iter()
+                                        .map(|locale| tr!(&locale.to_name().to_ascii_lowercase()))
+                                        .collect::<Vec<_>>()
+                                        .join(", "),
+
+                                    _ => tr!("none")
+                                }
+                            },
+
+                            adw::ActionRow {
+                                #[watch]
+                                set_title: &tr!("import-integrity-check"),
+
+                                #[watch]
+                                set_subtitle: &match model.broken {
+                                    _ if model.busy => tr!("import-integrity-checking"),
+
+                                    Some(0) => tr!("import-integrity-passed"),
+
+                                    Some(broken) => tr!("import-integrity-broken", {
+                                        "count" = broken
+                                    }),
+
+                                    None => tr!("import-integrity-unavailable")
+                                }
+                            }
+                        },
+
+                        add = &adw::PreferencesGroup {
+                            #[watch]
+                            set_visible: model.installation.is_some(),
+
+                            #[watch]
+                            set_sensitive: !model.busy,
+
+                            adw::ActionRow {
+                                #[watch]
+                                set_title: &tr!("import-move-files"),
+
+                                #[watch]
+                                set_subtitle: &tr!("import-move-files-description"),
+
+                                add_suffix = &gtk::Switch {
+                                    set_valign: gtk::Align::Center,
+
+                                    connect_state_notify[sender] => move |switch| {
+                                        sender.input(ImportInstallationAppMsg::SetMoveFiles(switch.state()));
+                                    }
+                                }
+                            },
+
+                            adw::ActionRow {
+                                #[watch]
+                                set_title: &tr!("import-target-folder"),
+
+                                #[watch]
+                                set_subtitle: &model.target.as_ref()
+                                    .map(|path| path.to_string_lossy().to_string())
+                                    .unwrap_or_else(|| tr!("not-selected")),
+
+                                #[watch]
+                                set_visible: model.move_files,
+
+                                set_activatable: true,
+
+                                connect_activated => ImportInstallationAppMsg::SelectTarget,
+
+                                add_prefix = &gtk::Image {
+                                    set_icon_name: Some("folder-symbolic")
+                                }
+                            }
+                        },
+
+                        add = &adw::PreferencesGroup {
+                            set_valign: gtk::Align::Center,
+                            set_vexpand: true,
+
+                            #[watch]
+                            set_visible: !model.busy,
+
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Horizontal,
+                                set_halign: gtk::Align::Center,
+
+                                gtk::Button {
+                                    #[watch]
+                                    set_label: &tr!("import"),
+
+                                    set_css_classes: &["suggested-action", "pill"],
+
+                                    #[watch]
+                                    set_sensitive: model.installation.is_some() && (!model.move_files || model.target.is_some()),
+
+                                    connect_clicked => ImportInstallationAppMsg::Import
+                                }
+                            }
+                        },
+
+                        add = &adw::PreferencesGroup {
+                            set_valign: gtk::Align::Center,
+                            set_vexpand: true,
+
+                            #[watch]
+                            set_visible: model.busy,
+
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Horizontal,
+                                set_halign: gtk::Align::Center,
+
+                                append = model.progress_bar.widget(),
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        tracing::info!("Initializing installation import window");
+
+        let model = Self {
+            progress_bar: ProgressBar::builder()
+                .launch(ProgressBarInit {
+                    caption: None,
+                    display_progress: true,
+                    display_fraction: false,
+                    visible: true
+                })
+                .detach(),
+
+            toast_overlay: adw::ToastOverlay::new(),
+
+            path: None,
+            installation: None,
+            broken: None,
+
+            move_files: false,
+            target: None,
+
+            busy: false
+        };
+
+        // Set progress bar width
+        model.progress_bar.widget().set_width_request(400);
+
+        let toast_overlay = &model.toast_overlay;
+
+        let widgets = view_output!();
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            ImportInstallationAppMsg::SelectFolder => {
+                let Some(folder) = rfd::AsyncFileDialog::new().pick_folder().await else {
+                    return;
+                };
+
+                let path = folder.path().to_path_buf();
+
+                self.path = Some(path.clone());
+                self.installation = None;
+                self.broken = None;
+                self.busy = true;
+
+                let progress_bar_input = self.progress_bar.sender().clone();
+
+                progress_bar_input.send(ProgressBarMsg::UpdateCaption(Some(tr!("import-integrity-checking")))).unwrap();
+                progress_bar_input.send(ProgressBarMsg::UpdateProgress(0, 1)).unwrap();
+
+                std::thread::spawn(move || {
+                    match detect_installation(&path) {
+                        Ok(installation) => {
+                            sender.input(ImportInstallationAppMsg::SetInstallation(Some(installation.clone())));
+
+                            let result = verify_installation(&installation, |update| {
+                                if let ImportUpdate::Verifying { processed, total } = update {
+                                    progress_bar_input.send(ProgressBarMsg::UpdateProgress(processed, total)).unwrap();
+                                }
+                            });
+
+                            match result {
+                                Ok(broken) => sender.input(ImportInstallationAppMsg::SetBroken(Some(broken))),
+
+                                Err(err) => {
+                                    tracing::error!("Failed to check imported installation: {err}");
+
+                                    sender.input(ImportInstallationAppMsg::SetBroken(None));
+                                }
+                            }
+                        }
+
+                        Err(err) => {
+                            tracing::error!("Failed to detect game installation: {err}");
+
+                            sender.input(ImportInstallationAppMsg::Toast {
+                                title: tr!("installation-detection-failed"),
+                                description: Some(err.to_string())
+                            });
+                        }
+                    }
+
+                    sender.input(ImportInstallationAppMsg::SetBusy(false));
+                });
+            }
+
+            ImportInstallationAppMsg::SelectTarget => {
+                if let Some(folder) = rfd::AsyncFileDialog::new().pick_folder().await {
+                    self.target = Some(folder.path().to_path_buf());
+                }
+            }
+
+            ImportInstallationAppMsg::SetMoveFiles(move_files) => self.move_files = move_files,
+
+            ImportInstallationAppMsg::SetInstallation(installation) => self.installation = installation,
+            ImportInstallationAppMsg::SetBroken(broken) => self.broken = broken,
+            ImportInstallationAppMsg::SetBusy(busy) => self.busy = busy,
+
+            ImportInstallationAppMsg::Import => {
+                let Some(installation) = self.installation.clone() else {
+                    return;
+                };
+
+                let target = self.target.clone().filter(|_| self.move_files);
+
+                self.busy = true;
+
+                let progress_bar_input = self.progress_bar.sender().clone();
+
+                progress_bar_input.send(ProgressBarMsg::UpdateCaption(Some(tr!("migrating-folders")))).unwrap();
+                progress_bar_input.send(ProgressBarMsg::UpdateProgress(0, 1)).unwrap();
+
+                std::thread::spawn(move || {
+                    let mut failed = Vec::new();
+
+                    let result = import_installation(&installation, target, |update| {
+                        match update {
+                            ImportUpdate::Moving { moved, total } => {
+                                progress_bar_input.send(ProgressBarMsg::UpdateProgress(moved, total)).unwrap();
+                            }
+
+                            ImportUpdate::FileFailed { path, error } => failed.push(format!("{}: {error}", path.to_string_lossy())),
+
+                            _ => ()
+                        }
+                    });
+
+                    let result = result.and_then(|path| {
+                        let mut config = Config::get()?;
+
+                        actions::import::apply_installation(&mut config, &installation, &path);
+
+                        crate::config_history::update_raw(config)?;
+
+                        Ok(path)
+                    });
+
+                    match result {
+                        Ok(path) => sender.output(ImportInstallationAppOutput::Imported { installation, path }).unwrap(),
+
+                        Err(err) => {
+                            tracing::error!("Failed to import game installation: {err}");
+
+                            let mut description = err.to_string();
+
+                            if !failed.is_empty() {
+                                description = format!("{description}\n\n{}", failed.join("\n"));
+                            }
+
+                            sender.input(ImportInstallationAppMsg::Toast {
+                                title: tr!("installation-import-failed"),
+                                description: Some(description)
+                            });
+                        }
+                    }
+
+                    sender.input(ImportInstallationAppMsg::SetBusy(false));
+                });
+            }
+
+            ImportInstallationAppMsg::Toast { title, description } => {
+                let toast = adw::Toast::new(&title);
+
+                toast.set_timeout(4);
+
+                if let Some(description) = description {
+                    toast.set_button_label(Some(&tr!("details")));
+
+                    let window = self.toast_overlay.root().and_downcast::<gtk::Window>();
+
+                    let dialog = adw::MessageDialog::new(window.as_ref(), Some(&title), Some(&description));
+
+                    dialog.add_response("close", &tr!("close", { "form" = "noun" }));
+                    dialog.add_response("save", &tr!("save"));
+
+                    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+
+                    dialog.connect_response(Some("save"), |_, _| {
+                        if let Err(err) = open::that(crate::DEBUG_FILE.as_os_str()) {
+                            tracing::error!("Failed to open debug file: {err}");
+                        }
+                    });
+
+                    toast.connect_button_clicked(move |_| {
+                        dialog.present();
+                    });
+                }
+
+                self.toast_overlay.add_toast(toast);
+            }
+        }
+    }
+}
@@ -5,30 +5,36 @@ use relm4::{
 
 use gtk::glib::clone;
 
+use anime_launcher_sdk::anime_game_core::installer::downloader::DownloadingError;
+
 use crate::*;
 use crate::ui::components::*;
+use crate::i18n::{format_size, SizeUnits};
 
 use super::{App, AppMsg};
 
-pub fn download_diff(sender: ComponentSender<App>, progress_bar_input: Sender<ProgressBarMsg>, mut diff: VersionDiff) {
+pub fn download_diff(sender: ComponentSender<App>, progress_bar_input: Sender<ProgressBarMsg>, diff: VersionDiff, config: Schema) {
     sender.input(AppMsg::SetDownloading(true));
 
     std::thread::spawn(move || {
-        let config = Config::get().unwrap();
-        let game_path = config.game.path.for_edition(config.launcher.edition).to_path_buf();
-
-        if let Some(temp) = config.launcher.temp {
-            diff = diff.with_temp_folder(temp);
-        }
-
-        let result = diff.install_to(game_path, clone!(@strong sender => move |state| {
+        let result = actions::download_diff::download_diff(&config, diff, clone!(@strong sender => move |state| {
             match &state {
                 DiffUpdate::InstallerUpdate(InstallerUpdate::DownloadingError(err)) => {
                     tracing::error!("Downloading failed: {err}");
 
+                    let description = match err {
+                        DownloadingError::NoSpaceAvailable(path, required, available) => tr!("not-enough-free-space", {
+                            "path" = path.to_string_lossy().to_string(),
+                            "required" = format_size(*required, SizeUnits::Binary),
+                            "available" = format_size(*available, SizeUnits::Binary)
+                        }),
+
+                        _ => err.to_string()
+                    };
+
                     sender.input(AppMsg::Toast {
                         title: tr!("downloading-failed"),
-                        description: Some(err.to_string())
+                        description: Some(description)
                     });
                 }
 
@@ -8,11 +8,14 @@ use gtk::glib::clone;
 use anime_launcher_sdk::components::wine;
 
 use crate::*;
+use crate::actions::storage_plan::*;
+use crate::actions::cleanup::ActivePath;
 use crate::ui::components::*;
 
 use super::{App, AppMsg};
 
-pub fn download_wine(sender: ComponentSender<App>, progress_bar_input: Sender<ProgressBarMsg>) {
+/// Select downloaded wine version, or plan downloading of a new one
+pub fn download_wine(sender: ComponentSender<App>) {
     let mut config = Config::get().unwrap();
 
     match wine::get_downloaded(&CONFIG.components.path, &config.game.wine.builds) {
@@ -43,67 +46,78 @@ pub fn download_wine(sender: ComponentSender<App>, progress_bar_input: Sender<Pr
                     None => latest
                 };
 
-                // Download wine version
-                match Installer::new(wine.uri) {
-                    Ok(mut installer) => {
-                        if let Some(temp_folder) = &config.launcher.temp {
-                            installer.temp_folder = temp_folder.to_path_buf();
-                        }
-
-                        sender.input(AppMsg::SetDownloading(true));
+                // Storage plan requests components sizes so it's calculated outside of the GTK thread
+                std::thread::spawn(move || {
+                    let plan = StoragePlan::for_components(&config, [(StorageUsage::WineBuilds, wine.uri.as_str())]);
 
-                        std::thread::spawn(clone!(@strong sender => move || {
-                            installer.install(&config.game.wine.builds, clone!(@strong sender => move |state| {
-                                match &state {
-                                    InstallerUpdate::DownloadingError(err) => {
-                                        tracing::error!("Downloading failed: {err}");
+                    sender.input(AppMsg::ShowWinePlan { wine, plan });
+                });
+            }
+        }
 
-                                        sender.input(AppMsg::Toast {
-                                            title: tr!("downloading-failed"),
-                                            description: Some(err.to_string())
-                                        });
-                                    }
+        Err(err) => sender.input(AppMsg::Toast {
+            title: tr!("downloaded-wine-list-failed"),
+            description: Some(err.to_string())
+        })
+    }
+}
 
-                                    InstallerUpdate::UnpackingError(err) => {
-                                        tracing::error!("Unpacking failed: {err}");
+/// Download and select the given wine version
+pub fn install_wine(sender: ComponentSender<App>, progress_bar_input: Sender<ProgressBarMsg>, mut config: Schema, wine: wine::Version) {
+    std::thread::spawn(move || {
+        match Installer::new(&wine.uri) {
+            Ok(mut installer) => {
+                if let Some(temp_folder) = &config.launcher.temp {
+                    installer.temp_folder = temp_folder.to_path_buf();
+                }
 
-                                        sender.input(AppMsg::Toast {
-                                            title: tr!("unpacking-failed"),
-                                            description: Some(err.clone())
-                                        });
-                                    }
+                sender.input(AppMsg::SetDownloading(true));
 
-                                    _ => ()
-                                }
+                let _active = ActivePath::component(&wine.uri, &installer.temp_folder, config.game.wine.builds.join(&wine.name));
 
-                                #[allow(unused_must_use)] {
-                                    progress_bar_input.send(ProgressBarMsg::UpdateFromState(DiffUpdate::InstallerUpdate(state)));
-                                }
-                            }));
+                installer.install(&config.game.wine.builds, clone!(@strong sender => move |state| {
+                    match &state {
+                        InstallerUpdate::DownloadingError(err) => {
+                            tracing::error!("Downloading failed: {err}");
 
-                            config.game.wine.selected = Some(wine.name.clone());
+                            sender.input(AppMsg::Toast {
+                                title: tr!("downloading-failed"),
+                                description: Some(err.to_string())
+                            });
+                        }
 
-                            Config::update(config);
+                        InstallerUpdate::UnpackingError(err) => {
+                            tracing::error!("Unpacking failed: {err}");
 
-                            sender.input(AppMsg::SetDownloading(false));
-                            sender.input(AppMsg::UpdateLauncherState {
-                                perform_on_download_needed: false,
-                                show_status_page: true
+                            sender.input(AppMsg::Toast {
+                                title: tr!("unpacking-failed"),
+                                description: Some(err.clone())
                             });
-                        }));
+                        }
+
+                        _ => ()
                     }
 
-                    Err(err) => sender.input(AppMsg::Toast {
-                        title: tr!("wine-install-failed"),
-                        description: Some(err.to_string())
-                    })
-                }
+                    #[allow(unused_must_use)] {
+                        progress_bar_input.send(ProgressBarMsg::UpdateFromState(DiffUpdate::InstallerUpdate(state)));
+                    }
+                }));
+
+                config.game.wine.selected = Some(wine.name.clone());
+
+                Config::update(config);
+
+                sender.input(AppMsg::SetDownloading(false));
+                sender.input(AppMsg::UpdateLauncherState {
+                    perform_on_download_needed: false,
+                    show_status_page: true
+                });
             }
-        }
 
-        Err(err) => sender.input(AppMsg::Toast {
-            title: tr!("downloaded-wine-list-failed"),
-            description: Some(err.to_string())
-        })
-    }
+            Err(err) => sender.input(AppMsg::Toast {
+                title: tr!("wine-install-failed"),
+                description: Some(err.to_string())
+            })
+        }
+    });
 }
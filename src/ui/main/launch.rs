@@ -7,9 +7,7 @@ use crate::*;
 
 use super::{App, AppMsg};
 
-pub fn launch(sender: ComponentSender<App>) {
-    let config = Config::get().unwrap();
-
+pub fn launch(sender: ComponentSender<App>, config: Schema) {
     match config.launcher.behavior {
         // Disable launch button and show kill game button if behavior set to "Nothing" to prevent sussy actions
         LauncherBehavior::Nothing => {
@@ -21,8 +19,10 @@ pub fn launch(sender: ComponentSender<App>) {
         LauncherBehavior::Hide | LauncherBehavior::Close => sender.input(AppMsg::HideWindow)
     }
 
+    sender.input(AppMsg::SetGameRunning(true));
+
     std::thread::spawn(move || {
-        if let Err(err) = anime_launcher_sdk::genshin::game::run() {
+        if let Err(err) = actions::launch::launch(&config) {
             tracing::error!("Failed to launch game: {err}");
 
             sender.input(AppMsg::Toast {
@@ -31,6 +31,8 @@ pub fn launch(sender: ComponentSender<App>) {
             });
         }
 
+        sender.input(AppMsg::SetGameRunning(false));
+
         match config.launcher.behavior {
             // Enable launch button and hide kill game button if behavior set to "Nothing" after the game has closed
             LauncherBehavior::Nothing => {
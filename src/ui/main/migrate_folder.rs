@@ -1,25 +1,115 @@
 use std::path::PathBuf;
 
-use relm4::prelude::*;
+use relm4::{
+    prelude::*,
+    Sender
+};
 
 use crate::*;
+use crate::ui::components::*;
+use crate::actions::migrate::MigrateUpdate;
+use crate::move_files::{NotEnoughSpace, TransferFailed};
+use crate::i18n::{format_size, SizeUnits};
 
 use super::{App, AppMsg};
 
-pub fn migrate_folder(sender: ComponentSender<App>, from: PathBuf, to: PathBuf, cleanup_folder: Option<PathBuf>) {
+#[allow(unused_must_use)]
+pub fn migrate_folder(sender: ComponentSender<App>, progress_bar_input: Sender<ProgressBarMsg>, from: PathBuf, to: PathBuf, cleanup_folder: Option<PathBuf>) {
     sender.input(AppMsg::DisableButtons(true));
+    sender.input(AppMsg::SetDownloading(true));
+
+    progress_bar_input.send(ProgressBarMsg::UpdateCaption(Some(tr!("migrating-folders"))));
 
     std::thread::spawn(move || {
-        move_files::move_files(&from, &to).expect("Failed to perform migration");
+        let mut failed = Vec::new();
+
+        let result = actions::migrate::migrate_folder(&from, &to, cleanup_folder, |update| {
+            match update {
+                MigrateUpdate::Moving { moved, total } => {
+                    progress_bar_input.send(ProgressBarMsg::UpdateProgress(moved, total));
+                }
+
+                MigrateUpdate::FileFailed { path, error } => failed.push(format!("{}: {error}", path.to_string_lossy())),
+
+                _ => ()
+            }
+        });
+
+        if let Err(err) = result {
+            tracing::error!("Failed to migrate folder: {err}");
 
-        if let Some(cleanup_folder) = cleanup_folder {
-            std::fs::remove_dir_all(cleanup_folder).expect("Failed to remove cleanup folder");
+            sender.input(AppMsg::Toast {
+                title: tr!("folder-migration-failed"),
+                description: Some(get_error_description(&err, &failed))
+            });
         }
 
-        sender.input(AppMsg::DisableButtons(false));
-        sender.input(AppMsg::UpdateLauncherState {
-            perform_on_download_needed: false,
-            show_status_page: true
+        finish(sender);
+    });
+}
+
+#[allow(unused_must_use)]
+pub fn rollback_migration(sender: ComponentSender<App>, progress_bar_input: Sender<ProgressBarMsg>) {
+    sender.input(AppMsg::DisableButtons(true));
+    sender.input(AppMsg::SetDownloading(true));
+
+    progress_bar_input.send(ProgressBarMsg::UpdateCaption(Some(tr!("rolling-back-migration"))));
+
+    std::thread::spawn(move || {
+        let mut failed = Vec::new();
+
+        let result = actions::migrate::rollback_migration(|update| {
+            match update {
+                MigrateUpdate::RollingBack { moved, total } => {
+                    progress_bar_input.send(ProgressBarMsg::UpdateProgress(moved, total));
+                }
+
+                MigrateUpdate::FileFailed { path, error } => failed.push(format!("{}: {error}", path.to_string_lossy())),
+
+                _ => ()
+            }
         });
+
+        if let Err(err) = result {
+            tracing::error!("Failed to roll back folder migration: {err}");
+
+            sender.input(AppMsg::Toast {
+                title: tr!("folder-migration-rollback-failed"),
+                description: Some(get_error_description(&err, &failed))
+            });
+        }
+
+        finish(sender);
+    });
+}
+
+fn finish(sender: ComponentSender<App>) {
+    sender.input(AppMsg::SetDownloading(false));
+    sender.input(AppMsg::DisableButtons(false));
+
+    sender.input(AppMsg::UpdateLauncherState {
+        perform_on_download_needed: false,
+        show_status_page: true
     });
 }
+
+/// `failed` contains descriptions of the files which weren't moved
+pub fn get_error_description(err: &anyhow::Error, failed: &[String]) -> String {
+    if let Some(err) = err.downcast_ref::<NotEnoughSpace>() {
+        return tr!("not-enough-free-space", {
+            "path" = err.path.to_string_lossy().to_string(),
+            "required" = format_size(err.required, SizeUnits::Binary),
+            "available" = format_size(err.available, SizeUnits::Binary)
+        });
+    }
+
+    if let Some(err) = err.downcast_ref::<TransferFailed>() {
+        let description = tr!("files-not-moved", {
+            "count" = err.failures
+        });
+
+        return format!("{description}\n\n{}", failed.join("\n"));
+    }
+
+    err.to_string()
+}
@@ -13,7 +13,7 @@ mod repair_game;
 mod download_wine;
 mod create_prefix;
 mod download_diff;
-mod migrate_folder;
+pub mod migrate_folder;
 mod disable_telemetry;
 mod launch;
 
@@ -24,11 +24,18 @@ use anime_launcher_sdk::genshin::config::Config;
 
 use anime_launcher_sdk::genshin::config::schema::launcher::LauncherStyle;
 
+use anime_launcher_sdk::sessions::SessionsExt;
+use anime_launcher_sdk::genshin::sessions::Sessions;
+
 use anime_launcher_sdk::genshin::states::*;
 use anime_launcher_sdk::genshin::consts::*;
 
 use crate::*;
 use crate::ui::components::*;
+use crate::i18n::{format_size, SizeUnits};
+use crate::background::{BackgroundSettings, BackgroundSource, BackgroundRotation};
+use crate::actions::storage_plan::{StoragePlan, StorageStatus};
+use crate::actions::cleanup::{CleanupSettings, ActivePath};
 
 use super::preferences::main::*;
 use super::about::*;
@@ -43,23 +50,37 @@ relm4::new_stateless_action!(WishUrl, WindowActionGroup, "wish_url");
 
 relm4::new_stateless_action!(About, WindowActionGroup, "about");
 
-pub static mut MAIN_WINDOW: Option<adw::ApplicationWindow> = None;
-pub static mut PREFERENCES_WINDOW: Option<AsyncController<PreferencesApp>> = None;
-pub static mut ABOUT_DIALOG: Option<Controller<AboutDialog>> = None;
-
 pub struct App {
+    ctx: AppContext,
+
+    preferences_window: AsyncController<PreferencesApp>,
+
     progress_bar: AsyncController<ProgressBar>,
+    news_panel: Controller<NewsPanel>,
 
     toast_overlay: adw::ToastOverlay,
 
+    sessions_dropdown: gtk::DropDown,
+    sessions_dropdown_handler: Option<gtk::glib::SignalHandlerId>,
+
+    profiles_dropdown: gtk::DropDown,
+    profiles_dropdown_handler: Option<gtk::glib::SignalHandlerId>,
+
     loading: Option<Option<String>>,
     style: LauncherStyle,
     state: Option<LauncherState>,
 
+    /// Names of the available game sessions
+    sessions: Vec<String>,
+
+    /// Names of the available installation profiles
+    profiles: Vec<String>,
+
     downloading: bool,
     disabled_buttons: bool,
     kill_game_button: bool,
-    disabled_kill_game_button: bool
+    disabled_kill_game_button: bool,
+    game_running: bool
 }
 
 #[derive(Debug)]
@@ -87,11 +108,79 @@ pub enum AppMsg {
     DisableButtons(bool),
     SetKillGameButton(bool),
     DisableKillGameButton(bool),
+    SetGameRunning(bool),
+
+    /// Supposed to be called automatically when the app's initialization is finished
+    SetReady,
+
+    /// Reload game sessions list from the sessions file
+    UpdateSessions,
+
+    /// Switch current game session
+    SetSession(String),
+
+    /// Reload installation profiles list from the profiles file
+    UpdateProfiles,
+
+    /// Switch current installation profile and reload the launcher's state
+    SetProfile(String),
+
+    /// Find game diff of the current installation and send it to the preferences
+    UpdateGameDiff,
+
+    /// Re-initialize game diff, background, launcher state and preferences
+    /// after the game edition or installation profile was changed
+    ReloadInstallation,
+
+    /// Prepare background picture in background thread and reload it then.
+    /// Switches the slideshow to the next picture if `next` is set
+    UpdateBackground {
+        next: bool
+    },
+
+    /// Reload background picture from the background file
+    ReloadBackground,
+
+    /// Fetch launcher news in background thread and show them then
+    UpdateNews,
+    SetNews(crate::news::News),
+
+    /// Offer to resume or roll back the folder migration if it was interrupted
+    CheckInterruptedMigration,
+    ResumeMigration,
+    RollbackMigration,
 
     OpenPreferences,
     RepairGame,
 
+    /// Show storage plan of the pre-download and start it then
     PredownloadUpdate,
+    ShowPredownloadPlan(StoragePlan),
+    StartPredownload,
+
+    /// Show storage plan of the game or voiceover diff and download it then
+    ///
+    /// Chained downloads are started without confirmation
+    /// unless there's not enough free space
+    PlanDiffDownload {
+        diff: VersionDiff,
+        confirm: bool
+    },
+
+    /// Storage plan of the diff was calculated
+    ShowDiffPlan {
+        diff: VersionDiff,
+        plan: StoragePlan,
+        confirm: bool
+    },
+
+    /// Storage plan of the wine build was calculated. It's shown before
+    /// downloading unless there's enough free space
+    ShowWinePlan {
+        wine: anime_launcher_sdk::components::wine::Version,
+        plan: StoragePlan
+    },
+
     PerformAction,
 
     HideWindow,
@@ -100,12 +189,15 @@ pub enum AppMsg {
     Toast {
         title: String,
         description: Option<String>
-    }
+    },
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
 }
 
 #[relm4::component(pub)]
 impl SimpleComponent for App {
-    type Init = ();
+    type Init = AppContext;
     type Input = AppMsg;
     type Output = ();
 
@@ -192,7 +284,9 @@ impl SimpleComponent for App {
                     },
 
                     adw::StatusPage {
+                        #[watch]
                         set_title: &tr!("loading-data"),
+
                         set_icon_name: Some(APP_ID),
                         set_vexpand: true,
 
@@ -229,6 +323,30 @@ impl SimpleComponent for App {
                             }
                         },
 
+                        add = &adw::PreferencesGroup {
+                            #[watch]
+                            set_halign: match model.style {
+                                LauncherStyle::Modern => gtk::Align::Fill,
+                                LauncherStyle::Classic => gtk::Align::Start
+                            },
+
+                            #[watch]
+                            set_valign: match model.style {
+                                LauncherStyle::Modern => gtk::Align::Start,
+                                LauncherStyle::Classic => gtk::Align::End
+                            },
+
+                            #[watch]
+                            set_width_request: match model.style {
+                                LauncherStyle::Modern => -1,
+                                LauncherStyle::Classic => 360
+                            },
+
+                            set_margin_top: 32,
+
+                            add = model.news_panel.widget(),
+                        },
+
                         add = &adw::PreferencesGroup {
                             #[watch]
                             set_valign: match model.style {
@@ -313,7 +431,7 @@ impl SimpleComponent for App {
                                                         size += voice.downloaded_size().unwrap_or(0);
                                                     }
 
-                                                    prettify_bytes(size)
+                                                    format_size(size, SizeUnits::Binary)
                                                 }
 
                                                 _ => String::from("?")
@@ -390,6 +508,42 @@ impl SimpleComponent for App {
                                     }
                                 },
 
+                                adw::Bin {
+                                    set_css_classes: &["background", "round-bin"],
+
+                                    #[watch]
+                                    set_visible: !model.profiles.is_empty(),
+
+                                    #[local_ref]
+                                    profiles_dropdown -> gtk::DropDown {
+                                        #[watch]
+                                        set_tooltip_text: Some(&tr!("installation-profiles")),
+
+                                        set_valign: gtk::Align::Center,
+
+                                        #[watch]
+                                        set_sensitive: !model.disabled_buttons && !model.game_running && !model.downloading
+                                    }
+                                },
+
+                                adw::Bin {
+                                    set_css_classes: &["background", "round-bin"],
+
+                                    #[watch]
+                                    set_visible: !model.sessions.is_empty(),
+
+                                    #[local_ref]
+                                    sessions_dropdown -> gtk::DropDown {
+                                        #[watch]
+                                        set_tooltip_text: Some(&tr!("game-sessions")),
+
+                                        set_valign: gtk::Align::Center,
+
+                                        #[watch]
+                                        set_sensitive: !model.disabled_buttons && !model.game_running
+                                    }
+                                },
+
                                 adw::Bin {
                                     set_css_classes: &["background", "round-bin"],
 
@@ -502,6 +656,7 @@ impl SimpleComponent for App {
                                     gtk::Button {
                                         adw::ButtonContent {
                                             set_icon_name: "violence-symbolic", // window-close-symbolic
+                                            #[watch]
                                             set_label: &tr!("kill-game-process")
                                         },
 
@@ -613,7 +768,7 @@ impl SimpleComponent for App {
             },
 
             connect_close_request[sender] => move |_| {
-                if let Err(err) = Config::flush() {
+                if let Err(err) = crate::config_history::flush() {
                     sender.input(AppMsg::Toast {
                         title: tr!("config-update-error"),
                         description: Some(err.to_string())
@@ -626,13 +781,21 @@ impl SimpleComponent for App {
     }
 
     fn init(
-        _init: Self::Init,
+        ctx: Self::Init,
         root: &Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         tracing::info!("Initializing main window");
 
-        let model = App {
+        ctx.set_main_window(root.clone());
+
+        let mut model = App {
+            ctx: ctx.clone(),
+
+            preferences_window: PreferencesApp::builder()
+                .launch(ctx.clone())
+                .forward(sender.input_sender(), std::convert::identity),
+
             progress_bar: ProgressBar::builder()
                 .launch(ProgressBarInit {
                     caption: None,
@@ -642,40 +805,104 @@ impl SimpleComponent for App {
                 })
                 .detach(),
 
+            news_panel: NewsPanel::builder()
+                .launch(())
+                .detach(),
+
             toast_overlay: adw::ToastOverlay::new(),
 
+            sessions_dropdown: gtk::DropDown::from_strings(&[]),
+            sessions_dropdown_handler: None,
+
+            profiles_dropdown: gtk::DropDown::from_strings(&[]),
+            profiles_dropdown_handler: None,
+
             loading: Some(None),
             style: CONFIG.launcher.style,
             state: None,
 
+            sessions: Vec::new(),
+            profiles: Vec::new(),
+
             downloading: false,
             disabled_buttons: false,
             kill_game_button: false,
-            disabled_kill_game_button: false
+            disabled_kill_game_button: false,
+            game_running: false
         };
 
         model.progress_bar.widget().set_halign(gtk::Align::Center);
         model.progress_bar.widget().set_width_request(360);
 
         let toast_overlay = &model.toast_overlay;
+        let sessions_dropdown = &model.sessions_dropdown;
+        let profiles_dropdown = &model.profiles_dropdown;
 
         let widgets = view_output!();
 
-        let about_dialog_broker: MessageBroker<AboutDialogMsg> = MessageBroker::new();
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| AppMsg::LanguageChanged);
+
+        // Connected manually to be able to block it while the sessions list is reloaded
+        model.sessions_dropdown_handler = Some(model.sessions_dropdown.connect_selected_notify(clone!(@strong sender => move |dropdown| {
+            if let Some(name) = dropdown.selected_item().and_downcast::<gtk::StringObject>() {
+                sender.input(AppMsg::SetSession(name.string().to_string()));
+            }
+        })));
+
+        model.profiles_dropdown_handler = Some(model.profiles_dropdown.connect_selected_notify(clone!(@strong sender => move |dropdown| {
+            if let Some(name) = dropdown.selected_item().and_downcast::<gtk::StringObject>() {
+                sender.input(AppMsg::SetProfile(name.string().to_string()));
+            }
+        })));
+
+        sender.input(AppMsg::UpdateSessions);
+        sender.input(AppMsg::UpdateProfiles);
 
-        unsafe {
-            MAIN_WINDOW = Some(widgets.main_window.clone());
+        // News don't block the launcher's loading
+        sender.input(AppMsg::UpdateNews);
 
-            PREFERENCES_WINDOW = Some(PreferencesApp::builder()
-                .launch(widgets.main_window.clone().into())
-                .forward(sender.input_sender(), std::convert::identity));
+        sender.input(AppMsg::CheckInterruptedMigration);
 
-            ABOUT_DIALOG = Some(AboutDialog::builder()
-                .transient_for(widgets.main_window.clone())
-                .launch_with_broker((), &about_dialog_broker)
-                .detach());
+        // Switch background slideshow picture when its interval passes.
+        // Settings are read every minute so their changes are applied without restart
+        let slideshow_minutes = std::cell::Cell::new(0);
+
+        gtk::glib::timeout_add_seconds_local(60, clone!(@strong sender => move || {
+            let settings = BackgroundSettings::get().unwrap_or_default();
+
+            if let (BackgroundSource::Folder, BackgroundRotation::Interval(interval)) = (settings.source, settings.rotation) {
+                slideshow_minutes.set(slideshow_minutes.get() + 1);
+
+                if slideshow_minutes.get() >= interval {
+                    slideshow_minutes.set(0);
+
+                    sender.input(AppMsg::UpdateBackground { next: true });
+                }
+            }
+
+            gtk::glib::ControlFlow::Continue
+        }));
+
+        // Warn about config errors found on start
+        let config_errors = crate::config_validator::validate(&ctx.config()).into_iter()
+            .filter(|issue| issue.severity == crate::config_validator::Severity::Error)
+            .map(|issue| format!("{}: {}", issue.field, issue.description))
+            .collect::<Vec<_>>();
+
+        if !config_errors.is_empty() {
+            sender.input(AppMsg::Toast {
+                title: tr!("config-check-failed"),
+                description: Some(config_errors.join("\n"))
+            });
         }
 
+        let about_dialog_broker: MessageBroker<AboutDialogMsg> = MessageBroker::new();
+
+        let about_dialog = AboutDialog::builder()
+            .transient_for(widgets.main_window.clone())
+            .launch_with_broker((), &about_dialog_broker)
+            .detach();
+
         let mut group = RelmActionGroup::<WindowActionGroup>::new();
 
         // TODO: reduce code somehow
@@ -731,10 +958,10 @@ impl SimpleComponent for App {
             }
         })));
 
-        group.add_action::<WishUrl>(RelmAction::new_stateless(clone!(@strong sender => move |_| {
-            std::thread::spawn(clone!(@strong sender => move || {
-                let config = Config::get().unwrap_or_else(|_| CONFIG.clone());
+        group.add_action::<WishUrl>(RelmAction::new_stateless(clone!(@strong sender, @strong ctx => move |_| {
+            let config = ctx.config();
 
+            std::thread::spawn(clone!(@strong sender => move || {
                 let web_cache = config.game.path.for_edition(config.launcher.edition)
                     .join(config.launcher.edition.data_folder())
                     .join("webCaches");
@@ -810,6 +1037,9 @@ impl SimpleComponent for App {
         })));
 
         group.add_action::<About>(RelmAction::new_stateless(move |_| {
+            // Keep the dialog's controller alive as long as the action exists
+            let _ = &about_dialog;
+
             about_dialog_broker.send(AboutDialogMsg::Show);
         }));
 
@@ -817,7 +1047,9 @@ impl SimpleComponent for App {
 
         tracing::info!("Main window initialized");
 
-        let download_picture = model.style == LauncherStyle::Classic && !KEEP_BACKGROUND_FILE.exists();
+        let prepare_picture = model.style == LauncherStyle::Classic;
+
+        let game = model.ctx.game();
 
         // Initialize some heavy tasks
         std::thread::spawn(move || {
@@ -825,17 +1057,21 @@ impl SimpleComponent for App {
 
             let mut tasks = Vec::new();
 
-            // Download background picture if needed
+            // Download or switch background picture if needed
 
-            if download_picture {
+            if prepare_picture {
                 tasks.push(std::thread::spawn(clone!(@strong sender => move || {
-                    if let Err(err) = crate::background::download_background() {
-                        tracing::error!("Failed to download background picture: {err}");
+                    match crate::background::prepare(true) {
+                        Ok(()) => sender.input(AppMsg::ReloadBackground),
 
-                        sender.input(AppMsg::Toast {
-                            title: tr!("background-downloading-failed"),
-                            description: Some(err.to_string())
-                        });
+                        Err(err) => {
+                            tracing::error!("Failed to prepare background picture: {err}");
+
+                            sender.input(AppMsg::Toast {
+                                title: tr!("background-downloading-failed"),
+                                description: Some(err.to_string())
+                            });
+                        }
                     }
                 })));
             }
@@ -893,19 +1129,7 @@ impl SimpleComponent for App {
             // Update initial game version status
 
             tasks.push(std::thread::spawn(clone!(@strong sender => move || {
-                sender.input(AppMsg::SetGameDiff(match GAME.try_get_diff() {
-                    Ok(diff) => Some(diff),
-                    Err(err) => {
-                        tracing::error!("Failed to find game diff: {err}");
-
-                        sender.input(AppMsg::Toast {
-                            title: tr!("game-diff-finding-error"),
-                            description: Some(err.to_string())
-                        });
-
-                        None
-                    }
-                }));
+                sender.input(AppMsg::SetGameDiff(find_game_diff(&sender, game)));
 
                 tracing::info!("Updated game version status");
             })));
@@ -922,7 +1146,7 @@ impl SimpleComponent for App {
             });
 
             // Mark app as loaded
-            crate::READY.store(true, Ordering::Relaxed);
+            sender.input(AppMsg::SetReady);
 
             tracing::info!("App is ready");
         });
@@ -934,6 +1158,9 @@ impl SimpleComponent for App {
         tracing::debug!("Called main window event: {:?}", msg);
 
         match msg {
+            // News are fetched in the launcher language
+            AppMsg::LanguageChanged => sender.input(AppMsg::UpdateNews),
+
             // TODO: make function from this message like with toast
             AppMsg::UpdateLauncherState { perform_on_download_needed, show_status_page } => {
                 if show_status_page {
@@ -979,11 +1206,30 @@ impl SimpleComponent for App {
                 
                 if let Some(state) = state {
                     match state {
-                        LauncherState::GameUpdateAvailable(_) |
-                        LauncherState::GameNotInstalled(_) |
-                        LauncherState::VoiceUpdateAvailable(_) |
-                        LauncherState::VoiceNotInstalled(_) if perform_on_download_needed => {
-                            sender.input(AppMsg::PerformAction);
+                        LauncherState::GameUpdateAvailable(diff) |
+                        LauncherState::GameNotInstalled(diff) |
+                        LauncherState::VoiceUpdateAvailable(diff) |
+                        LauncherState::VoiceNotInstalled(diff) if perform_on_download_needed => {
+                            sender.input(AppMsg::PlanDiffDownload {
+                                diff,
+                                confirm: false
+                            });
+                        }
+
+                        // All the chained downloads were finished successfully
+                        LauncherState::Launch |
+                        LauncherState::PredownloadAvailable { .. } if perform_on_download_needed => {
+                            if CleanupSettings::get().map(|settings| settings.auto_cleanup).unwrap_or(false) {
+                                let config = self.ctx.config();
+
+                                std::thread::spawn(move || {
+                                    // Nothing is removed if used components can't be found
+                                    match actions::cleanup::auto_cleanup(&config) {
+                                        Ok(freed) => tracing::info!("Automatic cleanup freed {freed} bytes"),
+                                        Err(err) => tracing::error!("Automatic cleanup skipped: {err}")
+                                    }
+                                });
+                            }
                         }
 
                         _ => ()
@@ -992,8 +1238,8 @@ impl SimpleComponent for App {
             }
 
             #[allow(unused_must_use)]
-            AppMsg::SetGameDiff(diff) => unsafe {
-                PREFERENCES_WINDOW.as_ref().unwrap_unchecked().sender().send(PreferencesAppMsg::SetGameDiff(diff));
+            AppMsg::SetGameDiff(diff) => {
+                self.preferences_window.sender().send(PreferencesAppMsg::SetGameDiff(diff));
             }
 
             AppMsg::SetLauncherState(state) => {
@@ -1006,10 +1252,18 @@ impl SimpleComponent for App {
 
             AppMsg::SetLauncherStyle(style) => {
                 self.style = style;
+
+                if style == LauncherStyle::Classic {
+                    sender.input(AppMsg::ReloadBackground);
+                }
             }
 
+            #[allow(unused_must_use)]
             AppMsg::SetDownloading(state) => {
                 self.downloading = state;
+
+                // Voiceovers queue changes the game files too
+                self.preferences_window.sender().send(PreferencesAppMsg::SetDownloading(state));
             }
 
             AppMsg::DisableButtons(state) => {
@@ -1024,14 +1278,257 @@ impl SimpleComponent for App {
                 self.disabled_kill_game_button = state;
             }
 
-            AppMsg::OpenPreferences => unsafe {
-                PREFERENCES_WINDOW.as_ref().unwrap_unchecked().widget().present();
+            AppMsg::SetGameRunning(state) => {
+                self.game_running = state;
             }
 
-            AppMsg::RepairGame => repair_game::repair_game(sender, self.progress_bar.sender().to_owned()),
+            AppMsg::SetReady => self.ctx.set_ready(true),
+
+            AppMsg::UpdateSessions => {
+                let mut sessions = Sessions::list()
+                    .map(|sessions| sessions.into_keys().collect::<Vec<_>>())
+                    .unwrap_or_default();
+
+                sessions.sort();
+
+                let current = Sessions::get_current().unwrap_or_default();
+
+                if let Some(handler) = &self.sessions_dropdown_handler {
+                    self.sessions_dropdown.block_signal(handler);
+                }
+
+                self.sessions_dropdown.set_model(Some(&gtk::StringList::new(&sessions.iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>())));
+
+                if let Some(position) = sessions.iter().position(|name| Some(name) == current.as_ref()) {
+                    self.sessions_dropdown.set_selected(position as u32);
+                }
+
+                if let Some(handler) = &self.sessions_dropdown_handler {
+                    self.sessions_dropdown.unblock_signal(handler);
+                }
+
+                self.sessions = sessions;
+            }
+
+            #[allow(unused_must_use)]
+            AppMsg::SetSession(name) => {
+                if Sessions::get_current().unwrap_or_default().as_ref() == Some(&name) {
+                    return;
+                }
+
+                let config = match Config::get() {
+                    Ok(config) => config,
+                    Err(err) => {
+                        self.toast(tr!("config-file-opening-error"), Some(err.to_string()));
+
+                        return;
+                    }
+                };
+
+                if let Err(err) = Sessions::set_current(name.clone()) {
+                    tracing::error!("Failed to set current session: {err}");
+
+                    self.toast(tr!("game-session-set-current-failed"), Some(err.to_string()));
+                }
+
+                else if let Err(err) = Sessions::apply(name.clone(), config.get_wine_prefix_path()) {
+                    tracing::error!("Failed to apply session: {err}");
+
+                    self.toast(tr!("game-session-apply-failed"), Some(err.to_string()));
+                }
+
+                else {
+                    tracing::info!("Switched game session to {name}");
+                }
+
+                sender.input(AppMsg::UpdateSessions);
+                sender.input(AppMsg::UpdateBackground { next: false });
+
+                self.preferences_window.sender().send(PreferencesAppMsg::ReloadSessions);
+            }
+
+            AppMsg::UpdateProfiles => {
+                let mut profiles = installation_profiles::list()
+                    .map(|profiles| profiles.into_keys().collect::<Vec<_>>())
+                    .unwrap_or_default();
+
+                profiles.sort();
+
+                let current = installation_profiles::get_current().unwrap_or_default();
+
+                if let Some(handler) = &self.profiles_dropdown_handler {
+                    self.profiles_dropdown.block_signal(handler);
+                }
+
+                self.profiles_dropdown.set_model(Some(&gtk::StringList::new(&profiles.iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>())));
+
+                if let Some(position) = profiles.iter().position(|name| Some(name) == current.as_ref()) {
+                    self.profiles_dropdown.set_selected(position as u32);
+                }
+
+                if let Some(handler) = &self.profiles_dropdown_handler {
+                    self.profiles_dropdown.unblock_signal(handler);
+                }
+
+                self.profiles = profiles;
+            }
 
             #[allow(unused_must_use)]
+            AppMsg::SetProfile(name) => {
+                if installation_profiles::get_current().unwrap_or_default().as_ref() == Some(&name) {
+                    return;
+                }
+
+                if let Err(err) = installation_profiles::switch(&name) {
+                    tracing::error!("Failed to switch installation profile: {err}");
+
+                    self.toast(tr!("installation-profile-switch-failed"), Some(err.to_string()));
+                }
+
+                else {
+                    tracing::info!("Switched installation profile to {name}");
+
+                    sender.input(AppMsg::ReloadInstallation);
+                }
+
+                sender.input(AppMsg::UpdateProfiles);
+                sender.input(AppMsg::UpdateSessions);
+
+                self.preferences_window.sender().send(PreferencesAppMsg::ReloadInstallationProfiles);
+            }
+
+            AppMsg::UpdateGameDiff => {
+                let game = self.ctx.game();
+
+                std::thread::spawn(clone!(@strong sender => move || {
+                    sender.input(AppMsg::SetGameDiff(find_game_diff(&sender, game)));
+                }));
+            }
+
+            #[allow(unused_must_use)]
+            AppMsg::ReloadInstallation => {
+                sender.input(AppMsg::UpdateGameDiff);
+
+                sender.input(AppMsg::UpdateLauncherState {
+                    perform_on_download_needed: false,
+                    show_status_page: true
+                });
+
+                sender.input(AppMsg::UpdateBackground { next: false });
+                sender.input(AppMsg::UpdateNews);
+
+                self.preferences_window.sender().send(PreferencesAppMsg::ReloadInstallation);
+            }
+
+            AppMsg::UpdateBackground { next } => {
+                if self.style == LauncherStyle::Classic {
+                    std::thread::spawn(clone!(@strong sender => move || {
+                        match crate::background::prepare(next) {
+                            Ok(()) => sender.input(AppMsg::ReloadBackground),
+
+                            Err(err) => {
+                                tracing::error!("Failed to prepare background picture: {err}");
+
+                                sender.input(AppMsg::Toast {
+                                    title: tr!("background-downloading-failed"),
+                                    description: Some(err.to_string())
+                                });
+                            }
+                        }
+                    }));
+                }
+            }
+
+            // Reloading the style makes gtk read the picture file again
+            AppMsg::ReloadBackground => crate::background::apply_css(),
+
+            AppMsg::UpdateNews => {
+                std::thread::spawn(clone!(@strong sender => move || {
+                    match crate::news::get_news() {
+                        Ok(news) => sender.input(AppMsg::SetNews(news)),
+                        Err(err) => tracing::warn!("Failed to fetch launcher news: {err}")
+                    }
+                }));
+            }
+
+            AppMsg::SetNews(news) => self.news_panel.emit(NewsPanelMsg::SetNews(news)),
+
+            AppMsg::CheckInterruptedMigration => {
+                let journal = match actions::migrate::get_interrupted_migration() {
+                    Ok(Some(journal)) => journal,
+                    Ok(None) => return,
+
+                    Err(err) => {
+                        tracing::error!("Failed to read migration journal: {err}");
+
+                        return;
+                    }
+                };
+
+                let dialog = adw::MessageDialog::new(
+                    self.ctx.main_window().as_ref(),
+                    Some(&tr!("folder-migration-interrupted")),
+                    Some(&tr!("folder-migration-interrupted-description", {
+                        "from" = journal.from.to_string_lossy().to_string(),
+                        "to" = journal.to.to_string_lossy().to_string()
+                    }))
+                );
+
+                dialog.add_response("rollback", &tr!("roll-back"));
+                dialog.add_response("resume", &tr!("resume"));
+
+                dialog.set_response_appearance("resume", adw::ResponseAppearance::Suggested);
+
+                dialog.connect_response(None, clone!(@strong sender => move |_, response| {
+                    match response {
+                        "resume" => sender.input(AppMsg::ResumeMigration),
+                        "rollback" => sender.input(AppMsg::RollbackMigration),
+
+                        _ => ()
+                    }
+                }));
+
+                dialog.present();
+            }
+
+            AppMsg::ResumeMigration => {
+                if let Ok(Some(journal)) = actions::migrate::get_interrupted_migration() {
+                    migrate_folder::migrate_folder(sender, self.progress_bar.sender().to_owned(), journal.from, journal.to, None);
+                }
+            }
+
+            AppMsg::RollbackMigration => migrate_folder::rollback_migration(sender, self.progress_bar.sender().to_owned()),
+
+            AppMsg::OpenPreferences => {
+                self.preferences_window.widget().present();
+            }
+
+            AppMsg::RepairGame => repair_game::repair_game(sender, self.progress_bar.sender().to_owned(), self.ctx.config()),
+
             AppMsg::PredownloadUpdate => {
+                if let Some(LauncherState::PredownloadAvailable { game, voices }) = self.state.clone() {
+                    let config = self.ctx.config();
+
+                    std::thread::spawn(move || {
+                        let plan = StoragePlan::for_predownload(&config, std::iter::once(&game).chain(&voices));
+
+                        sender.input(AppMsg::ShowPredownloadPlan(plan));
+                    });
+                }
+            }
+
+            AppMsg::ShowPredownloadPlan(plan) => {
+                crate::ui::storage_plan::show_dialog(self.ctx.main_window().as_ref(), &plan, clone!(@strong sender => move || {
+                    sender.input(AppMsg::StartPredownload);
+                }));
+            }
+
+            #[allow(unused_must_use)]
+            AppMsg::StartPredownload => {
                 if let Some(LauncherState::PredownloadAvailable { game, mut voices }) = self.state.clone() {
                     let tmp = Config::get().unwrap().launcher.temp.unwrap_or_else(std::env::temp_dir);
 
@@ -1047,6 +1544,8 @@ impl SimpleComponent for App {
 
                     std::thread::spawn(move || {
                         for mut diff in diffs {
+                            let _archive = diff.file_name().map(|name| ActivePath::new(tmp.join(name)));
+
                             let result = diff.download_to(&tmp, clone!(@strong progress_bar_input => move |curr, total| {
                                 progress_bar_input.send(ProgressBarMsg::UpdateProgress(curr, total));
                             }));
@@ -1072,36 +1571,79 @@ impl SimpleComponent for App {
                 }
             }
 
-            AppMsg::PerformAction => unsafe {
-                match self.state.as_ref().unwrap_unchecked() {
+            AppMsg::PlanDiffDownload { diff, confirm } => {
+                let config = self.ctx.config();
+
+                // Free space is checked outside of the GTK thread as it can take a while on slow disks
+                std::thread::spawn(move || {
+                    let plan = StoragePlan::for_diffs(&config, [&diff]);
+
+                    sender.input(AppMsg::ShowDiffPlan { diff, plan, confirm });
+                });
+            }
+
+            AppMsg::ShowDiffPlan { diff, plan, confirm } => {
+                let progress_bar_input = self.progress_bar.sender().to_owned();
+                let config = self.ctx.config();
+
+                if confirm || plan.status() == StorageStatus::NotEnough {
+                    crate::ui::storage_plan::show_dialog(self.ctx.main_window().as_ref(), &plan, clone!(@strong sender => move || {
+                        download_diff::download_diff(sender.clone(), progress_bar_input.clone(), diff.clone(), config.clone());
+                    }));
+                }
+
+                else {
+                    download_diff::download_diff(sender, progress_bar_input, diff, config);
+                }
+            }
+
+            AppMsg::ShowWinePlan { wine, plan } => {
+                let progress_bar_input = self.progress_bar.sender().to_owned();
+                let config = self.ctx.config();
+
+                if plan.status() != StorageStatus::Enough {
+                    crate::ui::storage_plan::show_dialog(self.ctx.main_window().as_ref(), &plan, clone!(@strong sender => move || {
+                        download_wine::install_wine(sender.clone(), progress_bar_input.clone(), config.clone(), wine.clone());
+                    }));
+                }
+
+                else {
+                    download_wine::install_wine(sender, progress_bar_input, config, wine);
+                }
+            }
+
+            AppMsg::PerformAction => if let Some(state) = &self.state {
+                match state {
                     LauncherState::PredownloadAvailable { .. } |
-                    LauncherState::Launch => launch::launch(sender),
+                    LauncherState::Launch => launch::launch(sender, self.ctx.config()),
 
                     LauncherState::FolderMigrationRequired { from, to, cleanup_folder } =>
-                        migrate_folder::migrate_folder(sender, from.to_owned(), to.to_owned(), cleanup_folder.to_owned()),
+                        migrate_folder::migrate_folder(sender, self.progress_bar.sender().to_owned(), from.to_owned(), to.to_owned(), cleanup_folder.to_owned()),
 
                     LauncherState::TelemetryNotDisabled => disable_telemetry::disable_telemetry(sender),
 
-                    LauncherState::WineNotInstalled => download_wine::download_wine(sender, self.progress_bar.sender().to_owned()),
+                    LauncherState::WineNotInstalled => download_wine::download_wine(sender),
                     LauncherState::PrefixNotExists  => create_prefix::create_prefix(sender),
 
                     LauncherState::GameUpdateAvailable(diff) |
                     LauncherState::GameNotInstalled(diff) |
                     LauncherState::VoiceUpdateAvailable(diff) |
-                    LauncherState::VoiceNotInstalled(diff) =>
-                        download_diff::download_diff(sender, self.progress_bar.sender().to_owned(), diff.to_owned()),
+                    LauncherState::VoiceNotInstalled(diff) => sender.input(AppMsg::PlanDiffDownload {
+                        diff: diff.to_owned(),
+                        confirm: true
+                    }),
 
                     LauncherState::GameOutdated(_) |
                     LauncherState::VoiceOutdated(_) => ()
                 }
             }
 
-            AppMsg::HideWindow => unsafe {
-                MAIN_WINDOW.as_ref().unwrap_unchecked().set_visible(false);
+            AppMsg::HideWindow => if let Some(window) = self.ctx.main_window() {
+                window.set_visible(false);
             }
 
-            AppMsg::ShowWindow => unsafe {
-                MAIN_WINDOW.as_ref().unwrap_unchecked().present();
+            AppMsg::ShowWindow => if let Some(window) = self.ctx.main_window() {
+                window.present();
             }
 
             AppMsg::Toast { title, description } => self.toast(title, description)
@@ -1119,7 +1661,7 @@ impl App {
             toast.set_button_label(Some(&tr!("details")));
 
             let dialog = adw::MessageDialog::new(
-                Some(unsafe { MAIN_WINDOW.as_ref().unwrap_unchecked() }),
+                self.ctx.main_window().as_ref(),
                 Some(title.as_ref()),
                 Some(description.as_ref())
             );
@@ -1143,3 +1685,20 @@ impl App {
         self.toast_overlay.add_toast(toast);
     }
 }
+
+/// Get diff of the currently selected game installation
+fn find_game_diff(sender: &ComponentSender<App>, game: Game) -> Option<VersionDiff> {
+    match game.try_get_diff() {
+        Ok(diff) => Some(diff),
+        Err(err) => {
+            tracing::error!("Failed to find game diff: {err}");
+
+            sender.input(AppMsg::Toast {
+                title: tr!("game-diff-finding-error"),
+                description: Some(err.to_string())
+            });
+
+            None
+        }
+    }
+}
@@ -4,3 +4,6 @@ pub mod preferences;
 pub mod components;
 pub mod first_run;
 pub mod migrate_installation;
+pub mod import_installation;
+pub mod config_rollback;
+pub mod storage_plan;
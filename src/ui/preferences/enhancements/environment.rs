@@ -52,6 +52,8 @@ impl AsyncFactoryComponent for Variable {
 }
 
 pub struct EnvironmentPage {
+    ctx: AppContext,
+
     variables: AsyncFactoryVecDeque<Variable>,
 
     name_entry: adw::EntryRow,
@@ -61,12 +63,15 @@ pub struct EnvironmentPage {
 #[derive(Debug, Clone)]
 pub enum EnvironmentPageMsg {
     Add,
-    Remove(DynamicIndex)
+    Remove(DynamicIndex),
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
 }
 
 #[relm4::component(async, pub)]
 impl SimpleAsyncComponent for EnvironmentPage {
-    type Init = ();
+    type Init = AppContext;
     type Input = EnvironmentPageMsg;
     type Output = EnhancementsAppMsg;
 
@@ -77,6 +82,7 @@ impl SimpleAsyncComponent for EnvironmentPage {
             adw::HeaderBar {
                 #[wrap(Some)]
                 set_title_widget = &adw::WindowTitle {
+                    #[watch]
                     set_title: &tr!("environment")
                 },
 
@@ -90,18 +96,23 @@ impl SimpleAsyncComponent for EnvironmentPage {
             },
 
             adw::PreferencesPage {
+                #[watch]
                 set_title: &tr!("environment"),
+
                 set_icon_name: Some("document-properties-symbolic"),
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("game-command"),
+
+                    #[watch]
                     set_description: Some(&tr!("game-command-description")),
 
                     adw::EntryRow {
                         set_title: "%command%",
                         set_text: CONFIG.game.command.as_ref().unwrap_or(&String::new()).trim(),
 
-                        connect_changed => |entry| {
+                        connect_changed[ctx = model.ctx.clone()] => move |entry| {
                             if let Ok(mut config) = Config::get() {
                                 let command = entry.text().trim().to_string();
 
@@ -111,13 +122,14 @@ impl SimpleAsyncComponent for EnvironmentPage {
                                     Some(command)
                                 };
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     }
                 },
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("new-variable"),
 
                     #[wrap(Some)]
@@ -128,6 +140,8 @@ impl SimpleAsyncComponent for EnvironmentPage {
 
                         adw::ButtonContent {
                             set_icon_name: "list-add-symbolic",
+
+                            #[watch]
                             set_label: &tr!("add")
                         },
 
@@ -136,11 +150,13 @@ impl SimpleAsyncComponent for EnvironmentPage {
 
                     #[local_ref]
                     name_entry -> adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("name")
                     },
 
                     #[local_ref]
                     value_entry -> adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("value")
                     }
                 },
@@ -152,13 +168,15 @@ impl SimpleAsyncComponent for EnvironmentPage {
     }
 
     async fn init(
-        _init: Self::Init,
+        ctx: Self::Init,
         root: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
         tracing::info!("Initializing environment settings");
 
         let mut model = Self {
+            ctx,
+
             variables: AsyncFactoryVecDeque::builder()
                 .launch_default()
                 .forward(sender.input_sender(), std::convert::identity),
@@ -178,11 +196,15 @@ impl SimpleAsyncComponent for EnvironmentPage {
 
         let widgets = view_output!();
 
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| EnvironmentPageMsg::LanguageChanged);
+
         AsyncComponentParts { model, widgets }
     }
 
     async fn update(&mut self, msg: Self::Input, _sender: AsyncComponentSender<Self>) {
         match msg {
+            EnvironmentPageMsg::LanguageChanged => (),
+
             EnvironmentPageMsg::Add => {
                 let name = self.name_entry.text().trim().to_string();
                 let value = self.value_entry.text().trim().to_string();
@@ -194,7 +216,7 @@ impl SimpleAsyncComponent for EnvironmentPage {
 
                         config.game.environment.insert(name.clone(), value.clone());
 
-                        Config::update(config);
+                        self.ctx.update_config(config);
 
                         self.variables.guard().push_back((name, value));
                     }
@@ -206,7 +228,7 @@ impl SimpleAsyncComponent for EnvironmentPage {
                     if let Some(var) = self.variables.guard().get(index.current_index()) {
                         config.game.environment.remove(&var.key);
 
-                        Config::update(config);
+                        self.ctx.update_config(config);
                     }
 
                     self.variables.guard().remove(index.current_index());
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use relm4::prelude::*;
 use relm4::factory::*;
 
@@ -7,6 +9,7 @@ use anime_launcher_sdk::sessions::SessionsExt;
 use anime_launcher_sdk::genshin::sessions::Sessions;
 
 use crate::*;
+use crate::session_profiles::SessionProfile;
 
 use super::EnhancementsAppMsg;
 
@@ -29,6 +32,7 @@ impl AsyncFactoryComponent for GameSession {
         root = adw::ActionRow {
             set_title: &self.name,
 
+            #[watch]
             set_subtitle: match &self.description {
                 Some(description) => description.as_str(),
                 None => ""
@@ -41,6 +45,7 @@ impl AsyncFactoryComponent for GameSession {
                 set_icon_name: "view-refresh-symbolic",
                 add_css_class: "flat",
 
+                #[watch]
                 set_tooltip_text: Some(&tr!("update-session")),
 
                 set_valign: gtk::Align::Center,
@@ -55,6 +60,7 @@ impl AsyncFactoryComponent for GameSession {
                 set_icon_name: "user-trash-symbolic",
                 add_css_class: "flat",
 
+                #[watch]
                 set_tooltip_text: Some(&tr!("delete-session")),
 
                 set_valign: gtk::Align::Center,
@@ -87,7 +93,19 @@ pub struct GamePage {
     sessions: AsyncFactoryVecDeque<GameSession>,
 
     sessions_root_widget: gtk::CheckButton,
-    session_name_entry: adw::EntryRow
+    session_name_entry: adw::EntryRow,
+
+    current_session: Option<String>,
+
+    profile_wine_entry: adw::EntryRow,
+    profile_dxvk_entry: adw::EntryRow,
+    profile_fps_entry: adw::EntryRow,
+    profile_command_entry: adw::EntryRow,
+    profile_environment_buffer: gtk::TextBuffer,
+    profile_pre_launch_entry: adw::EntryRow,
+    profile_post_launch_entry: adw::EntryRow,
+    profile_background_entry: adw::EntryRow,
+    profile_gamescope_switch: gtk::Switch
 }
 
 #[derive(Debug, Clone)]
@@ -95,7 +113,26 @@ pub enum GamePageMsg {
     AddSession,
     UpdateSession(usize),
     RemoveSession(usize),
-    SetCurrent(usize)
+    SetCurrent(usize),
+
+    /// Reload sessions list from the sessions file
+    ReloadSessions,
+
+    LoadProfile,
+    SaveProfile,
+
+    /// Choose background picture of the session profile
+    SelectBackground,
+
+    UpdateDescriptions,
+
+    SetDesktopShortcuts(bool),
+
+    /// Add non-Steam game shortcut for the current session
+    AddSteamShortcut,
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
 }
 
 #[relm4::component(async, pub)]
@@ -111,6 +148,7 @@ impl SimpleAsyncComponent for GamePage {
             adw::HeaderBar {
                 #[wrap(Some)]
                 set_title_widget = &adw::WindowTitle {
+                    #[watch]
                     set_title: &tr!("game")
                 },
 
@@ -124,14 +162,18 @@ impl SimpleAsyncComponent for GamePage {
             },
 
             adw::PreferencesPage {
+                #[watch]
                 set_title: &tr!("game"),
+
                 set_icon_name: Some("applications-games-symbolic"),
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("game-sessions"),
 
                     #[local_ref]
                     session_name_entry -> adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("name"),
 
                         add_suffix = &gtk::Button {
@@ -147,6 +189,163 @@ impl SimpleAsyncComponent for GamePage {
 
                 #[local_ref]
                 add = sessions -> adw::PreferencesGroup {},
+
+                add = &adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("session-shortcuts"),
+
+                    adw::ActionRow {
+                        #[watch]
+                        set_title: &tr!("desktop-shortcuts"),
+
+                        #[watch]
+                        set_subtitle: &tr!("desktop-shortcuts-description"),
+
+                        add_suffix = &gtk::Switch {
+                            set_valign: gtk::Align::Center,
+                            set_state: shortcuts::desktop::is_enabled(),
+
+                            connect_state_notify[sender] => move |switch| {
+                                sender.input(GamePageMsg::SetDesktopShortcuts(switch.state()));
+                            }
+                        }
+                    },
+
+                    adw::ActionRow {
+                        #[watch]
+                        set_title: &tr!("steam-shortcut"),
+
+                        #[watch]
+                        set_subtitle: &tr!("steam-shortcut-description"),
+
+                        set_visible: shortcuts::steam::is_available(),
+
+                        #[watch]
+                        set_sensitive: model.current_session.is_some(),
+
+                        add_suffix = &gtk::Button {
+                            #[watch]
+                            set_label: &tr!("add"),
+
+                            set_valign: gtk::Align::Center,
+
+                            connect_clicked => GamePageMsg::AddSteamShortcut
+                        }
+                    }
+                },
+
+                add = &adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("session-profile"),
+
+                    #[watch]
+                    set_description: Some(&tr!("session-profile-description")),
+
+                    #[watch]
+                    set_visible: model.current_session.is_some(),
+
+                    #[wrap(Some)]
+                    set_header_suffix = &gtk::Button {
+                        add_css_class: "flat",
+
+                        set_valign: gtk::Align::Center,
+
+                        adw::ButtonContent {
+                            set_icon_name: "document-save-symbolic",
+
+                            #[watch]
+                            set_label: &tr!("save")
+                        },
+
+                        connect_clicked => GamePageMsg::SaveProfile
+                    },
+
+                    #[local_ref]
+                    profile_wine_entry -> adw::EntryRow {
+                        #[watch]
+                        set_title: &tr!("wine-version")
+                    },
+
+                    #[local_ref]
+                    profile_dxvk_entry -> adw::EntryRow {
+                        #[watch]
+                        set_title: &tr!("dxvk-version")
+                    },
+
+                    #[local_ref]
+                    profile_fps_entry -> adw::EntryRow {
+                        #[watch]
+                        set_title: &tr!("fps-unlocker"),
+
+                        set_input_purpose: gtk::InputPurpose::Digits
+                    },
+
+                    #[local_ref]
+                    profile_command_entry -> adw::EntryRow {
+                        #[watch]
+                        set_title: &tr!("game-command")
+                    },
+
+                    adw::ExpanderRow {
+                        #[watch]
+                        set_title: &tr!("session-profile-environment"),
+
+                        #[watch]
+                        set_subtitle: &tr!("session-profile-environment-description"),
+
+                        add_row = &gtk::TextView {
+                            set_buffer: Some(&model.profile_environment_buffer),
+
+                            set_monospace: true,
+                            set_wrap_mode: gtk::WrapMode::Char,
+
+                            set_top_margin: 12,
+                            set_bottom_margin: 12,
+                            set_left_margin: 12,
+                            set_right_margin: 12
+                        }
+                    },
+
+                    #[local_ref]
+                    profile_pre_launch_entry -> adw::EntryRow {
+                        #[watch]
+                        set_title: &tr!("pre-launch-hook")
+                    },
+
+                    #[local_ref]
+                    profile_post_launch_entry -> adw::EntryRow {
+                        #[watch]
+                        set_title: &tr!("post-launch-hook")
+                    },
+
+                    #[local_ref]
+                    profile_background_entry -> adw::EntryRow {
+                        #[watch]
+                        set_title: &tr!("session-profile-background"),
+
+                        add_suffix = &gtk::Button {
+                            set_icon_name: "document-open-symbolic",
+                            add_css_class: "flat",
+
+                            set_valign: gtk::Align::Center,
+
+                            connect_clicked => GamePageMsg::SelectBackground
+                        }
+                    },
+
+                    adw::ActionRow {
+                        #[watch]
+                        set_title: &tr!("session-profile-gamescope"),
+
+                        #[watch]
+                        set_subtitle: &tr!("session-profile-gamescope-description"),
+
+                        #[local_ref]
+                        add_suffix = profile_gamescope_switch -> gtk::Switch {
+                            set_valign: gtk::Align::Center
+                        }
+                    }
+                }
             }
         }
     }
@@ -164,38 +363,49 @@ impl SimpleAsyncComponent for GamePage {
                 .forward(sender.input_sender(), std::convert::identity),
 
             sessions_root_widget: gtk::CheckButton::new(),
-            session_name_entry: adw::EntryRow::new()
+            session_name_entry: adw::EntryRow::new(),
+
+            current_session: Sessions::get_current().unwrap_or_default(),
+
+            profile_wine_entry: adw::EntryRow::new(),
+            profile_dxvk_entry: adw::EntryRow::new(),
+            profile_fps_entry: adw::EntryRow::new(),
+            profile_command_entry: adw::EntryRow::new(),
+            profile_environment_buffer: gtk::TextBuffer::new(None),
+            profile_pre_launch_entry: adw::EntryRow::new(),
+            profile_post_launch_entry: adw::EntryRow::new(),
+            profile_background_entry: adw::EntryRow::new(),
+            profile_gamescope_switch: gtk::Switch::new()
         };
 
-        let current = Sessions::get_current().unwrap_or_default();
-
-        for (name, _) in Sessions::list().unwrap_or_default() {
-            let check_button = gtk::CheckButton::new();
-
-            check_button.set_group(Some(&model.sessions_root_widget));
-
-            if Some(&name) == current.as_ref() {
-                check_button.set_active(true);
-            }
-
-            model.sessions.guard().push_back(GameSession {
-                name,
-                description: None,
-                check_button
-            });
-        }
+        model.load_sessions();
 
         let sessions = model.sessions.widget();
 
         let session_name_entry = &model.session_name_entry;
 
+        let profile_wine_entry = &model.profile_wine_entry;
+        let profile_dxvk_entry = &model.profile_dxvk_entry;
+        let profile_fps_entry = &model.profile_fps_entry;
+        let profile_command_entry = &model.profile_command_entry;
+        let profile_pre_launch_entry = &model.profile_pre_launch_entry;
+        let profile_post_launch_entry = &model.profile_post_launch_entry;
+        let profile_background_entry = &model.profile_background_entry;
+        let profile_gamescope_switch = &model.profile_gamescope_switch;
+
         let widgets = view_output!();
 
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| GamePageMsg::LanguageChanged);
+
+        sender.input(GamePageMsg::LoadProfile);
+
         AsyncComponentParts { model, widgets }
     }
 
     async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
         match msg {
+            GamePageMsg::LanguageChanged => (),
+
             GamePageMsg::AddSession => {
                 let name = self.session_name_entry.text().trim().to_string();
 
@@ -214,6 +424,10 @@ impl SimpleAsyncComponent for GamePage {
                                     description: None,
                                     check_button
                                 });
+
+                                shortcuts::sync();
+
+                                sender.output(EnhancementsAppMsg::SessionsChanged).unwrap();
                             }
 
                             Err(err) => {
@@ -250,6 +464,14 @@ impl SimpleAsyncComponent for GamePage {
 
                         return;
                     }
+
+                    if let Err(err) = session_profiles::remove(&session.name) {
+                        tracing::error!("Failed to remove session profile: {err}");
+                    }
+
+                    if self.current_session.as_ref() == Some(&session.name) {
+                        self.current_session = None;
+                    }
                 }
 
                 self.sessions.guard().remove(index);
@@ -257,6 +479,10 @@ impl SimpleAsyncComponent for GamePage {
                 if !self.sessions.is_empty() {
                     sender.input(GamePageMsg::SetCurrent(0));
                 }
+
+                shortcuts::sync();
+
+                sender.output(EnhancementsAppMsg::SessionsChanged).unwrap();
             }
 
             GamePageMsg::SetCurrent(index) => {
@@ -283,9 +509,190 @@ impl SimpleAsyncComponent for GamePage {
                         }
 
                         session.check_button.set_active(true);
+
+                        self.current_session = Some(session.name.clone());
+
+                        sender.input(GamePageMsg::LoadProfile);
+
+                        sender.output(EnhancementsAppMsg::SessionsChanged).unwrap();
+                        sender.output(EnhancementsAppMsg::BackgroundChanged).unwrap();
+                    }
+                }
+            }
+
+            GamePageMsg::ReloadSessions => {
+                self.current_session = Sessions::get_current().unwrap_or_default();
+
+                self.sessions.guard().clear();
+
+                self.load_sessions();
+
+                sender.input(GamePageMsg::LoadProfile);
+            }
+
+            GamePageMsg::LoadProfile => {
+                let profile = match &self.current_session {
+                    Some(name) => session_profiles::get(name).unwrap_or_default(),
+                    None => SessionProfile::default()
+                };
+
+                self.profile_wine_entry.set_text(profile.wine.as_deref().unwrap_or_default());
+                self.profile_dxvk_entry.set_text(profile.dxvk.as_deref().unwrap_or_default());
+                self.profile_command_entry.set_text(profile.command.as_deref().unwrap_or_default());
+                self.profile_pre_launch_entry.set_text(profile.pre_launch.as_deref().unwrap_or_default());
+                self.profile_post_launch_entry.set_text(profile.post_launch.as_deref().unwrap_or_default());
+
+                self.profile_background_entry.set_text(&profile.background
+                    .map(|background| background.to_string_lossy().to_string())
+                    .unwrap_or_default());
+
+                self.profile_fps_entry.set_text(&profile.fps.map(|fps| fps.to_string()).unwrap_or_default());
+
+                self.profile_environment_buffer.set_text(&session_profiles::format_environment(&profile.environment.unwrap_or_default()));
+
+                self.profile_gamescope_switch.set_active(profile.gamescope.is_some());
+            }
+
+            GamePageMsg::SaveProfile => {
+                let Some(name) = self.current_session.clone() else {
+                    return;
+                };
+
+                fn get_text(entry: &adw::EntryRow) -> Option<String> {
+                    let text = entry.text().trim().to_string();
+
+                    if text.is_empty() {
+                        None
+                    } else {
+                        Some(text)
+                    }
+                }
+
+                let old_profile = session_profiles::get(&name).unwrap_or_default();
+
+                let (start, end) = self.profile_environment_buffer.bounds();
+
+                let environment = Some(session_profiles::parse_environment(&self.profile_environment_buffer.text(&start, &end, false)))
+                    .filter(|environment| !environment.is_empty());
+
+                // Keep previously captured gamescope settings, or capture current ones
+                let gamescope = if self.profile_gamescope_switch.is_active() {
+                    old_profile.gamescope.or_else(|| Config::get().ok().map(|config| config.game.enhancements.gamescope))
+                } else {
+                    None
+                };
+
+                let profile = SessionProfile {
+                    environment,
+
+                    wine: get_text(&self.profile_wine_entry),
+                    dxvk: get_text(&self.profile_dxvk_entry),
+                    gamescope,
+                    fps: get_text(&self.profile_fps_entry).and_then(|fps| fps.parse().ok()),
+                    command: get_text(&self.profile_command_entry),
+                    pre_launch: get_text(&self.profile_pre_launch_entry),
+                    post_launch: get_text(&self.profile_post_launch_entry),
+                    background: get_text(&self.profile_background_entry).map(PathBuf::from)
+                };
+
+                if let Err(err) = session_profiles::set(&name, profile) {
+                    sender.output(EnhancementsAppMsg::Toast {
+                        title: tr!("session-profile-save-failed"),
+                        description: Some(err.to_string())
+                    }).unwrap();
+                }
+
+                sender.input(GamePageMsg::UpdateDescriptions);
+
+                sender.output(EnhancementsAppMsg::BackgroundChanged).unwrap();
+            }
+
+            GamePageMsg::SelectBackground => {
+                let picture = rfd::AsyncFileDialog::new()
+                    .add_filter(&tr!("pictures"), background::PICTURE_EXTENSIONS)
+                    .pick_file().await;
+
+                if let Some(picture) = picture {
+                    self.profile_background_entry.set_text(&picture.path().to_string_lossy());
+                }
+            }
+
+            GamePageMsg::UpdateDescriptions => {
+                let mut guard = self.sessions.guard();
+
+                for i in 0..guard.len() {
+                    if let Some(session) = guard.get_mut(i) {
+                        session.description = get_profile_summary(&session.name);
+                    }
+                }
+            }
+
+            GamePageMsg::SetDesktopShortcuts(enabled) => {
+                if let Err(err) = shortcuts::desktop::set_enabled(enabled) {
+                    sender.output(EnhancementsAppMsg::Toast {
+                        title: tr!("desktop-shortcuts-update-failed"),
+                        description: Some(err.to_string())
+                    }).unwrap();
+                }
+            }
+
+            GamePageMsg::AddSteamShortcut => {
+                if let Some(name) = &self.current_session {
+                    if let Err(err) = shortcuts::steam::add(name) {
+                        sender.output(EnhancementsAppMsg::Toast {
+                            title: tr!("steam-shortcut-add-failed"),
+                            description: Some(err.to_string())
+                        }).unwrap();
+                    }
+
+                    else {
+                        sender.output(EnhancementsAppMsg::Toast {
+                            title: tr!("steam-shortcut-added"),
+                            description: None
+                        }).unwrap();
                     }
                 }
             }
         }
     }
 }
+
+impl GamePage {
+    fn load_sessions(&mut self) {
+        let mut sessions = Sessions::list()
+            .map(|sessions| sessions.into_keys().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        sessions.sort();
+
+        let mut guard = self.sessions.guard();
+
+        for name in sessions {
+            let check_button = gtk::CheckButton::new();
+
+            check_button.set_group(Some(&self.sessions_root_widget));
+
+            if Some(&name) == self.current_session.as_ref() {
+                check_button.set_active(true);
+            }
+
+            guard.push_back(GameSession {
+                description: get_profile_summary(&name),
+                name,
+                check_button
+            });
+        }
+    }
+}
+
+/// Get list of changes the session's profile makes to the global config
+fn get_profile_summary(name: &str) -> Option<String> {
+    let config = Config::get().ok()?;
+    let changes = session_profiles::get(name).ok()?.diff(&config);
+
+    if changes.is_empty() {
+        None
+    } else {
+        Some(changes.join(", "))
+    }
+}
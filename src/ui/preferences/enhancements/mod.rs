@@ -87,6 +87,8 @@ impl AsyncFactoryComponent for DiscordRpcIcon {
 }
 
 pub struct EnhancementsApp {
+    ctx: AppContext,
+
     discord_rpc_icons: AsyncFactoryVecDeque<DiscordRpcIcon>,
     discord_rpc_root_check_button: gtk::CheckButton,
 
@@ -108,29 +110,44 @@ pub enum EnhancementsAppMsg {
     OpenSandboxSettingsPage,
     OpenEnvironmentSettingsPage,
 
+    ReloadSessions,
+    SessionsChanged,
+
+    /// Background picture of the current session was changed
+    BackgroundChanged,
+
     Toast {
         title: String,
         description: Option<String>
-    }
+    },
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
 }
 
 #[relm4::component(async, pub)]
 impl SimpleAsyncComponent for EnhancementsApp {
-    type Init = ();
+    type Init = AppContext;
     type Input = EnhancementsAppMsg;
     type Output = PreferencesAppMsg;
 
     view! {
         #[root]
         adw::PreferencesPage {
+            #[watch]
             set_title: &tr!("enhancements"),
+
             set_icon_name: Some("applications-graphics-symbolic"),
 
             add = &adw::PreferencesGroup {
+                #[watch]
                 set_title: &tr!("options"),
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("game"),
+
+                    #[watch]
                     set_subtitle: &tr!("game-settings-description"),
 
                     add_suffix = &gtk::Image {
@@ -143,7 +160,10 @@ impl SimpleAsyncComponent for EnhancementsApp {
                 },
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("sandbox"),
+
+                    #[watch]
                     set_subtitle: &tr!("sandbox-settings-description"),
 
                     add_suffix = &gtk::Image {
@@ -156,7 +176,10 @@ impl SimpleAsyncComponent for EnhancementsApp {
                 },
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("environment"),
+
+                    #[watch]
                     set_subtitle: &tr!("environment-settings-description"),
 
                     add_suffix = &gtk::Image {
@@ -170,10 +193,14 @@ impl SimpleAsyncComponent for EnhancementsApp {
             },
 
             add = &adw::PreferencesGroup {
+                #[watch]
                 set_title: &tr!("wine"),
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("synchronization"),
+
+                    #[watch]
                     set_subtitle: &tr!("wine-sync-description"),
 
                     #[wrap(Some)]
@@ -185,19 +212,22 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                     set_selected: CONFIG.game.wine.sync.ordinal() as u32,
 
-                    connect_selected_notify => |row| unsafe {
-                        if is_ready() {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| unsafe {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 config.game.wine.sync = WineSync::from_ordinal_unsafe(row.selected() as i8);
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     }
                 },
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("language"),
+
+                    #[watch]
                     set_subtitle: &tr!("wine-lang-description"),
 
                     #[wrap(Some)]
@@ -217,18 +247,19 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                     set_selected: CONFIG.game.wine.language.ordinal() as u32,
 
-                    connect_selected_notify => |row| unsafe {
-                        if is_ready() {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| unsafe {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 config.game.wine.language = WineLang::from_ordinal_unsafe(row.selected() as i8);
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     }
                 },
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("borderless-window"),
 
                     add_suffix = &gtk::Switch {
@@ -236,12 +267,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                         set_state: CONFIG.game.wine.borderless,
 
-                        connect_state_notify => |switch| {
-                            if is_ready() {
+                        connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.wine.borderless = switch.state();
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -249,6 +280,7 @@ impl SimpleAsyncComponent for EnhancementsApp {
                 },
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("virtual-desktop"),
 
                     #[wrap(Some)]
@@ -263,15 +295,15 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                     set_selected: CONFIG.game.wine.virtual_desktop.get_resolution().into(),
 
-                    connect_selected_notify => |row| {
-                        if is_ready() {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 let (width, height) = Resolution::try_from(row.selected()).unwrap().get_pair();
 
                                 config.game.wine.virtual_desktop.width = width;
                                 config.game.wine.virtual_desktop.height = height;
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     },
@@ -281,12 +313,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                         set_state: CONFIG.game.wine.virtual_desktop.enabled,
 
-                        connect_state_notify => |switch| {
-                            if is_ready() {
+                        connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.wine.virtual_desktop.enabled = switch.state();
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -294,7 +326,10 @@ impl SimpleAsyncComponent for EnhancementsApp {
                 },
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("map-drive-c"),
+
+                    #[watch]
                     set_subtitle: &tr!("map-drive-c-description"),
 
                     add_suffix = &gtk::Switch {
@@ -302,12 +337,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                         set_state: CONFIG.game.wine.drives.drive_c,
 
-                        connect_state_notify => |switch| {
-                            if is_ready() {
+                        connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.wine.drives.drive_c = switch.state();
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -316,7 +351,10 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                 #[name = "map_game_folder_row"]
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("map-game-folder"),
+
+                    #[watch]
                     set_subtitle: &tr!("map-game-folder-description"),
 
                     #[wrap(Some)]
@@ -332,12 +370,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
                         None => 8 // G:
                     },
 
-                    connect_selected_notify => |row| {
-                        if is_ready() {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 config.game.wine.drives.game_folder = Some(AllowedDrives::list()[row.selected() as usize]);
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     },
@@ -347,8 +385,8 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                         set_state: CONFIG.game.wine.drives.game_folder.is_some(),
 
-                        connect_state_notify[map_game_folder_row] => move |switch| {
-                            if is_ready() {
+                        connect_state_notify[map_game_folder_row, ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     if switch.state() {
                                         config.game.wine.drives.game_folder = Some(AllowedDrives::list()[map_game_folder_row.selected() as usize]);
@@ -356,7 +394,7 @@ impl SimpleAsyncComponent for EnhancementsApp {
                                         config.game.wine.drives.game_folder = None;
                                     }
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -365,9 +403,11 @@ impl SimpleAsyncComponent for EnhancementsApp {
             },
 
             add = &adw::PreferencesGroup {
+                #[watch]
                 set_title: &tr!("game"),
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("hud"),
 
                     #[wrap(Some)]
@@ -379,19 +419,22 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                     set_selected: CONFIG.game.enhancements.hud.ordinal() as u32,
 
-                    connect_selected_notify => |row| unsafe {
-                        if is_ready() {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| unsafe {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 config.game.enhancements.hud = HUD::from_ordinal_unsafe(row.selected() as i8);
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     }
                 },
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("fsr"),
+
+                    #[watch]
                     set_subtitle: &tr!("fsr-description"),
 
                     #[wrap(Some)]
@@ -404,12 +447,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                     set_selected: CONFIG.game.enhancements.fsr.quality.ordinal() as u32,
 
-                    connect_selected_notify => |row| unsafe {
-                        if is_ready() {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| unsafe {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 config.game.enhancements.fsr.quality = FsrQuality::from_ordinal_unsafe(row.selected() as i8);
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     },
@@ -419,12 +462,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                         set_state: CONFIG.game.enhancements.fsr.enabled,
 
-                        connect_state_notify => |switch| {
-                            if is_ready() {
+                        connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.fsr.enabled = switch.state();
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -432,7 +475,10 @@ impl SimpleAsyncComponent for EnhancementsApp {
                 },
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("gamemode"),
+
+                    #[watch]
                     set_subtitle: &tr!("gamemode-description"),
 
                     set_sensitive: is_available("gamemoderun"),
@@ -442,12 +488,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                         set_state: CONFIG.game.enhancements.gamemode,
 
-                        connect_state_notify => |switch| {
-                            if is_ready() {
+                        connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.gamemode = switch.state();
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -455,7 +501,10 @@ impl SimpleAsyncComponent for EnhancementsApp {
                 },
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("gamescope"),
+
+                    #[watch]
                     set_subtitle: &tr!("gamescope-description"),
 
                     set_sensitive: is_available("gamescope"),
@@ -474,12 +523,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                         set_state: CONFIG.game.enhancements.gamescope.enabled,
 
-                        connect_state_notify => |switch| {
-                            if is_ready() {
+                        connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.gamescope.enabled = switch.state();
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -488,22 +537,26 @@ impl SimpleAsyncComponent for EnhancementsApp {
             },
 
             add = &adw::PreferencesGroup {
+                #[watch]
                 set_title: &tr!("discord-rpc"),
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("enabled"),
+
+                    #[watch]
                     set_subtitle: &tr!("discord-rpc-description"),
 
                     add_suffix = &gtk::Switch {
                         set_valign: gtk::Align::Center,
                         set_state: CONFIG.launcher.discord_rpc.enabled,
 
-                        connect_state_notify => |switch| {
-                            if is_ready() {
+                        connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.launcher.discord_rpc.enabled = switch.state();
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -512,34 +565,39 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                 #[local_ref]
                 discord_rpc_icons -> adw::ExpanderRow {
+                    #[watch]
                     set_title: &tr!("icon")
                 },
 
                 adw::EntryRow {
+                    #[watch]
                     set_title: &tr!("title"),
+
                     set_text: &CONFIG.launcher.discord_rpc.title,
 
-                    connect_changed: |row| {
-                        if is_ready() {
+                    connect_changed[ctx = model.ctx.clone()] => move |row| {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 config.launcher.discord_rpc.title = row.text().to_string();
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     }
                 },
 
                 adw::EntryRow {
+                    #[watch]
                     set_title: &tr!("description"),
+
                     set_text: &CONFIG.launcher.discord_rpc.subtitle,
 
-                    connect_changed: |row| {
-                        if is_ready() {
+                    connect_changed[ctx = model.ctx.clone()] => move |row| {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 config.launcher.discord_rpc.subtitle = row.text().to_string();
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     }
@@ -547,14 +605,19 @@ impl SimpleAsyncComponent for EnhancementsApp {
             },
 
             add = &adw::PreferencesGroup {
+                #[watch]
                 set_title: &tr!("fps-unlocker"),
+
                 set_visible: match steam::launched_from() {
                     steam::LaunchedFrom::Steam => false,
                     steam::LaunchedFrom::Independent => true
                 },
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("enabled"),
+
+                    #[watch]
                     set_subtitle: &tr!("fps-unlocker-description"),
 
                     #[wrap(Some)]
@@ -581,12 +644,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
                         Fps::Custom(_) => 7
                     },
 
-                    connect_selected_notify => |row| {
-                        if is_ready() && row.selected() < Fps::list().len() as u32 - 1 {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| {
+                        if ctx.is_ready() && row.selected() < Fps::list().len() as u32 - 1 {
                             if let Ok(mut config) = Config::get() {
                                 config.game.enhancements.fps_unlocker.config.fps = Fps::list()[row.selected() as usize].to_num();
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     },
@@ -599,12 +662,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
                             steam::LaunchedFrom::Independent => CONFIG.game.enhancements.fps_unlocker.enabled
                         },
 
-                        connect_state_notify => |switch| {
-                            if is_ready() {
+                        connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.fps_unlocker.enabled = switch.state();
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -612,7 +675,10 @@ impl SimpleAsyncComponent for EnhancementsApp {
                 },
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("fps-unlocker-interval"),
+
+                    #[watch]
                     set_subtitle: &tr!("fps-unlocker-interval-description"),
 
                     add_suffix = &gtk::SpinButton {
@@ -621,12 +687,12 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
                         set_value: CONFIG.game.enhancements.fps_unlocker.config.interval as f64,
 
-                        connect_changed => |row| {
-                            if is_ready() {
+                        connect_changed[ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.fps_unlocker.config.interval = row.value() as u64;
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -646,13 +712,15 @@ impl SimpleAsyncComponent for EnhancementsApp {
     }
 
     async fn init(
-        _init: Self::Init,
+        ctx: Self::Init,
         root: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
         tracing::info!("Initializing enhancements settings");
 
         let mut model = Self {
+            ctx: ctx.clone(),
+
             discord_rpc_icons: AsyncFactoryVecDeque::builder()
                 .launch_default()
                 .forward(sender.input_sender(), std::convert::identity),
@@ -660,7 +728,7 @@ impl SimpleAsyncComponent for EnhancementsApp {
             discord_rpc_root_check_button: gtk::CheckButton::new(),
 
             gamescope: GamescopeApp::builder()
-                .launch(())
+                .launch(ctx.clone())
                 .detach(),
 
             game_page: GamePage::builder()
@@ -668,11 +736,11 @@ impl SimpleAsyncComponent for EnhancementsApp {
                 .forward(sender.input_sender(), std::convert::identity),
 
             sandbox_page: SandboxPage::builder()
-                .launch(())
+                .launch(ctx.clone())
                 .forward(sender.input_sender(), std::convert::identity),
 
             environment_page: EnvironmentPage::builder()
-                .launch(())
+                .launch(ctx.clone())
                 .forward(sender.input_sender(), std::convert::identity)
         };
 
@@ -749,13 +817,17 @@ impl SimpleAsyncComponent for EnhancementsApp {
 
         let widgets = view_output!();
 
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| EnhancementsAppMsg::LanguageChanged);
+
         AsyncComponentParts { model, widgets }
     }
 
     async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
         match msg {
-            EnhancementsAppMsg::SetGamescopeParent => unsafe {
-                self.gamescope.widget().set_transient_for(super::main::PREFERENCES_WINDOW.as_ref());
+            EnhancementsAppMsg::LanguageChanged => (),
+
+            EnhancementsAppMsg::SetGamescopeParent => {
+                self.gamescope.widget().set_transient_for(self.ctx.preferences_window().as_ref());
             }
 
             EnhancementsAppMsg::SetDiscordRpcIcon(index) => {
@@ -763,7 +835,7 @@ impl SimpleAsyncComponent for EnhancementsApp {
                     if let Ok(mut config) = Config::get() {
                         config.launcher.discord_rpc.icon = icon.name.clone();
 
-                        Config::update(config);
+                        self.ctx.update_config(config);
 
                         icon.check_button.set_active(true);
                     }
@@ -774,32 +846,40 @@ impl SimpleAsyncComponent for EnhancementsApp {
                 self.gamescope.widget().present();
             }
 
-            EnhancementsAppMsg::OpenMainPage => unsafe {
-                PREFERENCES_WINDOW.as_ref()
-                    .unwrap_unchecked()
-                    .widget()
-                    .close_subpage();
+            EnhancementsAppMsg::OpenMainPage => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.close_subpage();
+                }
+            }
+
+            EnhancementsAppMsg::OpenGameSettingsPage => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.game_page.widget());
+                }
+            }
+
+            EnhancementsAppMsg::OpenSandboxSettingsPage => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.sandbox_page.widget());
+                }
+            }
+
+            EnhancementsAppMsg::OpenEnvironmentSettingsPage => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.environment_page.widget());
+                }
             }
 
-            EnhancementsAppMsg::OpenGameSettingsPage => unsafe {
-                PREFERENCES_WINDOW.as_ref()
-                    .unwrap_unchecked()
-                    .widget()
-                    .present_subpage(self.game_page.widget());
+            EnhancementsAppMsg::ReloadSessions => {
+                self.game_page.emit(GamePageMsg::ReloadSessions);
             }
 
-            EnhancementsAppMsg::OpenSandboxSettingsPage => unsafe {
-                PREFERENCES_WINDOW.as_ref()
-                    .unwrap_unchecked()
-                    .widget()
-                    .present_subpage(self.sandbox_page.widget());
+            EnhancementsAppMsg::SessionsChanged => {
+                sender.output(PreferencesAppMsg::SessionsChanged).unwrap();
             }
 
-            EnhancementsAppMsg::OpenEnvironmentSettingsPage => unsafe {
-                PREFERENCES_WINDOW.as_ref()
-                    .unwrap_unchecked()
-                    .widget()
-                    .present_subpage(self.environment_page.widget());
+            EnhancementsAppMsg::BackgroundChanged => {
+                sender.output(PreferencesAppMsg::BackgroundChanged).unwrap();
             }
 
             EnhancementsAppMsg::Toast { title, description } => {
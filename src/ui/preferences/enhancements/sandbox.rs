@@ -65,6 +65,8 @@ impl_directory!(SharedDirectory, SandboxPageMsg::RemoveShared);
 impl_directory!(SymlinkPath, SandboxPageMsg::RemoveSymlink);
 
 pub struct SandboxPage {
+    ctx: AppContext,
+
     private_paths: AsyncFactoryVecDeque<PrivateDirectory>,
     shared_paths: AsyncFactoryVecDeque<SharedDirectory>,
     symlink_paths: AsyncFactoryVecDeque<SymlinkPath>,
@@ -88,12 +90,15 @@ pub enum SandboxPageMsg {
     RemoveShared(DynamicIndex),
 
     AddSymlink,
-    RemoveSymlink(DynamicIndex)
+    RemoveSymlink(DynamicIndex),
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
 }
 
 #[relm4::component(async, pub)]
 impl SimpleAsyncComponent for SandboxPage {
-    type Init = ();
+    type Init = AppContext;
     type Input = SandboxPageMsg;
     type Output = EnhancementsAppMsg;
 
@@ -104,6 +109,7 @@ impl SimpleAsyncComponent for SandboxPage {
             adw::HeaderBar {
                 #[wrap(Some)]
                 set_title_widget = &adw::WindowTitle {
+                    #[watch]
                     set_title: &tr!("sandbox")
                 },
 
@@ -117,17 +123,25 @@ impl SimpleAsyncComponent for SandboxPage {
             },
 
             adw::PreferencesPage {
+                #[watch]
                 set_title: &tr!("sandbox"),
+
                 set_icon_name: Some("folder-symbolic"),
 
                 set_sensitive: is_available("bwrap"),
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("sandbox"),
+
+                    #[watch]
                     set_description: Some(&tr!("sandbox-description")),
 
                     adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("enable-sandboxing"),
+
+                        #[watch]
                         set_subtitle: &tr!("enable-sandboxing-description"),
 
                         add_suffix = &gtk::Switch {
@@ -135,12 +149,12 @@ impl SimpleAsyncComponent for SandboxPage {
 
                             set_state: CONFIG.sandbox.enabled,
 
-                            connect_state_notify => |switch| {
-                                if is_ready() {
+                            connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
                                     if let Ok(mut config) = Config::get() {
                                         config.sandbox.enabled = switch.state();
 
-                                        Config::update(config);
+                                        ctx.update_config(config);
                                     }
                                 }
                             }
@@ -148,7 +162,10 @@ impl SimpleAsyncComponent for SandboxPage {
                     },
 
                     adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("hide-home-directory"),
+
+                        #[watch]
                         set_subtitle: &tr!("hide-home-directory-description"),
 
                         add_suffix = &gtk::Switch {
@@ -156,12 +173,12 @@ impl SimpleAsyncComponent for SandboxPage {
 
                             set_state: CONFIG.sandbox.isolate_home,
 
-                            connect_state_notify => |switch| {
-                                if is_ready() {
+                            connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
                                     if let Ok(mut config) = Config::get() {
                                         config.sandbox.isolate_home = switch.state();
 
-                                        Config::update(config);
+                                        ctx.update_config(config);
                                     }
                                 }
                             }
@@ -169,10 +186,12 @@ impl SimpleAsyncComponent for SandboxPage {
                     },
 
                     adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("hostname"),
+
                         set_text: CONFIG.sandbox.hostname.as_ref().unwrap_or(&String::new()).trim(),
 
-                        connect_changed => |entry| {
+                        connect_changed[ctx = model.ctx.clone()] => move |entry| {
                             if let Ok(mut config) = Config::get() {
                                 let command = entry.text().trim().to_string();
 
@@ -182,16 +201,18 @@ impl SimpleAsyncComponent for SandboxPage {
                                     Some(command)
                                 };
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     },
 
                     adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("additional-arguments"),
+
                         set_text: CONFIG.sandbox.args.as_ref().unwrap_or(&String::new()).trim(),
 
-                        connect_changed => |entry| {
+                        connect_changed[ctx = model.ctx.clone()] => move |entry| {
                             if let Ok(mut config) = Config::get() {
                                 let command = entry.text().trim().to_string();
 
@@ -201,7 +222,7 @@ impl SimpleAsyncComponent for SandboxPage {
                                     Some(command)
                                 };
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         },
 
@@ -224,11 +245,15 @@ impl SimpleAsyncComponent for SandboxPage {
                 },
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("private-directories"),
+
+                    #[watch]
                     set_description: Some(&tr!("private-directories-description")),
 
                     #[local_ref]
                     private_path_entry -> adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("path"),
 
                         add_suffix = &gtk::Button {
@@ -246,7 +271,10 @@ impl SimpleAsyncComponent for SandboxPage {
                 add = private_paths -> adw::PreferencesGroup {},
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("shared-directories"),
+
+                    #[watch]
                     set_description: Some(&tr!("shared-directories-description")),
 
                     #[wrap(Some)]
@@ -257,6 +285,8 @@ impl SimpleAsyncComponent for SandboxPage {
 
                         adw::ButtonContent {
                             set_icon_name: "list-add-symbolic",
+
+                            #[watch]
                             set_label: &tr!("add")
                         },
 
@@ -265,16 +295,21 @@ impl SimpleAsyncComponent for SandboxPage {
 
                     #[local_ref]
                     shared_path_from_entry -> adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("original-path")
                     },
 
                     #[local_ref]
                     shared_path_to_entry -> adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("new-path")
                     },
 
                     adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("read-only"),
+
+                        #[watch]
                         set_subtitle: &tr!("read-only-description"),
 
                         #[local_ref]
@@ -288,7 +323,10 @@ impl SimpleAsyncComponent for SandboxPage {
                 add = shared_paths -> adw::PreferencesGroup {},
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("symlinks"),
+
+                    #[watch]
                     set_description: Some(&tr!("symlinks-description")),
 
                     #[wrap(Some)]
@@ -299,6 +337,8 @@ impl SimpleAsyncComponent for SandboxPage {
 
                         adw::ButtonContent {
                             set_icon_name: "list-add-symbolic",
+
+                            #[watch]
                             set_label: &tr!("add")
                         },
 
@@ -307,11 +347,13 @@ impl SimpleAsyncComponent for SandboxPage {
 
                     #[local_ref]
                     symlink_path_from_entry -> adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("original-path")
                     },
 
                     #[local_ref]
                     symlink_path_to_entry -> adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("new-path")
                     }
                 },
@@ -323,13 +365,15 @@ impl SimpleAsyncComponent for SandboxPage {
     }
 
     async fn init(
-        _init: Self::Init,
+        ctx: Self::Init,
         root: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
         tracing::info!("Initializing sandbox settings");
 
         let mut model = Self {
+            ctx,
+
             private_paths: AsyncFactoryVecDeque::builder()
                 .launch_default()
                 .forward(sender.input_sender(), std::convert::identity),
@@ -392,11 +436,15 @@ impl SimpleAsyncComponent for SandboxPage {
 
         let widgets = view_output!();
 
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| SandboxPageMsg::LanguageChanged);
+
         AsyncComponentParts { model, widgets }
     }
 
     async fn update(&mut self, msg: Self::Input, _sender: AsyncComponentSender<Self>) {
         match msg {
+            SandboxPageMsg::LanguageChanged => (),
+
             SandboxPageMsg::AddPrivate => {
                 if let Ok(mut config) = Config::get() {
                     let path = self.private_path_entry.text().trim().to_string();
@@ -406,7 +454,7 @@ impl SimpleAsyncComponent for SandboxPage {
 
                         config.sandbox.private.push(path.clone());
 
-                        Config::update(config);
+                        self.ctx.update_config(config);
 
                         self.private_paths.guard().push_back((path, None));
                     }
@@ -418,7 +466,7 @@ impl SimpleAsyncComponent for SandboxPage {
                     if let Some(var) = self.private_paths.guard().get(index.current_index()) {
                         config.sandbox.private.retain(|item| item != &var.from);
 
-                        Config::update(config);
+                        self.ctx.update_config(config);
                     }
 
                     self.private_paths.guard().remove(index.current_index());
@@ -442,7 +490,7 @@ impl SimpleAsyncComponent for SandboxPage {
                             config.sandbox.mounts.bind.insert(from.clone(), to.clone());
                         }
 
-                        Config::update(config);
+                        self.ctx.update_config(config);
 
                         self.shared_paths.guard().push_back((
                             from,
@@ -462,7 +510,7 @@ impl SimpleAsyncComponent for SandboxPage {
                         config.sandbox.mounts.read_only.remove(&var.from);
                         config.sandbox.mounts.bind.remove(&var.from);
 
-                        Config::update(config);
+                        self.ctx.update_config(config);
                     }
 
                     self.shared_paths.guard().remove(index.current_index());
@@ -480,7 +528,7 @@ impl SimpleAsyncComponent for SandboxPage {
 
                         config.sandbox.mounts.symlinks.insert(from.clone(), to.clone());
 
-                        Config::update(config);
+                        self.ctx.update_config(config);
 
                         self.symlink_paths.guard().push_back((from, Some(to)));
                     }
@@ -492,7 +540,7 @@ impl SimpleAsyncComponent for SandboxPage {
                     if let Some(var) = self.symlink_paths.guard().get(index.current_index()) {
                         config.sandbox.mounts.symlinks.remove(&var.from);
 
-                        Config::update(config);
+                        self.ctx.update_config(config);
                     }
 
                     self.symlink_paths.guard().remove(index.current_index());
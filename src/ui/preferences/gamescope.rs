@@ -8,16 +8,25 @@ use anime_launcher_sdk::config::schema_blanks::prelude::*;
 
 use crate::*;
 
-pub struct GamescopeApp;
+pub struct GamescopeApp {
+    ctx: AppContext
+}
+
+#[derive(Debug, Clone)]
+pub enum GamescopeAppMsg {
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
+}
 
 #[relm4::component(async, pub)]
 impl SimpleAsyncComponent for GamescopeApp {
-    type Init = ();
-    type Input = ();
+    type Init = AppContext;
+    type Input = GamescopeAppMsg;
     type Output = ();
 
     view! {
         adw::PreferencesWindow {
+            #[watch]
             set_title: Some(&tr!("gamescope")),
 
             set_modal: true,
@@ -28,10 +37,13 @@ impl SimpleAsyncComponent for GamescopeApp {
 
             add = &adw::PreferencesPage {
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("game-resolution"),
 
                     adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("width"),
+
                         set_input_purpose: gtk::InputPurpose::Digits,
 
                         set_text: &if CONFIG.game.enhancements.gamescope.game.width > 0 {
@@ -40,19 +52,21 @@ impl SimpleAsyncComponent for GamescopeApp {
                             String::new()
                         },
 
-                        connect_changed => |row| {
-                            if is_ready() {
+                        connect_changed[ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.gamescope.game.width = row.text().parse().unwrap_or_default();
     
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
                     },
 
                     adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("height"),
+
                         set_input_purpose: gtk::InputPurpose::Digits,
 
                         set_text: &if CONFIG.game.enhancements.gamescope.game.height > 0 {
@@ -61,12 +75,12 @@ impl SimpleAsyncComponent for GamescopeApp {
                             String::new()
                         },
 
-                        connect_changed => |row| {
-                            if is_ready() {
+                        connect_changed[ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.gamescope.game.height = row.text().parse().unwrap_or_default();
     
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -74,10 +88,13 @@ impl SimpleAsyncComponent for GamescopeApp {
                 },
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("gamescope-resolution"),
 
                     adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("width"),
+
                         set_input_purpose: gtk::InputPurpose::Digits,
 
                         set_text: &if CONFIG.game.enhancements.gamescope.gamescope.width > 0 {
@@ -86,19 +103,21 @@ impl SimpleAsyncComponent for GamescopeApp {
                             String::new()
                         },
 
-                        connect_changed => |row| {
-                            if is_ready() {
+                        connect_changed[ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.gamescope.gamescope.width = row.text().parse().unwrap_or_default();
     
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
                     },
 
                     adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("height"),
+
                         set_input_purpose: gtk::InputPurpose::Digits,
 
                         set_text: &if CONFIG.game.enhancements.gamescope.gamescope.height > 0 {
@@ -107,12 +126,12 @@ impl SimpleAsyncComponent for GamescopeApp {
                             String::new()
                         },
 
-                        connect_changed => |row| {
-                            if is_ready() {
+                        connect_changed[ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.gamescope.gamescope.height = row.text().parse().unwrap_or_default();
     
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -120,22 +139,26 @@ impl SimpleAsyncComponent for GamescopeApp {
                 },
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("upscaling"),
 
                     adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("integer-scaling"),
+
+                        #[watch]
                         set_subtitle: &tr!("integer-scaling-description"),
 
                         add_suffix = &gtk::Switch {
                             set_valign: gtk::Align::Center,
                             set_state: CONFIG.game.enhancements.gamescope.integer_scaling,
 
-                            connect_state_notify => |switch| {
-                                if is_ready() {
+                            connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
                                     if let Ok(mut config) = Config::get() {
                                         config.game.enhancements.gamescope.integer_scaling = switch.state();
         
-                                        Config::update(config);
+                                        ctx.update_config(config);
                                     }
                                 }
                             }
@@ -144,18 +167,20 @@ impl SimpleAsyncComponent for GamescopeApp {
 
                     adw::ActionRow {
                         set_title: "FSR",
+
+                        #[watch]
                         set_subtitle: &tr!("gamescope-fsr-description"),
 
                         add_suffix = &gtk::Switch {
                             set_valign: gtk::Align::Center,
                             set_state: CONFIG.game.enhancements.gamescope.fsr,
 
-                            connect_state_notify => |switch| {
-                                if is_ready() {
+                            connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
                                     if let Ok(mut config) = Config::get() {
                                         config.game.enhancements.gamescope.fsr = switch.state();
         
-                                        Config::update(config);
+                                        ctx.update_config(config);
                                     }
                                 }
                             }
@@ -164,18 +189,20 @@ impl SimpleAsyncComponent for GamescopeApp {
 
                     adw::ActionRow {
                         set_title: "Nvidia Image Scaling",
+
+                        #[watch]
                         set_subtitle: &tr!("nis-description"),
 
                         add_suffix = &gtk::Switch {
                             set_valign: gtk::Align::Center,
                             set_state: CONFIG.game.enhancements.gamescope.nis,
 
-                            connect_state_notify => |switch| {
-                                if is_ready() {
+                            connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
                                     if let Ok(mut config) = Config::get() {
                                         config.game.enhancements.gamescope.nis = switch.state();
         
-                                        Config::update(config);
+                                        ctx.update_config(config);
                                     }
                                 }
                             }
@@ -184,12 +211,15 @@ impl SimpleAsyncComponent for GamescopeApp {
                 },
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("other-settings"),
 
                     // TODO: maybe use Fps enum like in fps unlocker settings
 
                     adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("framerate-limit"),
+
                         set_input_purpose: gtk::InputPurpose::Digits,
 
                         set_text: &if CONFIG.game.enhancements.gamescope.framerate.focused > 0 {
@@ -198,19 +228,21 @@ impl SimpleAsyncComponent for GamescopeApp {
                             String::new()
                         },
 
-                        connect_changed => |row| {
-                            if is_ready() {
+                        connect_changed[ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.gamescope.framerate.focused = row.text().parse().unwrap_or_default();
     
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
                     },
 
                     adw::EntryRow {
+                        #[watch]
                         set_title: &tr!("unfocused-framerate-limit"),
+
                         set_input_purpose: gtk::InputPurpose::Digits,
 
                         set_text: &if CONFIG.game.enhancements.gamescope.framerate.unfocused > 0 {
@@ -219,18 +251,19 @@ impl SimpleAsyncComponent for GamescopeApp {
                             String::new()
                         },
 
-                        connect_changed => |row| {
-                            if is_ready() {
+                        connect_changed[ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.gamescope.framerate.unfocused = row.text().parse().unwrap_or_default();
     
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
                     },
 
                     adw::ComboRow {
+                        #[watch]
                         set_title: &tr!("window-mode"),
 
                         #[wrap(Some)]
@@ -241,12 +274,12 @@ impl SimpleAsyncComponent for GamescopeApp {
 
                         set_selected: CONFIG.game.enhancements.gamescope.window_type.ordinal() as u32,
 
-                        connect_selected_notify => |row| unsafe {
-                            if is_ready() {
+                        connect_selected_notify[ctx = model.ctx.clone()] => move |row| unsafe {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.enhancements.gamescope.window_type = WindowType::from_ordinal_unsafe(row.selected() as i8);
     
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -257,15 +290,23 @@ impl SimpleAsyncComponent for GamescopeApp {
     }
 
     async fn init(
-        _init: Self::Init,
+        ctx: Self::Init,
         root: Self::Root,
-        _sender: AsyncComponentSender<Self>,
+        sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
         tracing::info!("Initializing gamescope settings");
 
-        let model = Self;
+        let model = Self { ctx };
         let widgets = view_output!();
 
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| GamescopeAppMsg::LanguageChanged);
+
         AsyncComponentParts { model, widgets }
     }
+
+    async fn update(&mut self, msg: Self::Input, _sender: AsyncComponentSender<Self>) {
+        match msg {
+            GamescopeAppMsg::LanguageChanged => ()
+        }
+    }
 }
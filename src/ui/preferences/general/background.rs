@@ -0,0 +1,350 @@
+use relm4::prelude::*;
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use crate::background::*;
+
+use super::GeneralAppMsg;
+
+use crate::*;
+
+/// Minutes between the slideshow pictures used when the interval rotation is selected
+const DEFAULT_INTERVAL: u64 = 30;
+
+pub struct BackgroundPage {
+    ctx: AppContext,
+
+    settings: BackgroundSettings
+}
+
+#[derive(Debug, Clone)]
+pub enum BackgroundPageMsg {
+    SetSource(BackgroundSource),
+    SelectFile,
+    SelectFolder,
+    SetRotation(BackgroundRotation),
+    SetFit(BackgroundFit),
+    SetBlur(bool),
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
+}
+
+#[relm4::component(async, pub)]
+impl SimpleAsyncComponent for BackgroundPage {
+    type Init = AppContext;
+    type Input = BackgroundPageMsg;
+    type Output = GeneralAppMsg;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            adw::HeaderBar {
+                #[wrap(Some)]
+                set_title_widget = &adw::WindowTitle {
+                    #[watch]
+                    set_title: &tr!("background")
+                },
+
+                pack_start = &gtk::Button {
+                    set_icon_name: "go-previous-symbolic",
+
+                    connect_clicked[sender] => move |_| {
+                        sender.output(GeneralAppMsg::OpenMainPage).unwrap();
+                    }
+                }
+            },
+
+            adw::PreferencesPage {
+                add = &adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("background-source"),
+
+                    adw::ComboRow {
+                        #[watch]
+                        set_title: &tr!("background-source"),
+
+                        #[watch]
+                        set_subtitle: &tr!("background-source-description"),
+
+                        set_model: Some(&gtk::StringList::new(&[
+                            &tr!("background-official"),
+                            &tr!("background-file"),
+                            &tr!("background-folder")
+                        ])),
+
+                        set_selected: match model.settings.source {
+                            BackgroundSource::Official => 0,
+                            BackgroundSource::File => 1,
+                            BackgroundSource::Folder => 2
+                        },
+
+                        connect_selected_notify[sender, ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
+                                sender.input(BackgroundPageMsg::SetSource(match row.selected() {
+                                    0 => BackgroundSource::Official,
+                                    1 => BackgroundSource::File,
+                                    2 => BackgroundSource::Folder,
+
+                                    _ => unreachable!()
+                                }));
+                            }
+                        }
+                    },
+
+                    adw::ActionRow {
+                        #[watch]
+                        set_title: &tr!("update-background"),
+
+                        #[watch]
+                        set_subtitle: &tr!("update-background-description"),
+
+                        #[watch]
+                        set_visible: model.settings.source == BackgroundSource::Official,
+
+                        add_suffix = &gtk::Switch {
+                            set_valign: gtk::Align::Center,
+                            set_active: !KEEP_BACKGROUND_FILE.exists(),
+
+                            connect_state_notify => |switch| {
+                                #[allow(unused_must_use)]
+                                if switch.state() {
+                                    std::fs::remove_file(KEEP_BACKGROUND_FILE.as_path());
+                                } else {
+                                    std::fs::write(KEEP_BACKGROUND_FILE.as_path(), "");
+                                }
+                            }
+                        }
+                    },
+
+                    adw::ActionRow {
+                        #[watch]
+                        set_title: &tr!("background-file"),
+
+                        #[watch]
+                        set_subtitle: &model.settings.file.as_ref()
+                            .map(|file| file.to_string_lossy().to_string())
+                            .unwrap_or_else(|| tr!("not-selected")),
+
+                        #[watch]
+                        set_visible: model.settings.source == BackgroundSource::File,
+
+                        add_suffix = &gtk::Button {
+                            set_icon_name: "document-open-symbolic",
+                            add_css_class: "flat",
+
+                            set_valign: gtk::Align::Center,
+
+                            connect_clicked => BackgroundPageMsg::SelectFile
+                        }
+                    },
+
+                    adw::ActionRow {
+                        #[watch]
+                        set_title: &tr!("background-folder"),
+
+                        #[watch]
+                        set_subtitle: &model.settings.folder.as_ref()
+                            .map(|folder| folder.to_string_lossy().to_string())
+                            .unwrap_or_else(|| tr!("not-selected")),
+
+                        #[watch]
+                        set_visible: model.settings.source == BackgroundSource::Folder,
+
+                        add_suffix = &gtk::Button {
+                            set_icon_name: "folder-open-symbolic",
+                            add_css_class: "flat",
+
+                            set_valign: gtk::Align::Center,
+
+                            connect_clicked => BackgroundPageMsg::SelectFolder
+                        }
+                    }
+                },
+
+                add = &adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("background-slideshow"),
+
+                    #[watch]
+                    set_visible: model.settings.source == BackgroundSource::Folder,
+
+                    adw::ComboRow {
+                        #[watch]
+                        set_title: &tr!("background-rotation"),
+
+                        set_model: Some(&gtk::StringList::new(&[
+                            &tr!("background-rotation-launch"),
+                            &tr!("background-rotation-interval")
+                        ])),
+
+                        set_selected: match model.settings.rotation {
+                            BackgroundRotation::Launch => 0,
+                            BackgroundRotation::Interval(_) => 1
+                        },
+
+                        connect_selected_notify[sender, ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
+                                sender.input(BackgroundPageMsg::SetRotation(match row.selected() {
+                                    0 => BackgroundRotation::Launch,
+                                    1 => BackgroundRotation::Interval(DEFAULT_INTERVAL),
+
+                                    _ => unreachable!()
+                                }));
+                            }
+                        }
+                    },
+
+                    adw::ActionRow {
+                        #[watch]
+                        set_title: &tr!("background-interval"),
+
+                        #[watch]
+                        set_subtitle: &tr!("background-interval-description"),
+
+                        #[watch]
+                        set_visible: matches!(model.settings.rotation, BackgroundRotation::Interval(_)),
+
+                        add_suffix = &gtk::SpinButton {
+                            set_valign: gtk::Align::Center,
+                            set_adjustment: &gtk::Adjustment::new(DEFAULT_INTERVAL as f64, 1.0, 1440.0, 1.0, 10.0, 0.0),
+
+                            #[watch]
+                            set_value: match model.settings.rotation {
+                                BackgroundRotation::Interval(interval) => interval as f64,
+                                BackgroundRotation::Launch => DEFAULT_INTERVAL as f64
+                            },
+
+                            connect_changed[sender, ctx = model.ctx.clone()] => move |row| {
+                                if ctx.is_ready() {
+                                    sender.input(BackgroundPageMsg::SetRotation(BackgroundRotation::Interval(row.value() as u64)));
+                                }
+                            }
+                        }
+                    }
+                },
+
+                add = &adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("background-style"),
+
+                    adw::ComboRow {
+                        #[watch]
+                        set_title: &tr!("background-fit"),
+
+                        set_model: Some(&gtk::StringList::new(&[
+                            &tr!("background-fit-cover"),
+                            &tr!("background-fit-contain")
+                        ])),
+
+                        set_selected: match model.settings.fit {
+                            BackgroundFit::Cover => 0,
+                            BackgroundFit::Contain => 1
+                        },
+
+                        connect_selected_notify[sender, ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
+                                sender.input(BackgroundPageMsg::SetFit(match row.selected() {
+                                    0 => BackgroundFit::Cover,
+                                    1 => BackgroundFit::Contain,
+
+                                    _ => unreachable!()
+                                }));
+                            }
+                        }
+                    },
+
+                    adw::ActionRow {
+                        #[watch]
+                        set_title: &tr!("background-blur"),
+
+                        add_suffix = &gtk::Switch {
+                            set_valign: gtk::Align::Center,
+                            set_active: model.settings.blur,
+
+                            connect_state_notify[sender, ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
+                                    sender.input(BackgroundPageMsg::SetBlur(switch.state()));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn init(
+        ctx: Self::Init,
+        root: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        tracing::info!("Initializing background settings");
+
+        let model = Self {
+            ctx,
+
+            settings: BackgroundSettings::get().unwrap_or_else(|err| {
+                tracing::error!("Failed to read background settings: {err}");
+
+                BackgroundSettings::default()
+            })
+        };
+
+        let widgets = view_output!();
+
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| BackgroundPageMsg::LanguageChanged);
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        let mut settings = self.settings.clone();
+
+        match msg {
+            BackgroundPageMsg::LanguageChanged => return,
+
+            BackgroundPageMsg::SetSource(source) => settings.source = source,
+
+            BackgroundPageMsg::SelectFile => {
+                let file = rfd::AsyncFileDialog::new()
+                    .add_filter(&tr!("pictures"), PICTURE_EXTENSIONS)
+                    .pick_file().await;
+
+                match file {
+                    Some(file) => settings.file = Some(file.path().to_path_buf()),
+                    None => return
+                }
+            }
+
+            BackgroundPageMsg::SelectFolder => {
+                match rfd::AsyncFileDialog::new().pick_folder().await {
+                    Some(folder) => settings.folder = Some(folder.path().to_path_buf()),
+                    None => return
+                }
+            }
+
+            BackgroundPageMsg::SetRotation(rotation) => settings.rotation = rotation,
+            BackgroundPageMsg::SetFit(fit) => settings.fit = fit,
+            BackgroundPageMsg::SetBlur(blur) => settings.blur = blur
+        }
+
+        if settings == self.settings {
+            return;
+        }
+
+        if let Err(err) = settings.set() {
+            sender.output(GeneralAppMsg::Toast {
+                title: tr!("background-settings-save-failed"),
+                description: Some(err.to_string())
+            }).unwrap();
+
+            return;
+        }
+
+        self.settings = settings;
+
+        sender.output(GeneralAppMsg::BackgroundChanged).unwrap();
+    }
+}
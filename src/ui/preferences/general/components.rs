@@ -15,6 +15,8 @@ use crate::ui::components::*;
 use crate::*;
 
 pub struct ComponentsPage {
+    ctx: AppContext,
+
     wine_components: AsyncController<ComponentsList<ComponentsPageMsg>>,
     dxvk_components: AsyncController<ComponentsList<ComponentsPageMsg>>,
 
@@ -43,12 +45,27 @@ pub enum ComponentsPageMsg {
     SelectDxvk(usize),
 
     ResetWineSelection(usize),
-    ResetDxvkSelection(usize)
+    ResetDxvkSelection(usize),
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
+}
+
+/// Check if DXVK can be used with the wine version selected in the config
+fn allow_dxvk_selection(config: &Schema) -> bool {
+    match &config.game.wine.selected {
+        Some(version) => match wine::Group::find_in(&config.components.path, version) {
+            Ok(Some(group)) => group.features.unwrap_or_default().need_dxvk,
+            _ => true
+        }
+
+        None => true
+    }
 }
 
 #[relm4::component(async, pub)]
 impl SimpleAsyncComponent for ComponentsPage {
-    type Init = ();
+    type Init = AppContext;
     type Input = ComponentsPageMsg;
     type Output = GeneralAppMsg;
 
@@ -59,6 +76,7 @@ impl SimpleAsyncComponent for ComponentsPage {
             adw::HeaderBar {
                 #[wrap(Some)]
                 set_title_widget = &adw::WindowTitle {
+                    #[watch]
                     set_title: &tr!("components")
                 },
 
@@ -73,9 +91,11 @@ impl SimpleAsyncComponent for ComponentsPage {
 
             adw::PreferencesPage {
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("wine-version"),
 
                     adw::ComboRow {
+                        #[watch]
                         set_title: &tr!("selected-version"),
 
                         #[watch]
@@ -89,8 +109,8 @@ impl SimpleAsyncComponent for ComponentsPage {
                         #[watch]
                         set_activatable: !model.selecting_wine_version,
 
-                        connect_selected_notify[sender] => move |row| {
-                            if is_ready() {
+                        connect_selected_notify[sender, ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
                                 sender.input(ComponentsPageMsg::SelectWine(row.selected() as usize));
                             }
                         } @wine_selected_notify,
@@ -104,7 +124,10 @@ impl SimpleAsyncComponent for ComponentsPage {
                     },
 
                     adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("recommended-only"),
+
+                        #[watch]
                         set_subtitle: &tr!("wine-recommended-description"),
 
                         #[watch]
@@ -116,8 +139,8 @@ impl SimpleAsyncComponent for ComponentsPage {
                             #[block_signal(wine_recommended_notify)]
                             set_state: true,
 
-                            connect_state_notify[sender] => move |switch| {
-                                if is_ready() {
+                            connect_state_notify[sender, ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
                                     sender.input(ComponentsPageMsg::WineRecommendedOnly(switch.state()));
                                 }
                             } @wine_recommended_notify
@@ -133,27 +156,31 @@ impl SimpleAsyncComponent for ComponentsPage {
                 },
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("wine-options"),
 
                     #[watch]
                     set_visible: !model.is_managed,
 
                     adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("wine-use-shared-libraries"),
+
+                        #[watch]
                         set_subtitle: &tr!("wine-use-shared-libraries-description"),
 
                         add_suffix = &gtk::Switch {
                             set_valign: gtk::Align::Center,
 
                             #[block_signal(wine_shared_libraries_notify)]
-                            set_state: CONFIG.game.wine.shared_libraries.wine,
+                            set_state: model.ctx.config().game.wine.shared_libraries.wine,
 
-                            connect_state_notify => |switch| {
-                                if is_ready() {
+                            connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
                                     if let Ok(mut config) = Config::get() {
                                         config.game.wine.shared_libraries.wine = switch.state();
 
-                                        Config::update(config);
+                                        ctx.update_config(config);
                                     }
                                 }
                             } @wine_shared_libraries_notify
@@ -161,21 +188,24 @@ impl SimpleAsyncComponent for ComponentsPage {
                     },
 
                     adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("gstreamer-use-shared-libraries"),
+
+                        #[watch]
                         set_subtitle: &tr!("gstreamer-use-shared-libraries-description"),
 
                         add_suffix = &gtk::Switch {
                             set_valign: gtk::Align::Center,
 
                             #[block_signal(gstreamer_shared_libraries_notify)]
-                            set_state: CONFIG.game.wine.shared_libraries.gstreamer,
+                            set_state: model.ctx.config().game.wine.shared_libraries.gstreamer,
 
-                            connect_state_notify => |switch| {
-                                if is_ready() {
+                            connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
                                     if let Ok(mut config) = Config::get() {
                                         config.game.wine.shared_libraries.gstreamer = switch.state();
 
-                                        Config::update(config);
+                                        ctx.update_config(config);
                                     }
                                 }
                             } @gstreamer_shared_libraries_notify
@@ -184,6 +214,7 @@ impl SimpleAsyncComponent for ComponentsPage {
                 },
 
                 add = &adw::PreferencesGroup {
+                    #[watch]
                     set_title: &tr!("dxvk-version"),
 
                     #[watch]
@@ -200,6 +231,7 @@ impl SimpleAsyncComponent for ComponentsPage {
                     set_sensitive: model.allow_dxvk_selection,
 
                     adw::ComboRow {
+                        #[watch]
                         set_title: &tr!("selected-version"),
     
                         #[watch]
@@ -213,8 +245,8 @@ impl SimpleAsyncComponent for ComponentsPage {
                         #[watch]
                         set_activatable: !model.selecting_dxvk_version,
 
-                        connect_selected_notify[sender] => move |row| {
-                            if is_ready() {
+                        connect_selected_notify[sender, ctx = model.ctx.clone()] => move |row| {
+                            if ctx.is_ready() {
                                 sender.input(ComponentsPageMsg::SelectDxvk(row.selected() as usize));
                             }
                         } @dxvk_selected_notify,
@@ -228,7 +260,10 @@ impl SimpleAsyncComponent for ComponentsPage {
                     },
 
                     adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("recommended-only"),
+
+                        #[watch]
                         set_subtitle: &tr!("dxvk-recommended-description"),
 
                         add_suffix = &gtk::Switch {
@@ -237,8 +272,8 @@ impl SimpleAsyncComponent for ComponentsPage {
                             #[block_signal(dxvk_recommended_notify)]
                             set_state: true,
 
-                            connect_state_notify[sender] => move |switch| {
-                                if is_ready() {
+                            connect_state_notify[sender, ctx = model.ctx.clone()] => move |switch| {
+                                if ctx.is_ready() {
                                     sender.input(ComponentsPageMsg::DxvkRecommendedOnly(switch.state()));
                                 }
                             } @dxvk_recommended_notify
@@ -260,18 +295,22 @@ impl SimpleAsyncComponent for ComponentsPage {
     }
 
     async fn init(
-        _init: Self::Init,
+        ctx: Self::Init,
         root: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
         tracing::info!("Initializing general settings -> components page");
 
+        let config = ctx.config();
+
         let model = Self {
+            ctx,
+
             wine_components: ComponentsList::builder()
                 .launch(ComponentsListInit {
                     pattern: ComponentsListPattern {
-                        download_folder: CONFIG.game.wine.builds.clone(),
-                        groups: wine::get_groups(&CONFIG.components.path).unwrap_or_default()
+                        download_folder: config.game.wine.builds.clone(),
+                        groups: wine::get_groups(&config.components.path).unwrap_or_default()
                             .into_iter()
                             .map(|mut group| {
                                 group.versions = group.versions.into_iter().take(12).collect();
@@ -301,8 +340,8 @@ impl SimpleAsyncComponent for ComponentsPage {
             dxvk_components: ComponentsList::builder()
                 .launch(ComponentsListInit {
                     pattern: ComponentsListPattern {
-                        download_folder: CONFIG.game.dxvk.builds.clone(),
-                        groups: dxvk::get_groups(&CONFIG.components.path).unwrap_or_default()
+                        download_folder: config.game.dxvk.builds.clone(),
+                        groups: dxvk::get_groups(&config.components.path).unwrap_or_default()
                             .into_iter()
                             .map(|mut group| {
                                 group.versions = group.versions.into_iter().take(12).collect();
@@ -332,14 +371,7 @@ impl SimpleAsyncComponent for ComponentsPage {
             downloaded_wine_versions: vec![],
             downloaded_dxvk_versions: vec![],
 
-            allow_dxvk_selection: match &CONFIG.game.wine.selected {
-                Some(version) => match wine::Group::find_in(&CONFIG.components.path, version) {
-                    Ok(Some(group)) => group.features.unwrap_or_default().need_dxvk,
-                    _ => true
-                }
-
-                None => true
-            },
+            allow_dxvk_selection: allow_dxvk_selection(&config),
 
             selected_wine_version: 0,
             selected_dxvk_version: 0,
@@ -355,6 +387,8 @@ impl SimpleAsyncComponent for ComponentsPage {
 
         let widgets = view_output!();
 
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| ComponentsPageMsg::LanguageChanged);
+
         AsyncComponentParts { model, widgets }
     }
 
@@ -362,6 +396,8 @@ impl SimpleAsyncComponent for ComponentsPage {
         tracing::debug!("Called general settings event: {:?}", msg);
 
         match msg {
+            ComponentsPageMsg::LanguageChanged => (),
+
             ComponentsPageMsg::WineRecommendedOnly(state) => {
                 // todo
                 self.wine_components.sender().send(ComponentsListMsg::ShowRecommendedOnly(state)).unwrap();
@@ -373,9 +409,11 @@ impl SimpleAsyncComponent for ComponentsPage {
             }
 
             ComponentsPageMsg::UpdateDownloadedWine => {
+                let config = self.ctx.config();
+
                 match steam::launched_from() {
                     steam::LaunchedFrom::Steam => {
-                        self.downloaded_wine_versions = wine::get_groups(&CONFIG.components.path)
+                        self.downloaded_wine_versions = wine::get_groups(&config.components.path)
                             .unwrap()
                             .into_iter()
                             .flat_map(|group| group.versions.clone().into_iter()
@@ -387,7 +425,7 @@ impl SimpleAsyncComponent for ComponentsPage {
                             ).collect();
                     },
                     steam::LaunchedFrom::Independent => {
-                        self.downloaded_wine_versions = wine::get_downloaded(&CONFIG.components.path, &CONFIG.game.wine.builds)
+                        self.downloaded_wine_versions = wine::get_downloaded(&config.components.path, &config.game.wine.builds)
                             .unwrap_or_default()
                             .into_iter()
                             .flat_map(|group| group.versions.clone().into_iter()
@@ -400,7 +438,7 @@ impl SimpleAsyncComponent for ComponentsPage {
                     }
                 }
 
-                self.selected_wine_version = if let Some(selected) = &CONFIG.game.wine.selected {
+                self.selected_wine_version = if let Some(selected) = &config.game.wine.selected {
                     let mut index = 0;
 
                     for (i, (version, _)) in self.downloaded_wine_versions.iter().enumerate() {
@@ -417,16 +455,20 @@ impl SimpleAsyncComponent for ComponentsPage {
                 else {
                     0
                 };
+
+                self.allow_dxvk_selection = allow_dxvk_selection(&config);
             }
 
             ComponentsPageMsg::UpdateDownloadedDxvk => {
-                self.downloaded_dxvk_versions = dxvk::get_downloaded(&CONFIG.components.path, &CONFIG.game.dxvk.builds)
+                let config = self.ctx.config();
+
+                self.downloaded_dxvk_versions = dxvk::get_downloaded(&config.components.path, &config.game.dxvk.builds)
                     .unwrap_or_default()
                     .into_iter()
                     .flat_map(|group| group.versions)
                     .collect();
 
-                self.selected_dxvk_version = if let Ok(Some(selected)) = CONFIG.get_selected_dxvk() {
+                self.selected_dxvk_version = if let Ok(Some(selected)) = config.get_selected_dxvk() {
                     let mut index = 0;
 
                     for (i, version) in self.downloaded_dxvk_versions.iter().enumerate() {
@@ -452,7 +494,7 @@ impl SimpleAsyncComponent for ComponentsPage {
                         if let Some((version, _)) = self.downloaded_wine_versions.get(index) {
                             let wine_name = version.name.to_string();
                             config.game.wine.selected = Some(wine_name);
-                            match Config::update_raw(config) {
+                            match crate::config_history::update_raw(config) {
                                 Ok(_) => {
                                     sender.input(ComponentsPageMsg::ResetWineSelection(index));
                                 }
@@ -0,0 +1,182 @@
+use relm4::prelude::*;
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use crate::config_validator::{self, Issue, Severity};
+
+use super::GeneralAppMsg;
+
+use crate::*;
+
+pub struct ConfigCheckPage {
+    ctx: AppContext,
+
+    issues_group: adw::PreferencesGroup,
+    issues_rows: Vec<adw::ActionRow>,
+
+    issues: Vec<Issue>
+}
+
+#[derive(Debug, Clone)]
+pub enum ConfigCheckPageMsg {
+    Check,
+    Fix(usize),
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
+}
+
+#[relm4::component(async, pub)]
+impl SimpleAsyncComponent for ConfigCheckPage {
+    type Init = AppContext;
+    type Input = ConfigCheckPageMsg;
+    type Output = GeneralAppMsg;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            adw::HeaderBar {
+                #[wrap(Some)]
+                set_title_widget = &adw::WindowTitle {
+                    #[watch]
+                    set_title: &tr!("check-config")
+                },
+
+                pack_start = &gtk::Button {
+                    set_icon_name: "go-previous-symbolic",
+
+                    connect_clicked[sender] => move |_| {
+                        sender.output(GeneralAppMsg::OpenMainPage).unwrap();
+                    }
+                }
+            },
+
+            adw::PreferencesPage {
+                add = &adw::PreferencesGroup {
+                    #[watch]
+                    set_visible: model.issues.is_empty(),
+
+                    adw::StatusPage {
+                        set_icon_name: Some("emblem-ok-symbolic"),
+
+                        #[watch]
+                        set_title: &tr!("config-check-passed")
+                    }
+                },
+
+                #[local_ref]
+                add = issues_group -> adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("config-issues"),
+
+                    #[watch]
+                    set_visible: !model.issues.is_empty(),
+
+                    #[wrap(Some)]
+                    set_header_suffix = &gtk::Button {
+                        add_css_class: "flat",
+
+                        set_valign: gtk::Align::Center,
+
+                        adw::ButtonContent {
+                            set_icon_name: "view-refresh-symbolic",
+
+                            #[watch]
+                            set_label: &tr!("check-config")
+                        },
+
+                        connect_clicked => ConfigCheckPageMsg::Check
+                    }
+                }
+            }
+        }
+    }
+
+    async fn init(
+        ctx: Self::Init,
+        root: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        tracing::info!("Initializing config check page");
+
+        let model = Self {
+            ctx,
+
+            issues_group: adw::PreferencesGroup::new(),
+            issues_rows: Vec::new(),
+
+            issues: Vec::new()
+        };
+
+        let issues_group = &model.issues_group;
+
+        let widgets = view_output!();
+
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| ConfigCheckPageMsg::LanguageChanged);
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            ConfigCheckPageMsg::LanguageChanged => (),
+
+            ConfigCheckPageMsg::Check => {
+                for row in self.issues_rows.drain(..) {
+                    self.issues_group.remove(&row);
+                }
+
+                self.issues = config_validator::validate(&self.ctx.config());
+
+                for (i, issue) in self.issues.iter().enumerate() {
+                    let row = adw::ActionRow::new();
+
+                    row.set_title(&issue.field);
+                    row.set_subtitle(&issue.description);
+
+                    row.add_prefix(&gtk::Image::from_icon_name(match issue.severity {
+                        Severity::Error => "dialog-error-symbolic",
+                        Severity::Warning => "dialog-warning-symbolic"
+                    }));
+
+                    if let Some(fix) = &issue.fix {
+                        let button = gtk::Button::with_label(&fix.title());
+
+                        button.set_valign(gtk::Align::Center);
+
+                        button.connect_clicked(gtk::glib::clone!(@strong sender => move |_| {
+                            sender.input(ConfigCheckPageMsg::Fix(i));
+                        }));
+
+                        row.add_suffix(&button);
+                    }
+
+                    self.issues_group.add(&row);
+                    self.issues_rows.push(row);
+                }
+            }
+
+            ConfigCheckPageMsg::Fix(i) => {
+                if let Some(Some(fix)) = self.issues.get(i).map(|issue| &issue.fix) {
+                    let mut config = self.ctx.config();
+
+                    match fix.apply(&mut config) {
+                        Ok(()) => self.ctx.update_config(config),
+
+                        Err(err) => {
+                            tracing::error!("Failed to fix config issue: {err}");
+
+                            sender.output(GeneralAppMsg::Toast {
+                                title: tr!("config-fix-failed"),
+                                description: Some(err.to_string())
+                            }).unwrap();
+                        }
+                    }
+                }
+
+                sender.input(ConfigCheckPageMsg::Check);
+            }
+        }
+    }
+}
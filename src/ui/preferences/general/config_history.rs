@@ -0,0 +1,228 @@
+use relm4::prelude::*;
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use crate::config_history::{self, Backup};
+use crate::i18n::{format_datetime, format_relative_date};
+
+use super::GeneralAppMsg;
+
+use crate::*;
+
+pub struct ConfigHistoryPage {
+    backups_group: adw::PreferencesGroup,
+    backups_rows: Vec<adw::ExpanderRow>,
+
+    backups: Vec<Backup>
+}
+
+#[derive(Debug, Clone)]
+pub enum ConfigHistoryPageMsg {
+    /// Reload backups list from the backups folder
+    Reload,
+
+    Backup,
+    Restore(usize),
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
+}
+
+#[relm4::component(async, pub)]
+impl SimpleAsyncComponent for ConfigHistoryPage {
+    type Init = ();
+    type Input = ConfigHistoryPageMsg;
+    type Output = GeneralAppMsg;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            adw::HeaderBar {
+                #[wrap(Some)]
+                set_title_widget = &adw::WindowTitle {
+                    #[watch]
+                    set_title: &tr!("config-history")
+                },
+
+                pack_start = &gtk::Button {
+                    set_icon_name: "go-previous-symbolic",
+
+                    connect_clicked[sender] => move |_| {
+                        sender.output(GeneralAppMsg::OpenMainPage).unwrap();
+                    }
+                }
+            },
+
+            adw::PreferencesPage {
+                #[local_ref]
+                add = backups_group -> adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("config-backups"),
+
+                    #[watch]
+                    set_description: Some(&tr!("config-backups-description")),
+
+                    #[wrap(Some)]
+                    set_header_suffix = &gtk::Button {
+                        add_css_class: "flat",
+
+                        set_valign: gtk::Align::Center,
+
+                        adw::ButtonContent {
+                            set_icon_name: "document-save-symbolic",
+
+                            #[watch]
+                            set_label: &tr!("create-backup")
+                        },
+
+                        connect_clicked => ConfigHistoryPageMsg::Backup
+                    }
+                }
+            }
+        }
+    }
+
+    async fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        tracing::info!("Initializing config history page");
+
+        let model = Self {
+            backups_group: adw::PreferencesGroup::new(),
+            backups_rows: Vec::new(),
+
+            backups: Vec::new()
+        };
+
+        let backups_group = &model.backups_group;
+
+        let widgets = view_output!();
+
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| ConfigHistoryPageMsg::LanguageChanged);
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            ConfigHistoryPageMsg::LanguageChanged => (),
+
+            ConfigHistoryPageMsg::Reload => {
+                for row in self.backups_rows.drain(..) {
+                    self.backups_group.remove(&row);
+                }
+
+                self.backups = match config_history::list() {
+                    Ok(backups) => backups,
+
+                    Err(err) => {
+                        tracing::error!("Failed to list config backups: {err}");
+
+                        Vec::new()
+                    }
+                };
+
+                let current: Option<serde_json::Value> = anime_launcher_sdk::genshin::consts::config_file().ok()
+                    .and_then(|path| std::fs::read(path).ok())
+                    .and_then(|config| serde_json::from_slice(&config).ok());
+
+                for (i, backup) in self.backups.iter().enumerate() {
+                    let Ok(content) = backup.read() else {
+                        continue;
+                    };
+
+                    let row = adw::ExpanderRow::new();
+
+                    row.set_title(&format_relative_date(backup.timestamp));
+                    row.set_tooltip_text(Some(&format_datetime(backup.timestamp)));
+
+                    // Compare with the previous version
+                    let changes = match self.backups.get(i + 1).and_then(|previous| previous.read().ok()) {
+                        Some(previous) => config_history::diff(&previous, &content),
+                        None => Vec::new()
+                    };
+
+                    if current.as_ref() == Some(&content) {
+                        row.set_subtitle(&tr!("config-backup-current"));
+                    }
+
+                    else {
+                        row.set_subtitle(&tr!("config-backup-changes", {
+                            "changes" = changes.len()
+                        }));
+                    }
+
+                    for change in changes {
+                        let change_row = adw::ActionRow::new();
+
+                        change_row.set_title(&change.to_string());
+                        change_row.set_title_lines(3);
+                        change_row.add_css_class("monospace");
+
+                        row.add_row(&change_row);
+                    }
+
+                    let button = gtk::Button::with_label(&tr!("restore"));
+
+                    button.set_valign(gtk::Align::Center);
+                    button.set_sensitive(current.as_ref() != Some(&content));
+
+                    button.connect_clicked(gtk::glib::clone!(@strong sender => move |_| {
+                        sender.input(ConfigHistoryPageMsg::Restore(i));
+                    }));
+
+                    row.add_action(&button);
+
+                    self.backups_group.add(&row);
+                    self.backups_rows.push(row);
+                }
+            }
+
+            ConfigHistoryPageMsg::Backup => {
+                // Save changes made in preferences before
+                if let Err(err) = config_history::flush() {
+                    sender.output(GeneralAppMsg::Toast {
+                        title: tr!("config-update-error"),
+                        description: Some(err.to_string())
+                    }).unwrap();
+                }
+
+                if let Err(err) = config_history::backup() {
+                    sender.output(GeneralAppMsg::Toast {
+                        title: tr!("config-backup-failed"),
+                        description: Some(err.to_string())
+                    }).unwrap();
+                }
+
+                sender.input(ConfigHistoryPageMsg::Reload);
+            }
+
+            ConfigHistoryPageMsg::Restore(i) => {
+                if let Some(backup) = self.backups.get(i) {
+                    match config_history::restore(backup) {
+                        Ok(()) => {
+                            sender.output(GeneralAppMsg::Toast {
+                                title: tr!("config-restored"),
+                                description: Some(tr!("config-restored-description"))
+                            }).unwrap();
+                        }
+
+                        Err(err) => {
+                            tracing::error!("Failed to restore config backup: {err}");
+
+                            sender.output(GeneralAppMsg::Toast {
+                                title: tr!("config-restore-failed"),
+                                description: Some(err.to_string())
+                            }).unwrap();
+                        }
+                    }
+                }
+
+                sender.input(ConfigHistoryPageMsg::Reload);
+            }
+        }
+    }
+}
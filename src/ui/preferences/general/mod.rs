@@ -21,10 +21,24 @@ use anime_launcher_sdk::genshin::env_emulation::Environment;
 use anime_launcher_sdk::integrations::steam;
 
 pub mod components;
+pub mod config_history;
+pub mod config_check;
+pub mod system_check;
+pub mod storage;
+pub mod voice_queue;
+pub mod background;
 
 use components::*;
+use config_history::*;
+use config_check::*;
+use system_check::*;
+use storage::*;
+use voice_queue::*;
+use background::*;
 
 use crate::ui::migrate_installation::MigrateInstallationApp;
+use crate::ui::import_installation::*;
+use crate::actions::voice_queue::VoiceOperationKind;
 use crate::i18n::*;
 use crate::*;
 
@@ -34,6 +48,10 @@ use super::main::PreferencesAppMsg;
 struct VoicePackageComponent {
     locale: VoiceLocale,
     installed: bool,
+
+    /// Pending operation in the voiceovers queue
+    queued: Option<VoiceOperationKind>,
+
     sensitive: bool
 }
 
@@ -47,8 +65,18 @@ impl AsyncFactoryComponent for VoicePackageComponent {
 
     view! {
         root = adw::ActionRow {
+            #[watch]
             set_title: &tr!(&self.locale.to_name().to_ascii_lowercase()),
 
+            #[watch]
+            set_subtitle: &match self.queued {
+                Some(VoiceOperationKind::Install) => tr!("voice-queued-install"),
+                Some(VoiceOperationKind::Update)  => tr!("voice-queued-update"),
+                Some(VoiceOperationKind::Remove)  => tr!("voice-queued-remove"),
+
+                None => String::new()
+            },
+
             add_suffix = &gtk::Button {
                 #[watch]
                 set_visible: self.installed,
@@ -91,24 +119,41 @@ impl AsyncFactoryComponent for VoicePackageComponent {
         Self {
             locale: init.0,
             installed: init.1,
+            queued: None,
             sensitive: true
         }
     }
 
     async fn update(&mut self, msg: Self::Input, sender: AsyncFactorySender<Self>) {
-        self.installed = !self.installed;
-
         sender.output(msg)
             .unwrap();
     }
 }
 
 pub struct GeneralApp {
+    ctx: AppContext,
+
     voice_packages: AsyncFactoryVecDeque<VoicePackageComponent>,
     migrate_installation: Controller<MigrateInstallationApp>,
+    import_installation: AsyncController<ImportInstallationApp>,
     components_page: AsyncController<ComponentsPage>,
+    config_history_page: AsyncController<ConfigHistoryPage>,
+    config_check_page: AsyncController<ConfigCheckPage>,
+    system_check_page: AsyncController<SystemCheckPage>,
+    storage_page: AsyncController<StoragePage>,
+    voice_queue_page: AsyncController<VoiceQueuePage>,
+    background_page: AsyncController<BackgroundPage>,
+
+    profile_name_entry: adw::EntryRow,
+    profiles_group: adw::PreferencesGroup,
+    profiles_rows: Vec<adw::ActionRow>,
 
     game_diff: Option<VersionDiff>,
+
+    /// Amount of pending and running voiceovers operations
+    voice_queue_size: usize,
+
+    edition: GameEdition,
     style: LauncherStyle,
     languages: Vec<String>
 }
@@ -124,40 +169,82 @@ pub enum GeneralAppMsg {
     // in the VoicePackage component
     AddVoicePackage(DynamicIndex),
     RemoveVoicePackage(DynamicIndex),
-    SetVoicePackageSensitivity(DynamicIndex, bool),
+
+    /// Voiceovers queue was changed by the queue page
+    VoiceQueueChanged {
+        pending: Vec<(VoiceLocale, VoiceOperationKind)>,
+        running: Option<VoiceLocale>
+    },
+
+    VoiceQueueStarted,
+    VoiceQueueFinished,
+
+    /// Main window started or finished downloading something
+    SetDownloading(bool),
 
     UpdateDownloadedWine,
     UpdateDownloadedDxvk,
 
     OpenMigrateInstallation,
+    OpenImportInstallation,
     RepairGame,
 
     OpenMainPage,
     OpenComponentsPage,
+    OpenConfigHistoryPage,
+    OpenConfigCheckPage,
+    OpenSystemCheckPage,
+    OpenStoragePage,
+    OpenVoiceQueuePage,
+    OpenBackgroundPage,
 
     UpdateLauncherStyle(LauncherStyle),
 
+    /// Background settings were changed in the background page
+    BackgroundChanged,
+
+    /// Switch game edition and re-initialize the launcher for it
+    SetEdition(GameEdition),
+
+    /// Update edition and voice packages after the game installation was changed
+    ReloadInstallation,
+
+    /// Existing game installation was imported
+    InstallationImported,
+
     WineOpen(&'static [&'static str]),
 
+    /// Reload installation profiles list from the profiles file
+    ReloadInstallationProfiles,
+
+    AddInstallationProfile,
+    RemoveInstallationProfile(String),
+
     Toast {
         title: String,
         description: Option<String>
-    }
+    },
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
 }
 
 #[relm4::component(async, pub)]
 impl SimpleAsyncComponent for GeneralApp {
-    type Init = ();
+    type Init = AppContext;
     type Input = GeneralAppMsg;
     type Output = PreferencesAppMsg;
 
     view! {
         #[root]
         adw::PreferencesPage {
+            #[watch]
             set_title: &tr!("general"),
+
             set_icon_name: Some("applications-system-symbolic"),
 
             add = &adw::PreferencesGroup {
+                #[watch]
                 set_title: &tr!("appearance"),
 
                 gtk::Box {
@@ -186,6 +273,7 @@ impl SimpleAsyncComponent for GeneralApp {
                         },
 
                         gtk::Label {
+                            #[watch]
                             set_text: &tr!("modern"),
 
                             set_margin_top: 16
@@ -212,6 +300,7 @@ impl SimpleAsyncComponent for GeneralApp {
                         },
 
                         gtk::Label {
+                            #[watch]
                             set_text: &tr!("classic"),
 
                             set_margin_top: 16
@@ -225,30 +314,31 @@ impl SimpleAsyncComponent for GeneralApp {
                 set_visible: model.style == LauncherStyle::Classic,
 
                 adw::ActionRow {
-                    set_title: &tr!("update-background"),
-                    set_subtitle: &tr!("update-background-description"),
+                    #[watch]
+                    set_title: &tr!("background"),
 
-                    add_suffix = &gtk::Switch {
-                        set_valign: gtk::Align::Center,
-                        set_active: !KEEP_BACKGROUND_FILE.exists(),
-
-                        connect_state_notify => |switch| {
-                            #[allow(unused_must_use)]
-                            if switch.state() {
-                                std::fs::remove_file(KEEP_BACKGROUND_FILE.as_path());
-                            } else {
-                                std::fs::write(KEEP_BACKGROUND_FILE.as_path(), "");
-                            }
-                        }
-                    }
+                    #[watch]
+                    set_subtitle: &tr!("background-description"),
+
+                    add_suffix = &gtk::Image {
+                        set_icon_name: Some("go-next-symbolic")
+                    },
+
+                    set_activatable: true,
+
+                    connect_activated => GeneralAppMsg::OpenBackgroundPage
                 }
             },
 
             add = &adw::PreferencesGroup {
+                #[watch]
                 set_title: &tr!("general"),
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("launcher-language"),
+
+                    #[watch]
                     set_subtitle: &tr!("launcher-language-description"),
 
                     set_model: Some(&gtk::StringList::new(&model.languages.iter().map(|lang| lang.as_str()).collect::<Vec<&str>>())),
@@ -261,20 +351,28 @@ impl SimpleAsyncComponent for GeneralApp {
                             .unwrap_or(0) as u32
                     },
 
-                    connect_selected_notify => |row| {
-                        if is_ready() {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| {
+                        if ctx.is_ready() {
+                            let lang = SUPPORTED_LANGUAGES
+                                .get(row.selected() as usize)
+                                .unwrap_or(&SUPPORTED_LANGUAGES[0]);
+
                             if let Ok(mut config) = Config::get() {
-                                config.launcher.language = crate::i18n::format_lang(SUPPORTED_LANGUAGES
-                                    .get(row.selected() as usize)
-                                    .unwrap_or(&SUPPORTED_LANGUAGES[0]));
-    
-                                Config::update(config);
+                                config.launcher.language = crate::i18n::format_lang(lang);
+
+                                ctx.update_config(config);
+                            }
+
+                            // Re-renders all the subscribed components
+                            if let Err(err) = ctx.set_language(lang.clone()) {
+                                tracing::error!("Failed to set launcher language: {err}");
                             }
                         }
                     }
                 },
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("game-edition"),
 
                     set_model: Some(&gtk::StringList::new(&[
@@ -282,26 +380,24 @@ impl SimpleAsyncComponent for GeneralApp {
                         &tr!("china")
                     ])),
 
+                    #[watch]
+                    #[block_signal(edition_notify)]
                     set_selected: GameEdition::list().iter()
-                        .position(|edition| edition == &CONFIG.launcher.edition)
+                        .position(|edition| edition == &model.edition)
                         .unwrap() as u32,
 
-                    connect_selected_notify[sender] => move |row| {
-                        if is_ready() {
-                            #[allow(unused_must_use)]
-                            if let Ok(mut config) = Config::get() {
-                                config.launcher.edition = GameEdition::list()[row.selected() as usize];
-
-                                Config::update(config);
-
-                                sender.output(PreferencesAppMsg::UpdateLauncherState);
-                            }
+                    connect_selected_notify[sender, ctx = model.ctx.clone()] => move |row| {
+                        if ctx.is_ready() {
+                            sender.input(GeneralAppMsg::SetEdition(GameEdition::list()[row.selected() as usize]));
                         }
-                    }
+                    } @edition_notify
                 },
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("game-environment"),
+
+                    #[watch]
                     set_subtitle: &tr!("game-environment-description"),
 
                     set_model: Some(&gtk::StringList::new(&[
@@ -309,15 +405,15 @@ impl SimpleAsyncComponent for GeneralApp {
                         "Android"
                     ])),
 
-                    set_selected: match CONFIG.launcher.environment {
+                    set_selected: match model.ctx.config().launcher.environment {
                         Environment::PC => 0,
                         Environment::Android => 1,
 
                         _ => unreachable!()
                     },
 
-                    connect_selected_notify => |row| {
-                        if is_ready() {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 config.launcher.environment = match row.selected() {
                                     0 => Environment::PC,
@@ -326,7 +422,7 @@ impl SimpleAsyncComponent for GeneralApp {
                                     _ => unreachable!()
                                 };
     
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     }
@@ -334,17 +430,41 @@ impl SimpleAsyncComponent for GeneralApp {
 
                 #[local_ref]
                 voice_packages -> adw::ExpanderRow {
+                    #[watch]
                     set_title: &tr!("game-voiceovers"),
+
+                    #[watch]
                     set_subtitle: &tr!("game-voiceovers-description")
                 },
 
+                adw::ActionRow {
+                    #[watch]
+                    set_title: &tr!("voice-queue"),
+
+                    #[watch]
+                    set_subtitle: &tr!("voice-queue-description", {
+                        "count" = model.voice_queue_size
+                    }),
+
+                    add_suffix = &gtk::Image {
+                        set_icon_name: Some("go-next-symbolic")
+                    },
+
+                    set_activatable: true,
+
+                    connect_activated => GeneralAppMsg::OpenVoiceQueuePage
+                },
+
                 gtk::Box {
                     set_orientation: gtk::Orientation::Horizontal,
                     set_spacing: 8,
                     set_margin_top: 16,
 
                     gtk::Button {
+                        #[watch]
                         set_label: &tr!("migrate-installation"),
+
+                        #[watch]
                         set_tooltip_text: Some(&tr!("migrate-installation-description")),
 
                         set_visible: match steam::launched_from() {
@@ -356,6 +476,22 @@ impl SimpleAsyncComponent for GeneralApp {
                     },
 
                     gtk::Button {
+                        #[watch]
+                        set_label: &tr!("import-installation"),
+
+                        #[watch]
+                        set_tooltip_text: Some(&tr!("import-installation-description")),
+
+                        set_visible: match steam::launched_from() {
+                            steam::LaunchedFrom::Steam => false,
+                            steam::LaunchedFrom::Independent => true
+                        },
+
+                        connect_clicked => GeneralAppMsg::OpenImportInstallation
+                    },
+
+                    gtk::Button {
+                        #[watch]
                         set_label: &tr!("repair-game"),
 
                         connect_clicked => GeneralAppMsg::RepairGame
@@ -363,10 +499,36 @@ impl SimpleAsyncComponent for GeneralApp {
                 }
             },
 
+            #[local_ref]
+            add = profiles_group -> adw::PreferencesGroup {
+                #[watch]
+                set_title: &tr!("installation-profiles"),
+
+                #[watch]
+                set_description: Some(&tr!("installation-profiles-description")),
+
+                #[local_ref]
+                profile_name_entry -> adw::EntryRow {
+                    #[watch]
+                    set_title: &tr!("name"),
+
+                    add_suffix = &gtk::Button {
+                        set_icon_name: "list-add-symbolic",
+                        add_css_class: "flat",
+
+                        set_valign: gtk::Align::Center,
+
+                        connect_clicked => GeneralAppMsg::AddInstallationProfile
+                    }
+                }
+            },
+
             add = &adw::PreferencesGroup {
+                #[watch]
                 set_title: &tr!("status"),
 
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("game-version"),
 
                     add_suffix = &gtk::Label {
@@ -404,12 +566,14 @@ impl SimpleAsyncComponent for GeneralApp {
 
                                 VersionDiff::Predownload { current, latest, .. } => tr!("game-predownload-available", {
                                     "old" = current.to_string(),
-                                    "new" = latest.to_string()
+                                    "new" = latest.to_string(),
+                                    "size" = format_size(diff.downloaded_size().unwrap_or(0), SizeUnits::Binary)
                                 }),
 
                                 VersionDiff::Diff { current, latest, .. } => tr!("game-update-available", {
                                     "old" = current.to_string(),
-                                    "new" = latest.to_string()
+                                    "new" = latest.to_string(),
+                                    "size" = format_size(diff.downloaded_size().unwrap_or(0), SizeUnits::Binary)
                                 }),
 
                                 VersionDiff::Outdated { latest, ..} => tr!("game-outdated", {
@@ -426,10 +590,14 @@ impl SimpleAsyncComponent for GeneralApp {
             },
 
             add = &adw::PreferencesGroup {
+                #[watch]
                 set_title: &tr!("options"),
 
                 adw::ComboRow {
+                    #[watch]
                     set_title: &tr!("launcher-behavior"),
+
+                    #[watch]
                     set_subtitle: &tr!("launcher-behavior-description"),
 
                     set_model: Some(&gtk::StringList::new(&[
@@ -438,14 +606,14 @@ impl SimpleAsyncComponent for GeneralApp {
                         &tr!("close", { "form" = "verb" }),
                     ])),
 
-                    set_selected: match CONFIG.launcher.behavior {
+                    set_selected: match model.ctx.config().launcher.behavior {
                         LauncherBehavior::Nothing => 0,
                         LauncherBehavior::Hide    => 1,
                         LauncherBehavior::Close   => 2
                     },
 
-                    connect_selected_notify => |row| {
-                        if is_ready() {
+                    connect_selected_notify[ctx = model.ctx.clone()] => move |row| {
+                        if ctx.is_ready() {
                             if let Ok(mut config) = Config::get() {
                                 config.launcher.behavior = [
                                     LauncherBehavior::Nothing,
@@ -453,26 +621,29 @@ impl SimpleAsyncComponent for GeneralApp {
                                     LauncherBehavior::Close
                                 ][row.selected() as usize];
 
-                                Config::update(config);
+                                ctx.update_config(config);
                             }
                         }
                     }
                 },
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("telemetry-behavior"),
+
+                    #[watch]
                     set_subtitle: &tr!("telemetry-behavior-description"),
 
                     add_suffix = &gtk::Switch {
                         set_valign: gtk::Align::Center,
 
-                        set_state: CONFIG.game.telemetry_ignored,
+                        set_state: model.ctx.config().game.telemetry_ignored,
 
-                        connect_state_notify => |switch| {
-                            if is_ready() {
+                        connect_state_notify[ctx = model.ctx.clone()] => move |switch| {
+                            if ctx.is_ready() {
                                 if let Ok(mut config) = Config::get() {
                                     config.game.telemetry_ignored = switch.state();
 
-                                    Config::update(config);
+                                    ctx.update_config(config);
                                 }
                             }
                         }
@@ -482,7 +653,10 @@ impl SimpleAsyncComponent for GeneralApp {
 
             add = &adw::PreferencesGroup {
                 adw::ActionRow {
+                    #[watch]
                     set_title: &tr!("components"),
+
+                    #[watch]
                     set_subtitle: &tr!("components-description"),
 
                     add_suffix = &gtk::Image {
@@ -494,7 +668,72 @@ impl SimpleAsyncComponent for GeneralApp {
                     connect_activated => GeneralAppMsg::OpenComponentsPage
                 },
 
+                adw::ActionRow {
+                    #[watch]
+                    set_title: &tr!("config-history"),
+
+                    #[watch]
+                    set_subtitle: &tr!("config-history-description"),
+
+                    add_suffix = &gtk::Image {
+                        set_icon_name: Some("go-next-symbolic")
+                    },
+
+                    set_activatable: true,
+
+                    connect_activated => GeneralAppMsg::OpenConfigHistoryPage
+                },
+
+                adw::ActionRow {
+                    #[watch]
+                    set_title: &tr!("check-config"),
+
+                    #[watch]
+                    set_subtitle: &tr!("check-config-description"),
+
+                    add_suffix = &gtk::Image {
+                        set_icon_name: Some("go-next-symbolic")
+                    },
+
+                    set_activatable: true,
+
+                    connect_activated => GeneralAppMsg::OpenConfigCheckPage
+                },
+
+                adw::ActionRow {
+                    #[watch]
+                    set_title: &tr!("check-system"),
+
+                    #[watch]
+                    set_subtitle: &tr!("check-system-description"),
+
+                    add_suffix = &gtk::Image {
+                        set_icon_name: Some("go-next-symbolic")
+                    },
+
+                    set_activatable: true,
+
+                    connect_activated => GeneralAppMsg::OpenSystemCheckPage
+                },
+
+                adw::ActionRow {
+                    #[watch]
+                    set_title: &tr!("storage"),
+
+                    #[watch]
+                    set_subtitle: &tr!("storage-description"),
+
+                    add_suffix = &gtk::Image {
+                        set_icon_name: Some("go-next-symbolic")
+                    },
+
+                    set_activatable: true,
+
+                    connect_activated => GeneralAppMsg::OpenStoragePage
+                },
+
                 adw::ExpanderRow {
+                    #[watch]
                     set_title: &tr!("wine-tools"),
 
                     set_visible: match steam::launched_from() {
@@ -503,7 +742,9 @@ impl SimpleAsyncComponent for GeneralApp {
                     },
 
                     add_row = &adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("command-line"),
+
                         set_subtitle: "wineconsole",
 
                         set_activatable: true,
@@ -512,7 +753,9 @@ impl SimpleAsyncComponent for GeneralApp {
                     },
 
                     add_row = &adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("registry-editor"),
+
                         set_subtitle: "regedit",
 
                         set_activatable: true,
@@ -521,7 +764,9 @@ impl SimpleAsyncComponent for GeneralApp {
                     },
 
                     add_row = &adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("explorer"),
+
                         set_subtitle: "explorer",
 
                         set_activatable: true,
@@ -530,7 +775,9 @@ impl SimpleAsyncComponent for GeneralApp {
                     },
 
                     add_row = &adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("task-manager"),
+
                         set_subtitle: "taskmgr",
 
                         set_activatable: true,
@@ -539,7 +786,9 @@ impl SimpleAsyncComponent for GeneralApp {
                     },
 
                     add_row = &adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("configuration"),
+
                         set_subtitle: "winecfg",
 
                         set_activatable: true,
@@ -548,7 +797,9 @@ impl SimpleAsyncComponent for GeneralApp {
                     },
 
                     add_row = &adw::ActionRow {
+                        #[watch]
                         set_title: &tr!("debugger"),
+
                         set_subtitle: "start winedbg",
 
                         set_activatable: true,
@@ -560,17 +811,39 @@ impl SimpleAsyncComponent for GeneralApp {
         },
 
         #[local_ref]
-        components_page -> gtk::Box {}
+        components_page -> gtk::Box {},
+
+        #[local_ref]
+        config_history_page -> gtk::Box {},
+
+        #[local_ref]
+        config_check_page -> gtk::Box {},
+
+        #[local_ref]
+        system_check_page -> gtk::Box {},
+
+        #[local_ref]
+        storage_page -> gtk::Box {},
+
+        #[local_ref]
+        voice_queue_page -> gtk::Box {},
+
+        #[local_ref]
+        background_page -> gtk::Box {}
     }
 
     async fn init(
-        _init: Self::Init,
+        ctx: Self::Init,
         root: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
         tracing::info!("Initializing general settings");
 
+        let config = ctx.config();
+
         let mut model = Self {
+            ctx: ctx.clone(),
+
             voice_packages: AsyncFactoryVecDeque::builder()
                 .launch_default()
                 .forward(sender.input_sender(), std::convert::identity),
@@ -579,27 +852,71 @@ impl SimpleAsyncComponent for GeneralApp {
                 .launch(())
                 .detach(),
 
+            import_installation: ImportInstallationApp::builder()
+                .launch(())
+                .forward(sender.input_sender(), |msg| match msg {
+                    ImportInstallationAppOutput::Imported { .. } => GeneralAppMsg::InstallationImported
+                }),
+
             components_page: ComponentsPage::builder()
+                .launch(ctx.clone())
+                .forward(sender.input_sender(), std::convert::identity),
+
+            config_history_page: ConfigHistoryPage::builder()
                 .launch(())
                 .forward(sender.input_sender(), std::convert::identity),
 
+            config_check_page: ConfigCheckPage::builder()
+                .launch(ctx.clone())
+                .forward(sender.input_sender(), std::convert::identity),
+
+            system_check_page: SystemCheckPage::builder()
+                .launch(ctx.clone())
+                .forward(sender.input_sender(), std::convert::identity),
+
+            storage_page: StoragePage::builder()
+                .launch(ctx.clone())
+                .forward(sender.input_sender(), std::convert::identity),
+
+            voice_queue_page: VoiceQueuePage::builder()
+                .launch(ctx.clone())
+                .forward(sender.input_sender(), std::convert::identity),
+
+            background_page: BackgroundPage::builder()
+                .launch(ctx.clone())
+                .forward(sender.input_sender(), std::convert::identity),
+
+            profile_name_entry: adw::EntryRow::new(),
+            profiles_group: adw::PreferencesGroup::new(),
+            profiles_rows: Vec::new(),
+
             game_diff: None,
-            style: CONFIG.launcher.style,
+            voice_queue_size: 0,
+            edition: config.launcher.edition,
+            style: config.launcher.style,
             languages: SUPPORTED_LANGUAGES.iter().map(|lang| tr!(format_lang(lang).as_str())).collect()
         };
 
-        for package in VoiceLocale::list() {
-            model.voice_packages.guard().push_back((
-                *package,
-                CONFIG.game.voices.iter().any(|voice| VoiceLocale::from_str(voice) == Some(*package))
-            ));
-        }
+        model.load_voice_packages(&config.game.voices);
 
         let voice_packages = model.voice_packages.widget();
         let components_page = model.components_page.widget();
+        let config_history_page = model.config_history_page.widget();
+        let config_check_page = model.config_check_page.widget();
+        let system_check_page = model.system_check_page.widget();
+        let storage_page = model.storage_page.widget();
+        let voice_queue_page = model.voice_queue_page.widget();
+        let background_page = model.background_page.widget();
+
+        let profile_name_entry = &model.profile_name_entry;
+        let profiles_group = &model.profiles_group;
 
         let widgets = view_output!();
 
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| GeneralAppMsg::LanguageChanged);
+
+        sender.input(GeneralAppMsg::ReloadInstallationProfiles);
+
         AsyncComponentParts { model, widgets }
     }
 
@@ -607,65 +924,55 @@ impl SimpleAsyncComponent for GeneralApp {
         tracing::debug!("Called general settings event: {:?}", msg);
 
         match msg {
+            GeneralAppMsg::LanguageChanged => (),
+
             GeneralAppMsg::SetGameDiff(diff) => {
                 self.game_diff = diff;
             }
 
-            #[allow(unused_must_use)]
-            GeneralAppMsg::AddVoicePackage(index) => {
-                if let Some(package) = self.voice_packages.get(index.current_index()) {
-                    if let Ok(mut config) = Config::get() {
-                        if !config.game.voices.iter().any(|voice| VoiceLocale::from_str(voice) == Some(package.locale)) {
-                            config.game.voices.push(package.locale.to_code().to_string());
+            GeneralAppMsg::AddVoicePackage(index) => self.enqueue_voice_package(index, false),
+            GeneralAppMsg::RemoveVoicePackage(index) => self.enqueue_voice_package(index, true),
 
-                            Config::update(config);
-    
-                            sender.output(PreferencesAppMsg::UpdateLauncherState);
-                        }
-                    }
-                }
-            }
+            GeneralAppMsg::VoiceQueueChanged { pending, running } => {
+                let config = self.ctx.config();
 
-            #[allow(unused_must_use)]
-            GeneralAppMsg::RemoveVoicePackage(index) => {
-                if let Some(package) = self.voice_packages.guard().get_mut(index.current_index()) {
-                    if let Ok(mut config) = Config::get() {
-                        package.sensitive = false;
+                let mut guard = self.voice_packages.guard();
 
-                        config.game.voices.retain(|voice| VoiceLocale::from_str(voice) != Some(package.locale));
+                for i in 0..guard.len() {
+                    if let Some(package) = guard.get_mut(i) {
+                        package.queued = pending.iter()
+                            .find(|(locale, _)| *locale == package.locale)
+                            .map(|(_, kind)| *kind);
 
-                        Config::update(config.clone());
+                        package.installed = match package.queued {
+                            Some(VoiceOperationKind::Install | VoiceOperationKind::Update) => true,
+                            Some(VoiceOperationKind::Remove) => false,
 
-                        let package = VoicePackage::with_locale(package.locale, config.launcher.edition).unwrap();
-                        let game_path = config.game.path.for_edition(config.launcher.edition).to_path_buf();
+                            None => config.game.voices.iter().any(|voice| VoiceLocale::from_str(voice) == Some(package.locale))
+                        };
 
-                        if package.is_installed_in(&game_path) {
-                            std::thread::spawn(move || {
-                                if let Err(err) = package.delete_in(game_path) {
-                                    tracing::error!("Failed to delete voice package: {:?}", package.locale());
+                        package.sensitive = running != Some(package.locale);
+                    }
+                }
 
-                                    sender.input(GeneralAppMsg::Toast {
-                                        title: tr!("voice-package-deletion-error"),
-                                        description: Some(err.to_string())
-                                    });
-                                }
+                drop(guard);
 
-                                sender.input(GeneralAppMsg::SetVoicePackageSensitivity(index, true));
-                                sender.output(PreferencesAppMsg::UpdateLauncherState);
-                            });
-                        }
+                self.voice_queue_size = pending.len() + running.is_some() as usize;
+            }
 
-                        else {
-                            sender.input(GeneralAppMsg::SetVoicePackageSensitivity(index, true));
-                        }
-                    }
-                }
+            #[allow(unused_must_use)]
+            GeneralAppMsg::VoiceQueueStarted => {
+                sender.output(PreferencesAppMsg::DisableButtons(true));
             }
 
-            GeneralAppMsg::SetVoicePackageSensitivity(index, sensitive) => {
-                if let Some(package) = self.voice_packages.guard().get_mut(index.current_index()) {
-                    package.sensitive = sensitive;
-                }
+            #[allow(unused_must_use)]
+            GeneralAppMsg::VoiceQueueFinished => {
+                sender.output(PreferencesAppMsg::DisableButtons(false));
+                sender.output(PreferencesAppMsg::UpdateLauncherState);
+            }
+
+            GeneralAppMsg::SetDownloading(state) => {
+                self.voice_queue_page.emit(VoiceQueuePageMsg::SetBlocked(state));
             }
 
             GeneralAppMsg::UpdateDownloadedWine => {
@@ -680,37 +987,101 @@ impl SimpleAsyncComponent for GeneralApp {
                     .unwrap();
             }
 
-            GeneralAppMsg::OpenMigrateInstallation => unsafe {
-                if let Some(window) = crate::ui::main::PREFERENCES_WINDOW.as_ref() {
-                    self.migrate_installation.widget().set_transient_for(Some(window.widget()));
+            GeneralAppMsg::OpenMigrateInstallation => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    self.migrate_installation.widget().set_transient_for(Some(&window));
                 }
 
                 self.migrate_installation.widget().present();
             }
 
+            GeneralAppMsg::OpenImportInstallation => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    self.import_installation.widget().set_transient_for(Some(&window));
+                }
+
+                self.import_installation.widget().present();
+            }
+
+            GeneralAppMsg::InstallationImported => {
+                self.import_installation.widget().close();
+
+                sender.input(GeneralAppMsg::ReloadInstallation);
+
+                // Main window reloads the game installation state
+                sender.output(PreferencesAppMsg::EditionChanged).unwrap();
+            }
+
             GeneralAppMsg::RepairGame => {
                 sender.output(Self::Output::RepairGame).unwrap();
             }
 
-            GeneralAppMsg::OpenMainPage => unsafe {
-                PREFERENCES_WINDOW.as_ref()
-                    .unwrap_unchecked()
-                    .widget()
-                    .close_subpage();
+            GeneralAppMsg::OpenMainPage => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.close_subpage();
+                }
+            }
+
+            GeneralAppMsg::OpenComponentsPage => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.components_page.widget());
+                }
+            }
+
+            GeneralAppMsg::OpenConfigHistoryPage => {
+                self.config_history_page.emit(ConfigHistoryPageMsg::Reload);
+
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.config_history_page.widget());
+                }
+            }
+
+            GeneralAppMsg::OpenConfigCheckPage => {
+                self.config_check_page.emit(ConfigCheckPageMsg::Check);
+
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.config_check_page.widget());
+                }
+            }
+
+            GeneralAppMsg::OpenSystemCheckPage => {
+                self.system_check_page.emit(SystemCheckPageMsg::Check);
+
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.system_check_page.widget());
+                }
+            }
+
+            GeneralAppMsg::OpenStoragePage => {
+                self.storage_page.emit(StoragePageMsg::Reload);
+
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.storage_page.widget());
+                }
             }
 
-            GeneralAppMsg::OpenComponentsPage => unsafe {
-                PREFERENCES_WINDOW.as_ref()
-                    .unwrap_unchecked()
-                    .widget()
-                    .present_subpage(self.components_page.widget());
+            GeneralAppMsg::OpenVoiceQueuePage => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.voice_queue_page.widget());
+                }
+            }
+
+            GeneralAppMsg::OpenBackgroundPage => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.present_subpage(self.background_page.widget());
+                }
+            }
+
+            #[allow(unused_must_use)]
+            GeneralAppMsg::BackgroundChanged => {
+                sender.output(PreferencesAppMsg::BackgroundChanged);
             }
 
             #[allow(unused_must_use)]
             GeneralAppMsg::UpdateLauncherStyle(style) => {
-                if style == LauncherStyle::Classic && !KEEP_BACKGROUND_FILE.exists() {
-                    if let Err(err) = crate::background::download_background() {
-                        tracing::error!("Failed to download background picture");
+                if style == LauncherStyle::Classic {
+                    if let Err(err) = crate::background::prepare(false) {
+                        tracing::error!("Failed to prepare background picture: {err}");
 
                         sender.input(GeneralAppMsg::Toast {
                             title: tr!("background-downloading-failed"),
@@ -724,7 +1095,7 @@ impl SimpleAsyncComponent for GeneralApp {
                 if let Ok(mut config) = Config::get() {
                     config.launcher.style = style;
 
-                    Config::update(config);
+                    self.ctx.update_config(config);
                 }
 
                 self.style = style;
@@ -732,8 +1103,42 @@ impl SimpleAsyncComponent for GeneralApp {
                 sender.output(Self::Output::SetLauncherStyle(style));
             }
 
+            #[allow(unused_must_use)]
+            GeneralAppMsg::SetEdition(edition) => {
+                if let Ok(mut config) = Config::get() {
+                    if config.launcher.edition != edition {
+                        tracing::info!("Switching game edition to {edition:?}");
+
+                        config.launcher.edition = edition;
+
+                        self.ctx.update_config(config);
+
+                        self.edition = edition;
+
+                        sender.output(PreferencesAppMsg::EditionChanged);
+                    }
+                }
+            }
+
+            GeneralAppMsg::ReloadInstallation => {
+                let config = self.ctx.config();
+
+                self.edition = config.launcher.edition;
+
+                // Pending operations were planned for the previous installation
+                self.voice_queue_page.emit(VoiceQueuePageMsg::Clear);
+
+                self.voice_packages.guard().clear();
+
+                self.load_voice_packages(&config.game.voices);
+
+                // Selected components are stored in the installation profile
+                sender.input(GeneralAppMsg::UpdateDownloadedWine);
+                sender.input(GeneralAppMsg::UpdateDownloadedDxvk);
+            }
+
             GeneralAppMsg::WineOpen(executable) => {
-                let config = Config::get().unwrap_or_else(|_| CONFIG.clone());
+                let config = self.ctx.config();
 
                 if let Ok(Some(wine)) = config.get_selected_wine() {
                     let result = wine
@@ -756,6 +1161,81 @@ impl SimpleAsyncComponent for GeneralApp {
                 }
             }
 
+            GeneralAppMsg::ReloadInstallationProfiles => {
+                for row in self.profiles_rows.drain(..) {
+                    self.profiles_group.remove(&row);
+                }
+
+                let mut profiles = installation_profiles::list()
+                    .map(|profiles| profiles.into_iter().collect::<Vec<_>>())
+                    .unwrap_or_default();
+
+                profiles.sort_by(|a, b| a.0.cmp(&b.0));
+
+                let current = installation_profiles::get_current().unwrap_or_default();
+
+                for (name, profile) in profiles {
+                    let row = adw::ActionRow::new();
+
+                    row.set_title(&name);
+                    row.set_subtitle(&profile.game_path.to_string_lossy());
+
+                    if current.as_ref() == Some(&name) {
+                        row.add_prefix(&gtk::Image::from_icon_name("emblem-ok-symbolic"));
+                    }
+
+                    let button = gtk::Button::new();
+
+                    button.set_icon_name("user-trash-symbolic");
+                    button.add_css_class("flat");
+                    button.set_valign(gtk::Align::Center);
+                    button.set_tooltip_text(Some(&tr!("delete-installation-profile")));
+
+                    button.connect_clicked(gtk::glib::clone!(@strong sender => move |_| {
+                        sender.input(GeneralAppMsg::RemoveInstallationProfile(name.clone()));
+                    }));
+
+                    row.add_suffix(&button);
+
+                    self.profiles_group.add(&row);
+                    self.profiles_rows.push(row);
+                }
+            }
+
+            #[allow(unused_must_use)]
+            GeneralAppMsg::AddInstallationProfile => {
+                let name = self.profile_name_entry.text().trim().to_string();
+
+                if !name.is_empty() {
+                    if let Err(err) = crate::config_history::flush().and_then(|_| installation_profiles::save_current(&name)) {
+                        sender.input(GeneralAppMsg::Toast {
+                            title: tr!("installation-profile-save-failed"),
+                            description: Some(err.to_string())
+                        });
+                    }
+
+                    else {
+                        self.profile_name_entry.set_text("");
+                    }
+
+                    sender.input(GeneralAppMsg::ReloadInstallationProfiles);
+                    sender.output(PreferencesAppMsg::InstallationProfilesChanged);
+                }
+            }
+
+            #[allow(unused_must_use)]
+            GeneralAppMsg::RemoveInstallationProfile(name) => {
+                if let Err(err) = installation_profiles::remove(&name) {
+                    sender.input(GeneralAppMsg::Toast {
+                        title: tr!("installation-profile-remove-failed"),
+                        description: Some(err.to_string())
+                    });
+                }
+
+                sender.input(GeneralAppMsg::ReloadInstallationProfiles);
+                sender.output(PreferencesAppMsg::InstallationProfilesChanged);
+            }
+
             #[allow(unused_must_use)]
             GeneralAppMsg::Toast { title, description } => {
                 sender.output(Self::Output::Toast { title, description });
@@ -763,3 +1243,29 @@ impl SimpleAsyncComponent for GeneralApp {
         }
     }
 }
+
+impl GeneralApp {
+    /// Add voiceover's installation or removal to the queue
+    fn enqueue_voice_package(&mut self, index: DynamicIndex, remove: bool) {
+        if let Some(package) = self.voice_packages.guard().get_mut(index.current_index()) {
+            // Unlocked after the operation is planned
+            package.sensitive = false;
+
+            self.voice_queue_page.emit(VoiceQueuePageMsg::Enqueue {
+                locale: package.locale,
+                remove
+            });
+        }
+    }
+
+    fn load_voice_packages(&mut self, voices: &[String]) {
+        let mut guard = self.voice_packages.guard();
+
+        for package in VoiceLocale::list() {
+            guard.push_back((
+                *package,
+                voices.iter().any(|voice| VoiceLocale::from_str(voice) == Some(*package))
+            ));
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use relm4::prelude::*;
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use crate::actions::cleanup::{self, CleanupItem, CleanupKind, CleanupSettings};
+use crate::i18n::{format_size, SizeUnits};
+
+use super::GeneralAppMsg;
+
+use crate::*;
+
+fn kind_title(kind: CleanupKind) -> String {
+    match kind {
+        CleanupKind::OldGameArchive   => tr!("cleanup-old-game-archive"),
+        CleanupKind::GameArchive      => tr!("cleanup-game-archive"),
+        CleanupKind::ComponentArchive => tr!("cleanup-component-archive"),
+        CleanupKind::Cache            => tr!("cleanup-cache"),
+        CleanupKind::DiscordRpcIcons  => tr!("cleanup-discord-rpc-icons"),
+        CleanupKind::WineBuild        => tr!("cleanup-wine-build"),
+        CleanupKind::DxvkBuild        => tr!("cleanup-dxvk-build")
+    }
+}
+
+pub struct StoragePage {
+    ctx: AppContext,
+
+    items_group: adw::PreferencesGroup,
+    items_rows: Vec<adw::ActionRow>,
+
+    items: Vec<CleanupItem>,
+
+    /// Indexes of the items selected for removal
+    selected: Vec<usize>,
+
+    settings: CleanupSettings,
+    busy: bool
+}
+
+#[derive(Debug, Clone)]
+pub enum StoragePageMsg {
+    /// Find launcher-owned files in background
+    Reload,
+    SetItems(Vec<CleanupItem>),
+    SearchFailed(String),
+
+    Select(usize, bool),
+    RemoveSelected,
+    Removed(Result<u64, String>),
+
+    SetAutoCleanup(bool),
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
+}
+
+#[relm4::component(async, pub)]
+impl SimpleAsyncComponent for StoragePage {
+    type Init = AppContext;
+    type Input = StoragePageMsg;
+    type Output = GeneralAppMsg;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            adw::HeaderBar {
+                #[wrap(Some)]
+                set_title_widget = &adw::WindowTitle {
+                    #[watch]
+                    set_title: &tr!("storage")
+                },
+
+                pack_start = &gtk::Button {
+                    set_icon_name: "go-previous-symbolic",
+
+                    connect_clicked[sender] => move |_| {
+                        sender.output(GeneralAppMsg::OpenMainPage).unwrap();
+                    }
+                }
+            },
+
+            adw::PreferencesPage {
+                add = &adw::PreferencesGroup {
+                    adw::ActionRow {
+                        #[watch]
+                        set_title: &tr!("auto-cleanup"),
+
+                        #[watch]
+                        set_subtitle: &tr!("auto-cleanup-description"),
+
+                        add_suffix = &gtk::Switch {
+                            set_valign: gtk::Align::Center,
+                            set_active: model.settings.auto_cleanup,
+
+                            connect_state_notify[sender] => move |switch| {
+                                sender.input(StoragePageMsg::SetAutoCleanup(switch.state()));
+                            }
+                        }
+                    }
+                },
+
+                #[local_ref]
+                add = items_group -> adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("launcher-files"),
+
+                    #[watch]
+                    set_description: Some(&tr!("launcher-files-description", {
+                        "size" = format_size(model.items.iter().map(|item| item.size).sum(), SizeUnits::Binary)
+                    })),
+
+                    #[wrap(Some)]
+                    set_header_suffix = &gtk::Box {
+                        set_spacing: 8,
+
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_valign: gtk::Align::Center,
+                            set_icon_name: "view-refresh-symbolic",
+
+                            #[watch]
+                            set_sensitive: !model.busy,
+
+                            connect_clicked => StoragePageMsg::Reload
+                        },
+
+                        gtk::Button {
+                            add_css_class: "destructive-action",
+                            set_valign: gtk::Align::Center,
+
+                            #[watch]
+                            set_label: &tr!("remove-selected", {
+                                "size" = format_size(model.selected.iter()
+                                    .filter_map(|i| model.items.get(*i))
+                                    .map(|item| item.size)
+                                    .sum(), SizeUnits::Binary)
+                            }),
+
+                            #[watch]
+                            set_sensitive: !model.busy && !model.selected.is_empty(),
+
+                            connect_clicked => StoragePageMsg::RemoveSelected
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn init(
+        ctx: Self::Init,
+        root: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        tracing::info!("Initializing storage page");
+
+        let model = Self {
+            ctx,
+
+            items_group: adw::PreferencesGroup::new(),
+            items_rows: Vec::new(),
+
+            items: Vec::new(),
+            selected: Vec::new(),
+
+            settings: CleanupSettings::get().unwrap_or_default(),
+            busy: false
+        };
+
+        let items_group = &model.items_group;
+
+        let widgets = view_output!();
+
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| StoragePageMsg::LanguageChanged);
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            StoragePageMsg::LanguageChanged => sender.input(StoragePageMsg::SetItems(self.items.clone())),
+
+            StoragePageMsg::Reload => {
+                self.busy = true;
+
+                let config = self.ctx.config();
+
+                std::thread::spawn(move || {
+                    match cleanup::find_items(&config) {
+                        Ok(items) => sender.input(StoragePageMsg::SetItems(items)),
+                        Err(err) => sender.input(StoragePageMsg::SearchFailed(err.to_string()))
+                    }
+                });
+            }
+
+            StoragePageMsg::SetItems(items) => {
+                for row in self.items_rows.drain(..) {
+                    self.items_group.remove(&row);
+                }
+
+                // Only not needed files are selected by default
+                self.selected = items.iter()
+                    .enumerate()
+                    .filter(|(_, item)| !item.in_progress)
+                    .filter(|(_, item)| matches!(item.kind, CleanupKind::OldGameArchive | CleanupKind::ComponentArchive))
+                    .map(|(i, _)| i)
+                    .collect();
+
+                for (i, item) in items.iter().enumerate() {
+                    let row = adw::ActionRow::new();
+
+                    row.set_title(&kind_title(item.kind));
+                    row.set_subtitle(&item.path.to_string_lossy());
+
+                    let check = gtk::CheckButton::new();
+
+                    check.set_valign(gtk::Align::Center);
+                    check.set_active(self.selected.contains(&i));
+
+                    check.connect_toggled(gtk::glib::clone!(@strong sender => move |check| {
+                        sender.input(StoragePageMsg::Select(i, check.is_active()));
+                    }));
+
+                    row.add_prefix(&check);
+                    row.set_activatable_widget(Some(&check));
+
+                    let label = if item.in_progress {
+                        tr!("downloading")
+                    } else {
+                        format_size(item.size, SizeUnits::Binary)
+                    };
+
+                    row.add_suffix(&gtk::Label::new(Some(&label)));
+
+                    // Never remove files which are being downloaded
+                    row.set_sensitive(!item.in_progress);
+
+                    self.items_group.add(&row);
+                    self.items_rows.push(row);
+                }
+
+                self.items = items;
+                self.busy = false;
+            }
+
+            StoragePageMsg::SearchFailed(err) => {
+                tracing::error!("Failed to find launcher files: {err}");
+
+                self.busy = false;
+
+                sender.output(GeneralAppMsg::Toast {
+                    title: tr!("cleanup-search-failed"),
+                    description: Some(err)
+                }).unwrap();
+            }
+
+            StoragePageMsg::Select(i, selected) => {
+                self.selected.retain(|item| *item != i);
+
+                if selected {
+                    self.selected.push(i);
+                }
+            }
+
+            StoragePageMsg::RemoveSelected => {
+                self.busy = true;
+
+                let items = self.selected.iter()
+                    .filter_map(|i| self.items.get(*i))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                std::thread::spawn(move || {
+                    let result = cleanup::remove(&items)
+                        .map_err(|err| err.to_string());
+
+                    sender.input(StoragePageMsg::Removed(result));
+                });
+            }
+
+            StoragePageMsg::Removed(result) => {
+                match result {
+                    Ok(freed) => {
+                        sender.output(GeneralAppMsg::Toast {
+                            title: tr!("cleanup-finished", {
+                                "size" = format_size(freed, SizeUnits::Binary)
+                            }),
+                            description: None
+                        }).unwrap();
+                    }
+
+                    Err(err) => {
+                        tracing::error!("Failed to remove launcher files: {err}");
+
+                        sender.output(GeneralAppMsg::Toast {
+                            title: tr!("cleanup-failed"),
+                            description: Some(err)
+                        }).unwrap();
+                    }
+                }
+
+                sender.input(StoragePageMsg::Reload);
+            }
+
+            StoragePageMsg::SetAutoCleanup(enabled) => {
+                self.settings.auto_cleanup = enabled;
+
+                if let Err(err) = self.settings.set() {
+                    tracing::error!("Failed to save cleanup settings: {err}");
+
+                    sender.output(GeneralAppMsg::Toast {
+                        title: tr!("cleanup-settings-save-failed"),
+                        description: Some(err.to_string())
+                    }).unwrap();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use relm4::prelude::*;
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use crate::system_check::{self, Check};
+
+use super::GeneralAppMsg;
+
+use crate::*;
+
+pub struct SystemCheckPage {
+    ctx: AppContext,
+
+    checks_group: adw::PreferencesGroup,
+    checks_rows: Vec<adw::ActionRow>,
+
+    checks: Vec<Check>
+}
+
+#[derive(Debug, Clone)]
+pub enum SystemCheckPageMsg {
+    Check,
+    CopyReport,
+
+    /// Copy remedy of the check to the clipboard
+    CopyRemedy(usize),
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
+}
+
+/// Put text to the system clipboard
+pub fn copy_to_clipboard(text: &str) {
+    if let Some(display) = gtk::gdk::Display::default() {
+        display.clipboard().set_text(text);
+    }
+}
+
+#[relm4::component(async, pub)]
+impl SimpleAsyncComponent for SystemCheckPage {
+    type Init = AppContext;
+    type Input = SystemCheckPageMsg;
+    type Output = GeneralAppMsg;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            adw::HeaderBar {
+                #[wrap(Some)]
+                set_title_widget = &adw::WindowTitle {
+                    #[watch]
+                    set_title: &tr!("check-system")
+                },
+
+                pack_start = &gtk::Button {
+                    set_icon_name: "go-previous-symbolic",
+
+                    connect_clicked[sender] => move |_| {
+                        sender.output(GeneralAppMsg::OpenMainPage).unwrap();
+                    }
+                },
+
+                pack_end = &gtk::Button {
+                    set_icon_name: "edit-copy-symbolic",
+
+                    #[watch]
+                    set_tooltip_text: Some(&tr!("copy-report")),
+
+                    connect_clicked => SystemCheckPageMsg::CopyReport
+                }
+            },
+
+            adw::PreferencesPage {
+                #[local_ref]
+                add = checks_group -> adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("system-checks"),
+
+                    #[wrap(Some)]
+                    set_header_suffix = &gtk::Button {
+                        add_css_class: "flat",
+
+                        set_valign: gtk::Align::Center,
+
+                        adw::ButtonContent {
+                            set_icon_name: "view-refresh-symbolic",
+
+                            #[watch]
+                            set_label: &tr!("check-system")
+                        },
+
+                        connect_clicked => SystemCheckPageMsg::Check
+                    }
+                }
+            }
+        }
+    }
+
+    async fn init(
+        ctx: Self::Init,
+        root: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        tracing::info!("Initializing system check page");
+
+        let model = Self {
+            ctx,
+
+            checks_group: adw::PreferencesGroup::new(),
+            checks_rows: Vec::new(),
+
+            checks: Vec::new()
+        };
+
+        let checks_group = &model.checks_group;
+
+        let widgets = view_output!();
+
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| SystemCheckPageMsg::LanguageChanged);
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            SystemCheckPageMsg::LanguageChanged => (),
+
+            SystemCheckPageMsg::Check => {
+                for row in self.checks_rows.drain(..) {
+                    self.checks_group.remove(&row);
+                }
+
+                self.checks = system_check::run(&self.ctx.config());
+
+                for (i, check) in self.checks.iter().enumerate() {
+                    let row = adw::ActionRow::new();
+
+                    row.set_title(&check.name);
+                    row.set_subtitle(&check.description);
+
+                    row.add_prefix(&gtk::Image::from_icon_name(check.severity.icon_name()));
+
+                    if let Some(remedy) = &check.remedy {
+                        let button = gtk::Button::from_icon_name("edit-copy-symbolic");
+
+                        button.add_css_class("flat");
+                        button.set_valign(gtk::Align::Center);
+                        button.set_tooltip_text(Some(remedy));
+
+                        button.connect_clicked(gtk::glib::clone!(@strong sender => move |_| {
+                            sender.input(SystemCheckPageMsg::CopyRemedy(i));
+                        }));
+
+                        row.add_suffix(&button);
+                    }
+
+                    self.checks_group.add(&row);
+                    self.checks_rows.push(row);
+                }
+            }
+
+            SystemCheckPageMsg::CopyReport => {
+                copy_to_clipboard(&system_check::report(&self.checks));
+
+                sender.output(GeneralAppMsg::Toast {
+                    title: tr!("report-copied"),
+                    description: None
+                }).unwrap();
+            }
+
+            SystemCheckPageMsg::CopyRemedy(i) => {
+                if let Some(Some(remedy)) = self.checks.get(i).map(|check| &check.remedy) {
+                    copy_to_clipboard(remedy);
+
+                    sender.output(GeneralAppMsg::Toast {
+                        title: tr!("remedy-copied"),
+                        description: None
+                    }).unwrap();
+                }
+            }
+        }
+    }
+}
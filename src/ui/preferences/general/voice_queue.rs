@@ -0,0 +1,475 @@
+use relm4::prelude::*;
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use crate::actions::voice_queue::{self, VoiceOperation, VoiceOperationKind, VoiceProgress, VoiceQueue};
+use crate::ui::components::*;
+use crate::i18n::{format_size, SizeUnits};
+
+use super::GeneralAppMsg;
+
+use crate::*;
+
+fn operation_title(operation: &VoiceOperation) -> String {
+    let size = match operation.size {
+        Some(size) => format_size(size, SizeUnits::Binary),
+        None => tr!("unknown-size")
+    };
+
+    match operation.kind {
+        VoiceOperationKind::Install => tr!("voice-operation-install", { "size" = size }),
+        VoiceOperationKind::Update  => tr!("voice-operation-update", { "size" = size }),
+        VoiceOperationKind::Remove  => tr!("voice-operation-remove", { "size" = size })
+    }
+}
+
+fn locale_title(locale: VoiceLocale) -> String {
+    tr!(&locale.to_name().to_ascii_lowercase())
+}
+
+pub struct VoiceQueuePage {
+    ctx: AppContext,
+
+    operations_group: adw::PreferencesGroup,
+    operations_rows: Vec<adw::ActionRow>,
+
+    progress_bar: AsyncController<ProgressBar>,
+
+    queue: VoiceQueue,
+
+    /// Operation which is being processed now
+    running: Option<VoiceOperation>,
+
+    /// Amount of operations which are being planned in background
+    planning: usize,
+
+    /// Main window is downloading or changing the game files, so the queue can't be started
+    blocked: bool,
+
+    /// Amount of processed operations and work they did since the queue was started
+    processed: usize,
+    done: u64
+}
+
+#[derive(Debug, Clone)]
+pub enum VoiceQueuePageMsg {
+    /// Plan voiceover's installation or removal and add it to the queue
+    Enqueue {
+        locale: VoiceLocale,
+        remove: bool
+    },
+
+    /// Add updates of all the selected voiceovers to the queue
+    EnqueueUpdates,
+
+    Planned(Result<Vec<VoiceOperation>, String>),
+
+    MoveUp(usize),
+    MoveDown(usize),
+    Cancel(usize),
+
+    /// Remove all the pending operations, e.g. after the game installation was changed
+    Clear,
+
+    /// Block the queue from being started while the main window is downloading something
+    SetBlocked(bool),
+
+    Start,
+
+    /// Progress of the running operation
+    Progress(VoiceProgress),
+
+    /// Running operation finished, with an error if it failed
+    Finished(Option<String>),
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
+}
+
+impl VoiceQueuePage {
+    fn render(&mut self, sender: &AsyncComponentSender<Self>) {
+        for row in self.operations_rows.drain(..) {
+            self.operations_group.remove(&row);
+        }
+
+        if let Some(operation) = &self.running {
+            let row = adw::ActionRow::new();
+
+            row.set_title(&locale_title(operation.locale));
+            row.set_subtitle(&operation_title(operation));
+
+            let spinner = gtk::Spinner::new();
+
+            spinner.start();
+
+            row.add_prefix(&spinner);
+
+            self.operations_group.add(&row);
+            self.operations_rows.push(row);
+        }
+
+        let len = self.queue.operations().len();
+
+        for (i, operation) in self.queue.operations().iter().enumerate() {
+            let row = adw::ActionRow::new();
+
+            row.set_title(&locale_title(operation.locale));
+            row.set_subtitle(&operation_title(operation));
+
+            let buttons = [
+                ("go-up-symbolic", i > 0, VoiceQueuePageMsg::MoveUp(i)),
+                ("go-down-symbolic", i + 1 < len, VoiceQueuePageMsg::MoveDown(i)),
+                ("process-stop-symbolic", true, VoiceQueuePageMsg::Cancel(i))
+            ];
+
+            for (icon, sensitive, msg) in buttons {
+                let button = gtk::Button::from_icon_name(icon);
+
+                button.add_css_class("flat");
+                button.set_valign(gtk::Align::Center);
+                button.set_sensitive(sensitive);
+
+                button.connect_clicked(gtk::glib::clone!(@strong sender => move |_| {
+                    sender.input(msg.clone());
+                }));
+
+                row.add_suffix(&button);
+            }
+
+            self.operations_group.add(&row);
+            self.operations_rows.push(row);
+        }
+
+        let pending = self.queue.operations().iter()
+            .map(|operation| (operation.locale, operation.kind))
+            .collect();
+
+        sender.output(GeneralAppMsg::VoiceQueueChanged {
+            pending,
+            running: self.running.as_ref().map(|operation| operation.locale)
+        }).unwrap();
+    }
+
+    fn update_progress(&self, progress: Option<VoiceProgress>) {
+        let total = self.done
+            + self.running.as_ref().map(VoiceOperation::work_size).unwrap_or(0)
+            + self.queue.work_size();
+
+        let current = match (&self.running, progress) {
+            (Some(operation), Some(progress)) => operation.work_done(progress),
+            _ => 0
+        };
+
+        self.progress_bar.emit(ProgressBarMsg::UpdateProgress(self.done + current, total.max(1)));
+
+        if let Some(operation) = &self.running {
+            self.progress_bar.emit(ProgressBarMsg::UpdateCaption(Some(tr!("voice-queue-progress", {
+                "current" = self.processed + 1,
+                "total" = self.processed + 1 + self.queue.operations().len(),
+                "voice" = locale_title(operation.locale)
+            }))));
+        }
+    }
+
+    /// Start processing the next operation, or finish the queue if it's empty
+    fn next(&mut self, sender: &AsyncComponentSender<Self>) {
+        self.running = self.queue.pop_front();
+
+        match self.running.clone() {
+            Some(operation) => {
+                tracing::info!("Processing voiceover operation: {:?} {:?}", operation.kind, operation.locale);
+
+                self.update_progress(None);
+
+                let sender = sender.clone();
+
+                std::thread::spawn(move || {
+                    let result = voice_queue::process(&operation, gtk::glib::clone!(@strong sender => move |progress| {
+                        sender.input(VoiceQueuePageMsg::Progress(progress));
+                    }));
+
+                    sender.input(VoiceQueuePageMsg::Finished(result.err().map(|err| err.to_string())));
+                });
+            }
+
+            None => {
+                tracing::info!("Voiceovers queue finished");
+
+                self.processed = 0;
+                self.done = 0;
+
+                self.progress_bar.emit(ProgressBarMsg::Reset);
+
+                sender.output(GeneralAppMsg::VoiceQueueFinished).unwrap();
+            }
+        }
+
+        self.render(sender);
+    }
+}
+
+#[relm4::component(async, pub)]
+impl SimpleAsyncComponent for VoiceQueuePage {
+    type Init = AppContext;
+    type Input = VoiceQueuePageMsg;
+    type Output = GeneralAppMsg;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            adw::HeaderBar {
+                #[wrap(Some)]
+                set_title_widget = &adw::WindowTitle {
+                    #[watch]
+                    set_title: &tr!("voice-queue")
+                },
+
+                pack_start = &gtk::Button {
+                    set_icon_name: "go-previous-symbolic",
+
+                    connect_clicked[sender] => move |_| {
+                        sender.output(GeneralAppMsg::OpenMainPage).unwrap();
+                    }
+                }
+            },
+
+            adw::PreferencesPage {
+                #[local_ref]
+                add = operations_group -> adw::PreferencesGroup {
+                    #[watch]
+                    set_title: &tr!("voice-queue"),
+
+                    #[watch]
+                    set_description: Some(&tr!("voice-queue-download-size", {
+                        "size" = format_size(model.queue.download_size(), SizeUnits::Binary)
+                    })),
+
+                    #[wrap(Some)]
+                    set_header_suffix = &gtk::Box {
+                        set_spacing: 8,
+
+                        gtk::Button {
+                            add_css_class: "flat",
+                            set_valign: gtk::Align::Center,
+
+                            #[watch]
+                            set_label: &tr!("queue-updates"),
+
+                            #[watch]
+                            set_sensitive: model.planning == 0,
+
+                            connect_clicked => VoiceQueuePageMsg::EnqueueUpdates
+                        },
+
+                        gtk::Button {
+                            add_css_class: "suggested-action",
+                            set_valign: gtk::Align::Center,
+
+                            #[watch]
+                            set_label: &tr!("start-queue"),
+
+                            #[watch]
+                            set_sensitive: model.running.is_none() && model.planning == 0 && !model.blocked && !model.queue.is_empty(),
+
+                            #[watch]
+                            set_tooltip_text: model.blocked.then(|| tr!("voice-queue-blocked")).as_deref(),
+
+                            connect_clicked => VoiceQueuePageMsg::Start
+                        }
+                    }
+                },
+
+                add = &adw::PreferencesGroup {
+                    #[watch]
+                    set_visible: model.running.is_some(),
+
+                    add = model.progress_bar.widget(),
+                }
+            }
+        }
+    }
+
+    async fn init(
+        ctx: Self::Init,
+        root: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        tracing::info!("Initializing voiceovers queue page");
+
+        let model = Self {
+            ctx,
+
+            operations_group: adw::PreferencesGroup::new(),
+            operations_rows: Vec::new(),
+
+            progress_bar: ProgressBar::builder()
+                .launch(ProgressBarInit {
+                    caption: None,
+                    display_progress: true,
+                    display_fraction: true,
+                    visible: true
+                })
+                .detach(),
+
+            queue: VoiceQueue::default(),
+            running: None,
+            planning: 0,
+            blocked: false,
+
+            processed: 0,
+            done: 0
+        };
+
+        let operations_group = &model.operations_group;
+
+        let widgets = view_output!();
+
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| VoiceQueuePageMsg::LanguageChanged);
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            VoiceQueuePageMsg::LanguageChanged => self.render(&sender),
+
+            VoiceQueuePageMsg::Enqueue { locale, remove } => {
+                self.planning += 1;
+
+                let config = self.ctx.config();
+
+                std::thread::spawn(move || {
+                    let result = if remove {
+                        Ok(vec![VoiceOperation::remove(&config, locale)])
+                    } else {
+                        VoiceOperation::install(&config, locale)
+                            .map(|operation| operation.into_iter().collect())
+                            .map_err(|err| err.to_string())
+                    };
+
+                    sender.input(VoiceQueuePageMsg::Planned(result));
+                });
+            }
+
+            VoiceQueuePageMsg::EnqueueUpdates => {
+                self.planning += 1;
+
+                let config = self.ctx.config();
+
+                std::thread::spawn(move || {
+                    let mut operations = Vec::new();
+
+                    for locale in config.game.voices.iter().filter_map(|voice| VoiceLocale::from_str(voice)) {
+                        match VoiceOperation::install(&config, locale) {
+                            Ok(Some(operation)) => operations.push(operation),
+                            Ok(None) => (),
+
+                            Err(err) => {
+                                sender.input(VoiceQueuePageMsg::Planned(Err(err.to_string())));
+
+                                return;
+                            }
+                        }
+                    }
+
+                    sender.input(VoiceQueuePageMsg::Planned(Ok(operations)));
+                });
+            }
+
+            VoiceQueuePageMsg::Planned(result) => {
+                self.planning -= 1;
+
+                match result {
+                    Ok(operations) => {
+                        for operation in operations {
+                            // The voiceover is being processed already
+                            if self.running.as_ref().map(|running| running.locale) == Some(operation.locale) {
+                                continue;
+                            }
+
+                            self.queue.push(operation);
+                        }
+                    }
+
+                    Err(err) => {
+                        tracing::error!("Failed to plan voiceover operation: {err}");
+
+                        sender.output(GeneralAppMsg::Toast {
+                            title: tr!("voice-operation-planning-failed"),
+                            description: Some(err)
+                        }).unwrap();
+                    }
+                }
+
+                if self.running.is_some() {
+                    self.update_progress(None);
+                }
+
+                self.render(&sender);
+            }
+
+            VoiceQueuePageMsg::MoveUp(i) => {
+                self.queue.move_up(i);
+
+                self.render(&sender);
+            }
+
+            VoiceQueuePageMsg::MoveDown(i) => {
+                self.queue.move_down(i);
+
+                self.render(&sender);
+            }
+
+            VoiceQueuePageMsg::Cancel(i) => {
+                self.queue.cancel(i);
+
+                self.render(&sender);
+            }
+
+            VoiceQueuePageMsg::Clear => {
+                self.queue.clear();
+
+                self.render(&sender);
+            }
+
+            VoiceQueuePageMsg::SetBlocked(blocked) => self.blocked = blocked,
+
+            VoiceQueuePageMsg::Start => {
+                if self.running.is_none() && !self.blocked {
+                    sender.output(GeneralAppMsg::VoiceQueueStarted).unwrap();
+
+                    self.next(&sender);
+                }
+            }
+
+            VoiceQueuePageMsg::Progress(progress) => self.update_progress(Some(progress)),
+
+            VoiceQueuePageMsg::Finished(error) => {
+                if let Some(operation) = self.running.take() {
+                    self.processed += 1;
+                    self.done += operation.work_size();
+
+                    if let Some(error) = error {
+                        tracing::error!("Voiceover operation failed: {error}");
+
+                        let title = match operation.kind {
+                            VoiceOperationKind::Remove => tr!("voice-package-deletion-error"),
+
+                            _ => tr!("voice-operation-failed", {
+                                "voice" = locale_title(operation.locale)
+                            })
+                        };
+
+                        sender.output(GeneralAppMsg::Toast {
+                            title,
+                            description: Some(error)
+                        }).unwrap();
+                    }
+                }
+
+                self.next(&sender);
+            }
+        }
+    }
+}
@@ -5,18 +5,17 @@ use adw::prelude::*;
 
 use anime_launcher_sdk::anime_game_core::genshin::prelude::*;
 
-use anime_launcher_sdk::config::ConfigExt;
-use anime_launcher_sdk::genshin::config::Config;
 use anime_launcher_sdk::genshin::config::schema::launcher::LauncherStyle;
 
 use crate::tr;
+use crate::context::AppContext;
 
 use super::general::*;
 use super::enhancements::*;
 
-pub static mut PREFERENCES_WINDOW: Option<adw::PreferencesWindow> = None;
-
 pub struct PreferencesApp {
+    ctx: AppContext,
+
     general: AsyncController<GeneralApp>,
     enhancements: AsyncController<EnhancementsApp>
 }
@@ -31,21 +30,53 @@ pub enum PreferencesAppMsg {
     UpdateLauncherState,
     RepairGame,
 
+    /// Disable main window's buttons, e.g. while voiceovers queue is processed
+    DisableButtons(bool),
+
+    /// Main window started or finished downloading something
+    SetDownloading(bool),
+
+    /// Reload game sessions list in the game settings page
+    ReloadSessions,
+
+    /// Game sessions list was changed in the game settings page
+    SessionsChanged,
+
+    /// Reload installation profiles list in the general settings page
+    ReloadInstallationProfiles,
+
+    /// Installation profiles list was changed in the general settings page
+    InstallationProfilesChanged,
+
+    /// Game edition was changed in the general settings page
+    EditionChanged,
+
+    /// Background settings or current session's background were changed
+    BackgroundChanged,
+
+    /// Update settings related to the game installation after it was changed
+    ReloadInstallation,
+
     Toast {
         title: String,
         description: Option<String>
-    }
+    },
+
+    /// Re-render translated labels after the launcher language was changed
+    LanguageChanged
 }
 
 #[relm4::component(async, pub)]
 impl SimpleAsyncComponent for PreferencesApp {
-    type Init = gtk::Window;
+    type Init = AppContext;
     type Input = PreferencesAppMsg;
     type Output = crate::ui::main::AppMsg;
 
     view! {
         preferences_window = adw::PreferencesWindow {
+            #[watch]
             set_title: Some(&tr!("preferences")),
+
             set_default_size: (700, 560),
 
             set_hide_on_close: true,
@@ -56,42 +87,49 @@ impl SimpleAsyncComponent for PreferencesApp {
             add = model.enhancements.widget(),
 
             connect_close_request[sender] => move |_| {
-                if let Err(err) = Config::flush() {
+                if let Err(err) = crate::config_history::flush() {
                     sender.input(PreferencesAppMsg::Toast {
                         title: tr!("config-update-error"),
                         description: Some(err.to_string())
                     });
                 }
 
+                // Keep changed config in the history
+                else if let Err(err) = crate::config_history::backup() {
+                    tracing::warn!("Failed to backup config: {err}");
+                }
+
                 gtk::glib::Propagation::Proceed
             }
         }
     }
 
     async fn init(
-        parent: Self::Init,
+        ctx: Self::Init,
         root: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
         tracing::info!("Initializing preferences window");
 
+        ctx.set_preferences_window(root.clone());
+
         let model = Self {
+            ctx: ctx.clone(),
+
             general: GeneralApp::builder()
-                .launch(())
+                .launch(ctx.clone())
                 .forward(sender.input_sender(), std::convert::identity),
 
             enhancements: EnhancementsApp::builder()
-                .launch(())
+                .launch(ctx.clone())
                 .forward(sender.input_sender(), std::convert::identity)
         };
 
         let widgets = view_output!();
 
-        widgets.preferences_window.set_transient_for(Some(&parent));
+        crate::i18n::LANG.subscribe(sender.input_sender(), |_| PreferencesAppMsg::LanguageChanged);
 
-        unsafe {
-            PREFERENCES_WINDOW = Some(widgets.preferences_window.clone());
-        }
+        widgets.preferences_window.set_transient_for(ctx.main_window().as_ref());
 
         model.enhancements.emit(EnhancementsAppMsg::SetGamescopeParent);
 
@@ -105,6 +143,8 @@ impl SimpleAsyncComponent for PreferencesApp {
         tracing::debug!("Called preferences window event: {:?}", msg);
 
         match msg {
+            PreferencesAppMsg::LanguageChanged => (),
+
             PreferencesAppMsg::SetGameDiff(diff) => {
                 self.general.emit(GeneralAppMsg::SetGameDiff(diff));
             }
@@ -123,13 +163,56 @@ impl SimpleAsyncComponent for PreferencesApp {
             }
 
             #[allow(unused_must_use)]
-            PreferencesAppMsg::RepairGame => unsafe {
-                PREFERENCES_WINDOW.as_ref().unwrap_unchecked().close();
+            PreferencesAppMsg::DisableButtons(state) => {
+                sender.output(Self::Output::DisableButtons(state));
+            }
+
+            PreferencesAppMsg::ReloadSessions => {
+                self.enhancements.emit(EnhancementsAppMsg::ReloadSessions);
+            }
+
+            #[allow(unused_must_use)]
+            PreferencesAppMsg::SessionsChanged => {
+                sender.output(Self::Output::UpdateSessions);
+            }
+
+            PreferencesAppMsg::ReloadInstallationProfiles => {
+                self.general.emit(GeneralAppMsg::ReloadInstallationProfiles);
+            }
+
+            #[allow(unused_must_use)]
+            PreferencesAppMsg::InstallationProfilesChanged => {
+                sender.output(Self::Output::UpdateProfiles);
+            }
+
+            #[allow(unused_must_use)]
+            PreferencesAppMsg::EditionChanged => {
+                sender.output(Self::Output::ReloadInstallation);
+            }
+
+            #[allow(unused_must_use)]
+            PreferencesAppMsg::BackgroundChanged => {
+                sender.output(Self::Output::UpdateBackground { next: false });
+            }
+
+            PreferencesAppMsg::ReloadInstallation => {
+                self.general.emit(GeneralAppMsg::ReloadInstallation);
+            }
+
+            PreferencesAppMsg::SetDownloading(state) => {
+                self.general.emit(GeneralAppMsg::SetDownloading(state));
+            }
+
+            #[allow(unused_must_use)]
+            PreferencesAppMsg::RepairGame => {
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.close();
+                }
 
                 sender.output(Self::Output::RepairGame);
             }
 
-            PreferencesAppMsg::Toast { title, description } => unsafe {
+            PreferencesAppMsg::Toast { title, description } => {
                 let toast = adw::Toast::new(&title);
 
                 toast.set_timeout(4);
@@ -137,7 +220,7 @@ impl SimpleAsyncComponent for PreferencesApp {
                 if let Some(description) = description {
                     toast.set_button_label(Some(&tr!("details")));
 
-                    let dialog = adw::MessageDialog::new(PREFERENCES_WINDOW.as_ref(), Some(&title), Some(&description));
+                    let dialog = adw::MessageDialog::new(self.ctx.preferences_window().as_ref(), Some(&title), Some(&description));
 
                     dialog.add_response("close", &tr!("close", { "form" = "noun" }));
                     dialog.add_response("save", &tr!("save"));
@@ -155,7 +238,9 @@ impl SimpleAsyncComponent for PreferencesApp {
                     });
                 }
 
-                PREFERENCES_WINDOW.as_ref().unwrap_unchecked().add_toast(toast);
+                if let Some(window) = self.ctx.preferences_window() {
+                    window.add_toast(toast);
+                }
             }
         }
     }
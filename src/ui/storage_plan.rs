@@ -0,0 +1,90 @@
+use gtk::prelude::*;
+use adw::prelude::*;
+
+use crate::actions::storage_plan::*;
+use crate::i18n::{format_size, SizeUnits};
+use crate::tr;
+
+fn format_optional_size(size: Option<u64>) -> String {
+    match size {
+        Some(size) => format_size(size, SizeUnits::Binary),
+        None => tr!("unknown-size")
+    }
+}
+
+fn usage_title(usage: StorageUsage) -> String {
+    match usage {
+        StorageUsage::Temp       => tr!("storage-temp-folder"),
+        StorageUsage::Game       => tr!("storage-game-folder"),
+        StorageUsage::WineBuilds => tr!("storage-wine-builds"),
+        StorageUsage::DxvkBuilds => tr!("storage-dxvk-builds")
+    }
+}
+
+/// Get filesystem's folders and their free space, e.g. to show it as an action row
+pub fn describe_filesystem(filesystem: &PlannedFilesystem) -> (String, String) {
+    let folders = filesystem.folders.iter()
+        .map(|(usage, path)| format!("{}: {}", usage_title(*usage), path.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let space = tr!("storage-plan-space", {
+        "required" = format_size(filesystem.required, SizeUnits::Binary),
+        "available" = format_optional_size(filesystem.available)
+    });
+
+    (folders, space)
+}
+
+/// Get human readable plan description
+pub fn describe(plan: &StoragePlan) -> String {
+    let mut description = tr!("storage-plan-download-size", {
+        "size" = format_optional_size(plan.download_size)
+    });
+
+    if plan.unpacked_size != Some(0) {
+        description += "\n";
+        description += &tr!("storage-plan-unpacked-size", {
+            "size" = format_optional_size(plan.unpacked_size)
+        });
+    }
+
+    for filesystem in &plan.filesystems {
+        let (folders, space) = describe_filesystem(filesystem);
+
+        description += &format!("\n\n{folders}\n{space}");
+    }
+
+    match plan.status() {
+        StorageStatus::NotEnough => description += &format!("\n\n{}", tr!("storage-plan-not-enough")),
+        StorageStatus::Low       => description += &format!("\n\n{}", tr!("storage-plan-low")),
+        StorageStatus::Unknown   => description += &format!("\n\n{}", tr!("storage-plan-unknown")),
+
+        StorageStatus::Enough => ()
+    }
+
+    description
+}
+
+/// Show storage plan before downloading. `download` is called if the user has agreed to continue
+///
+/// Downloading is not possible if there's not enough free space
+pub fn show_dialog(parent: Option<&impl IsA<gtk::Window>>, plan: &StoragePlan, download: impl Fn() + 'static) {
+    let dialog = adw::MessageDialog::new(parent, Some(&tr!("storage-plan")), Some(&describe(plan)));
+
+    dialog.add_response("cancel", &tr!("cancel"));
+    dialog.add_response("download", &tr!("download"));
+
+    match plan.status() {
+        StorageStatus::NotEnough => dialog.set_response_enabled("download", false),
+
+        StorageStatus::Low |
+        StorageStatus::Unknown => dialog.set_response_appearance("download", adw::ResponseAppearance::Destructive),
+
+        StorageStatus::Enough => dialog.set_response_appearance("download", adw::ResponseAppearance::Suggested)
+    }
+
+    dialog.connect_response(Some("download"), move |_, _| download());
+
+    dialog.present();
+}
@@ -0,0 +1,591 @@
+//! Compare keys used by the `tr!` macro with the ones defined in the locales
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Locale which every `tr!` key must be defined in
+const REFERENCE_LOCALE: &str = "en";
+
+/// Keys which are looked up by a computed id (voiceover names)
+/// and can't be found in the sources
+const DYNAMIC_KEYS: &[&str] = &["english", "japanese", "korean", "chinese"];
+
+/// Keys which are not translated to some locales yet
+///
+/// They fall back to English. Remove the key from here when it's translated to every locale
+const UNTRANSLATED_KEYS: &[&str] = &[
+    "auto-cleanup",
+    "auto-cleanup-description",
+    "back",
+    "background",
+    "background-blur",
+    "background-description",
+    "background-file",
+    "background-fit",
+    "background-fit-contain",
+    "background-fit-cover",
+    "background-folder",
+    "background-interval",
+    "background-interval-description",
+    "background-official",
+    "background-rotation",
+    "background-rotation-interval",
+    "background-rotation-launch",
+    "background-settings-save-failed",
+    "background-slideshow",
+    "background-source",
+    "background-source-description",
+    "background-style",
+    "cancel",
+    "check-config",
+    "check-config-description",
+    "check-system",
+    "check-system-description",
+    "cleanup-cache",
+    "cleanup-component-archive",
+    "cleanup-discord-rpc-icons",
+    "cleanup-dxvk-build",
+    "cleanup-failed",
+    "cleanup-finished",
+    "cleanup-game-archive",
+    "cleanup-search-failed",
+    "cleanup-old-game-archive",
+    "cleanup-settings-save-failed",
+    "cleanup-wine-build",
+    "command-copied",
+    "command-copied-description",
+    "config-backup-changes",
+    "config-backup-current",
+    "config-backup-failed",
+    "config-backups",
+    "config-backups-description",
+    "config-broken",
+    "config-broken-description",
+    "config-check-failed",
+    "config-check-passed",
+    "config-fix-create-folder",
+    "config-fix-disable-virtual-desktop",
+    "config-fix-failed",
+    "config-fix-language",
+    "config-fix-repairer-threads",
+    "config-fix-reset-resolution",
+    "config-fix-reset-temp",
+    "config-history",
+    "config-history-description",
+    "config-issue-component-missing",
+    "config-issue-folder-missing",
+    "config-issue-language",
+    "config-issue-not-folder",
+    "config-issue-repairer-threads",
+    "config-issue-resolution",
+    "config-issue-temp-full",
+    "config-issues",
+    "config-restore-failed",
+    "config-restored",
+    "config-restored-description",
+    "copy-command",
+    "copy-report",
+    "create-backup",
+    "date-days-ago",
+    "date-hours-ago",
+    "date-just-now",
+    "date-minutes-ago",
+    "delete-installation-profile",
+    "desktop-shortcuts",
+    "desktop-shortcuts-description",
+    "desktop-shortcuts-update-failed",
+    "detected-installation",
+    "disabled",
+    "duration-days",
+    "duration-hours",
+    "duration-minutes",
+    "duration-seconds",
+    "files-not-moved",
+    "folder-migration-failed",
+    "folder-migration-interrupted",
+    "folder-migration-interrupted-description",
+    "folder-migration-rollback-failed",
+    "gamescope-fsr-description",
+    "import",
+    "import-installation",
+    "import-installation-description",
+    "import-integrity-broken",
+    "import-integrity-check",
+    "import-integrity-checking",
+    "import-integrity-passed",
+    "import-integrity-unavailable",
+    "import-move-files",
+    "import-move-files-description",
+    "import-target-folder",
+    "install-missing-packages",
+    "installation-detection-failed",
+    "installation-import-failed",
+    "installation-profile-remove-failed",
+    "installation-profile-save-failed",
+    "installation-profile-switch-failed",
+    "installation-profiles",
+    "installation-profiles-description",
+    "integer-scaling-description",
+    "launcher-files",
+    "launcher-files-description",
+    "launcher-restart-failed",
+    "migration-conflict-not-enough-space",
+    "migration-conflict-same-target",
+    "migration-conflict-target-inside-source",
+    "migration-conflict-target-not-empty",
+    "migration-copy",
+    "migration-path-only",
+    "migration-plan",
+    "migration-plan-description",
+    "migration-rename",
+    "migration-required-space",
+    "migration-skip",
+    "news-announcements",
+    "news-events",
+    "news-info",
+    "nis-description",
+    "not-enough-free-space",
+    "not-selected",
+    "packages-install-failed",
+    "pictures",
+    "post-launch-hook",
+    "pre-launch-hook",
+    "queue-updates",
+    "remedy-copied",
+    "remove-selected",
+    "report-copied",
+    "reset-config",
+    "restore",
+    "roll-back",
+    "rolling-back-migration",
+    "select-component",
+    "select-components",
+    "session-dxvk-not-downloaded",
+    "session-profile",
+    "session-profile-background",
+    "session-profile-description",
+    "session-profile-environment",
+    "session-profile-environment-description",
+    "session-profile-gamescope",
+    "session-profile-gamescope-description",
+    "session-profile-save-failed",
+    "session-shortcuts",
+    "session-wine-not-downloaded",
+    "shortcut-open-launcher",
+    "shortcut-play-session",
+    "shortcut-play-session-description",
+    "start-queue",
+    "steam-shortcut",
+    "steam-shortcut-add-failed",
+    "steam-shortcut-added",
+    "steam-shortcut-description",
+    "steam-shortcut-steam-running",
+    "storage",
+    "storage-description",
+    "storage-dxvk-builds",
+    "storage-game-folder",
+    "storage-plan",
+    "storage-plan-calculating",
+    "storage-plan-download-size",
+    "storage-plan-low",
+    "storage-plan-not-enough",
+    "storage-plan-space",
+    "storage-plan-unknown",
+    "storage-plan-unpacked-size",
+    "storage-temp-folder",
+    "storage-wine-builds",
+    "system-check-binary-available",
+    "system-check-binary-missing",
+    "system-check-esync",
+    "system-check-esync-remedy",
+    "system-check-free-space",
+    "system-check-free-space-remedy",
+    "system-check-fsync",
+    "system-check-glibc",
+    "system-check-kernel",
+    "system-check-max-map-count",
+    "system-check-update-system",
+    "system-check-vulkan-available",
+    "system-check-vulkan-missing",
+    "system-checks",
+    "telemetry-behavior",
+    "telemetry-behavior-description",
+    "unknown-size",
+    "upscaling",
+    "voice-operation-failed",
+    "voice-operation-install",
+    "voice-operation-planning-failed",
+    "voice-operation-remove",
+    "voice-operation-update",
+    "voice-queue",
+    "voice-queue-blocked",
+    "voice-queue-description",
+    "voice-queue-download-size",
+    "voice-queue-progress",
+    "voice-queued-install",
+    "voice-queued-remove",
+    "voice-queued-update"
+];
+
+/// Keys which are not used by the launcher anymore but still defined by some locales
+const UNUSED_KEYS: &[&str] = &[
+    "active-session-description",
+    "active-sessions",
+    "agree",
+    "applied",
+    "apply-main-patch",
+    "apply-main-patch-description",
+    "apply-mfplat-patch",
+    "apply-patch",
+    "ask-superuser-permissions",
+    "ask-superuser-permissions-description",
+    "default",
+    "disable-mhypbase",
+    "disable-mhypbase-description",
+    "download-patch",
+    "downloading-background-picture",
+    "game-patching-error",
+    "japan",
+    "japanese-game-installation-folder",
+    "korea",
+    "korean-game-installation-folder",
+    "loading-game-version",
+    "loading-launcher-state--patch",
+    "loading-patch-status",
+    "main-patch-update-failed",
+    "main-window--patch-outdated-tooltip",
+    "main-window--patch-unavailable-tooltip",
+    "mfplat-patch-version",
+    "mfplat-patch-version-description",
+    "not-applied",
+    "patch-broken",
+    "patch-broken-tooltip",
+    "patch-folder",
+    "patch-info-fetching-error",
+    "patch-not-applied-tooltip",
+    "patch-not-available",
+    "patch-not-available-tooltip",
+    "patch-outdated",
+    "patch-outdated-tooltip",
+    "patch-preparation",
+    "patch-preparation-tooltip",
+    "patch-state-check-failed",
+    "patch-sync-failed",
+    "patch-testing-tooltip",
+    "patch-unsafe",
+    "patch-unsafe-tooltip",
+    "player-patch-version",
+    "player-patch-version-description",
+    "popup",
+    "sea",
+    "sea-game-installation-folder",
+    "taiwan",
+    "taiwanese-game-installation-folder",
+    "tos-dialog-message",
+    "tos-dialog-title",
+    "tos-violation-warning",
+    "tos-violation-warning-message"
+];
+
+#[derive(Debug, Clone, Default)]
+struct Message {
+    /// Names of the `$variables` used by the message
+    variables: HashSet<String>,
+
+    /// Names of the other messages used by this one
+    references: HashSet<String>
+}
+
+fn locales_folder() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/locales")
+}
+
+/// Format message key with the sorted list of its arguments
+fn format_arguments(key: &str, arguments: HashSet<String>) -> String {
+    let mut arguments = arguments.into_iter().collect::<Vec<_>>();
+
+    arguments.sort();
+
+    format!("{key} ({})", arguments.join(", "))
+}
+
+/// Format sorted list of keys
+fn format_keys<'a>(keys: impl IntoIterator<Item = &'a String>) -> String {
+    let mut keys = keys.into_iter().map(String::as_str).collect::<Vec<_>>();
+
+    keys.sort();
+
+    keys.join(", ")
+}
+
+/// Read messages defined in the fluent file
+fn read_messages(path: &Path) -> HashMap<String, Message> {
+    let content = std::fs::read_to_string(path).expect("Failed to read fluent file");
+
+    let mut messages = HashMap::new();
+    let mut current: Option<(String, Message)> = None;
+
+    for line in content.lines() {
+        // Terms are private to the fluent files so they're not checked
+        if line.starts_with('-') {
+            if let Some((id, message)) = current.take() {
+                messages.insert(id, message);
+            }
+
+            continue;
+        }
+
+        // Message definition starts from the first column
+        if !line.starts_with(char::is_whitespace) && !line.starts_with('#') {
+            if let Some((id, value)) = line.split_once('=') {
+                if let Some((id, message)) = current.take() {
+                    messages.insert(id, message);
+                }
+
+                current = Some((id.trim().to_string(), Message::default()));
+
+                if let Some((_, message)) = &mut current {
+                    parse_placeables(value, message);
+                }
+
+                continue;
+            }
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            if let Some((_, message)) = &mut current {
+                parse_placeables(line, message);
+            }
+        }
+    }
+
+    if let Some((id, message)) = current {
+        messages.insert(id, message);
+    }
+
+    messages
+}
+
+/// Find variables and message references in `{ ... }` placeables
+fn parse_placeables(text: &str, message: &mut Message) {
+    for placeable in text.split('{').skip(1) {
+        let placeable = placeable.split('}').next().unwrap_or_default().trim();
+
+        if let Some(variable) = placeable.strip_prefix('$') {
+            let name = variable.split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+                .next()
+                .unwrap_or_default();
+
+            message.variables.insert(name.to_string());
+        }
+
+        else if placeable.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            message.references.insert(placeable.to_string());
+        }
+    }
+}
+
+/// Find keys and argument names of every `tr!` macro call with literal id
+fn scan_sources(path: &Path, used: &mut HashMap<String, Vec<HashSet<String>>>) {
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path).expect("Failed to read sources folder").flatten() {
+            scan_sources(&entry.path(), used);
+        }
+
+        return;
+    }
+
+    if path.extension().map(|ext| ext != "rs").unwrap_or(true) {
+        return;
+    }
+
+    let content = std::fs::read_to_string(path).expect("Failed to read source file");
+
+    for (i, _) in content.match_indices("tr!(\"") {
+        // Skip other macros ending with `tr!`, like `include_str!`
+        if content[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+
+        let Some((key, rest)) = content[i + 5..].split_once('"') else {
+            continue;
+        };
+
+        let mut args = HashSet::new();
+
+        // Arguments block: tr!("key", { "name" = value, ... })
+        let rest = rest.trim_start();
+
+        if let Some(block) = rest.strip_prefix(',').map(str::trim_start).and_then(|rest| rest.strip_prefix('{')) {
+            let mut depth = 1;
+
+            let end = block.find(|c| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => ()
+                }
+
+                depth == 0
+            }).unwrap_or(block.len());
+
+            let block = &block[..end];
+
+            let parts = block.split('"').collect::<Vec<_>>();
+
+            // Every argument name is a string literal followed by `=`
+            for pair in parts.windows(2).skip(1) {
+                if pair[1].trim_start().starts_with('=') {
+                    args.insert(pair[0].to_string());
+                }
+            }
+        }
+
+        used.entry(key.to_string()).or_default().push(args);
+    }
+}
+
+/// Every locale should define all the used keys except the untranslated ones,
+/// and messages should use only the arguments passed by the sources
+#[test]
+fn translations() {
+    let mut used = HashMap::new();
+
+    scan_sources(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut used);
+
+    let common = read_messages(&locales_folder().join("common.ftl"));
+
+    let mut locales = std::fs::read_dir(locales_folder())
+        .expect("Failed to read locales folder")
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+
+    locales.sort();
+
+    let mut report = Vec::new();
+
+    // Variables used by the messages in any locale
+    let mut variables = HashMap::<String, HashSet<String>>::new();
+
+    // Allowed keys which were actually missing or unused in any locale
+    let mut untranslated = HashSet::new();
+    let mut unused_defined = HashSet::new();
+
+    for locale in &locales {
+        let mut messages = common.clone();
+
+        let mut files = std::fs::read_dir(locales_folder().join(locale))
+            .expect("Failed to read locale folder")
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().map(|ext| ext == "ftl").unwrap_or(false))
+            .collect::<Vec<_>>();
+
+        files.sort();
+
+        let mut defined = HashMap::new();
+
+        for file in files {
+            defined.extend(read_messages(&file));
+        }
+
+        // Keys referenced by other messages are used as well
+        let referenced = defined.values()
+            .flat_map(|message: &Message| message.references.iter().cloned())
+            .collect::<HashSet<_>>();
+
+        messages.extend(defined.clone());
+
+        let (allowed, missing): (Vec<_>, Vec<_>) = used.keys()
+            .filter(|key| !messages.contains_key(*key))
+            .partition(|key| locale != REFERENCE_LOCALE && UNTRANSLATED_KEYS.contains(&key.as_str()));
+
+        untranslated.extend(allowed.into_iter().cloned());
+
+        let (allowed, unused): (Vec<_>, Vec<_>) = defined.keys()
+            .filter(|key| !used.contains_key(*key) && !referenced.contains(*key) && !DYNAMIC_KEYS.contains(&key.as_str()))
+            .partition(|key| UNUSED_KEYS.contains(&key.as_str()));
+
+        unused_defined.extend(allowed.into_iter().cloned());
+
+        let mut not_passed = Vec::new();
+
+        for (key, calls) in &used {
+            if let Some(message) = messages.get(key) {
+                variables.entry(key.clone())
+                    .or_default()
+                    .extend(message.variables.iter().cloned());
+            }
+
+            let Some(message) = defined.get(key) else {
+                continue;
+            };
+
+            // Every call is checked separately so one passing the arguments doesn't hide another which doesn't
+            let unknown = calls.iter()
+                .flat_map(|args| message.variables.difference(args).cloned())
+                .collect::<HashSet<_>>();
+
+            if !unknown.is_empty() {
+                not_passed.push(format_arguments(key, unknown));
+            }
+        }
+
+        not_passed.sort();
+
+        if !missing.is_empty() {
+            report.push(format!("{locale}: {} missing keys: {}", missing.len(), format_keys(missing)));
+        }
+
+        if !unused.is_empty() {
+            report.push(format!("{locale}: {} unused keys: {}", unused.len(), format_keys(unused)));
+        }
+
+        if !not_passed.is_empty() {
+            report.push(format!("{locale}: arguments not passed by the sources: {}", not_passed.join(", ")));
+        }
+    }
+
+    // Some arguments are used only by a part of the locales, e.g. to choose grammatical form,
+    // so only ones not used by any locale are reported
+    let mut not_used = used.iter()
+        .filter_map(|(key, calls)| {
+            let variables = variables.get(key)?;
+
+            let extra = calls.iter()
+                .flat_map(|args| args.difference(variables).cloned())
+                .collect::<HashSet<_>>();
+
+            (!extra.is_empty()).then(|| format_arguments(key, extra))
+        })
+        .collect::<Vec<_>>();
+
+    not_used.sort();
+
+    if !not_used.is_empty() {
+        report.push(format!("arguments not used by the messages: {}", not_used.join(", ")));
+    }
+
+    // Keep the allowed lists up to date
+    let stale = UNTRANSLATED_KEYS.iter()
+        .filter(|key| !untranslated.contains(**key))
+        .map(|key| key.to_string())
+        .collect::<Vec<_>>();
+
+    if !stale.is_empty() {
+        report.push(format!("translated or not used keys in UNTRANSLATED_KEYS: {}", format_keys(&stale)));
+    }
+
+    let stale = UNUSED_KEYS.iter()
+        .filter(|key| !unused_defined.contains(**key))
+        .map(|key| key.to_string())
+        .collect::<Vec<_>>();
+
+    if !stale.is_empty() {
+        report.push(format!("used or removed keys in UNUSED_KEYS: {}", format_keys(&stale)));
+    }
+
+    assert!(report.is_empty(), "Translations check failed:\n{}", report.join("\n"));
+}